@@ -31,7 +31,8 @@
 //! The [`encode_into`] and [`decode_into`] functions
 //! automatically dispatch to SIMD-accelerated paths (AVX2 on x86/x86_64,
 //! NEON on aarch64). When a constant-time guarantee is required, use
-//! [`encode_into_constant_time`] or [`decode_into_constant_time`].
+//! [`encode_into_constant_time`], [`decode_into_constant_time`] or
+//! [`decode_constant_time`].
 //!
 //! # `const fn` support
 //!
@@ -79,6 +80,34 @@ pub enum Alphabet {
 }
 
 impl Alphabet {
+    /// Returns the alphabet with the given name, or `None` if the name is unknown, e.g. to read the
+    /// alphabet from a configuration file.
+    ///
+    /// The names are `crockford`, `rfc4648`, `rfc4648-lower`, `rfc4648-hex` and
+    /// `rfc4648-hex-lower`. The RFC 4648 alphabets are padded unless the name ends with `-nopad`,
+    /// e.g. `rfc4648-lower-nopad`. Names are case-sensitive.
+    ///
+    /// ```rust
+    /// use base32::Alphabet;
+    /// assert_eq!(Alphabet::from_name("rfc4648-nopad"), Some(Alphabet::Rfc4648NoPadding));
+    /// assert_eq!(Alphabet::from_name("base64"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Alphabet> {
+        let alphabet = match name {
+            "crockford" => Alphabet::Crockford,
+            "rfc4648" => Alphabet::Rfc4648,
+            "rfc4648-nopad" => Alphabet::Rfc4648NoPadding,
+            "rfc4648-lower" => Alphabet::Rfc4648Lower,
+            "rfc4648-lower-nopad" => Alphabet::Rfc4648LowerNoPadding,
+            "rfc4648-hex" => Alphabet::Rfc4648Hex,
+            "rfc4648-hex-nopad" => Alphabet::Rfc4648HexNoPadding,
+            "rfc4648-hex-lower" => Alphabet::Rfc4648HexLower,
+            "rfc4648-hex-lower-nopad" => Alphabet::Rfc4648HexLowerNoPadding,
+            _ => return None,
+        };
+        Some(alphabet)
+    }
+
     #[inline]
     const fn is_padded(&self) -> bool {
         match self {
@@ -98,6 +127,7 @@ impl Alphabet {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EncodeError {
     InvalidOutputLength,
+    InvalidPadding,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -111,6 +141,7 @@ impl core::fmt::Display for EncodeError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidOutputLength => f.write_str("output buffer size is not valid"),
+            Self::InvalidPadding => f.write_str("invalid base32 padding character"),
         }
     }
 }
@@ -369,31 +400,185 @@ pub const fn encode_into_constant_time(output: &mut [u8], data: &[u8], alphabet:
 
 /// Appends the base32-encoded representation of `data` to a [`String`].
 ///
+/// The data is encoded directly into the string, which only allocates if its capacity is too
+/// small. To encode many values without allocating, clear and reuse the same string, see
+/// [`encoded_length`] to reserve its capacity.
+///
 /// # Example
 ///
 /// ```rust
 /// let mut s = String::from("tag: ");
 /// base32::encode_into_string(&mut s, b"hello", base32::Alphabet::Rfc4648);
 /// assert_eq!(s, "tag: NBSWY3DP");
+///
+/// s.clear();
+/// base32::encode_into_string(&mut s, b"foo", base32::Alphabet::Rfc4648);
+/// assert_eq!(s, "MZXW6===");
 /// ```
 #[cfg(feature = "alloc")]
 pub fn encode_into_string(output: &mut alloc::string::String, data: &[u8], alphabet: Alphabet) {
     let encoded_length = encoded_length(data.len(), alphabet.is_padded()).expect("output length overflow");
-    if encoded_length <= 256 {
-        // zero-alloc version for small data
-        let mut buf = [0u8; 256];
-        let mut buf = &mut buf[..encoded_length];
-        encode_into(&mut buf, data, alphabet).unwrap();
-        // SAFETY: base64 only produces ASCII characters, which are valid UTF-8.
-        output.push_str(unsafe { core::str::from_utf8_unchecked(&buf) });
-    } else {
-        let mut buf = alloc::vec![0u8; encoded_length];
-        encode_into(&mut buf, data, alphabet).unwrap();
-        // SAFETY: base64 only produces ASCII characters, which are valid UTF-8.
-        output.push_str(unsafe { core::str::from_utf8_unchecked(&buf) });
+    let start = output.len();
+    let end = start.checked_add(encoded_length).expect("output length overflow");
+
+    // SAFETY: the string is extended with NUL bytes, which are valid UTF-8, and they are then
+    // overwritten with the encoded data, which is ASCII.
+    let output = unsafe { output.as_mut_vec() };
+    output.resize(end, 0);
+    encode_into(&mut output[start..], data, alphabet).expect("output buffer sized correctly");
+}
+
+/// Number of bytes encoded at once by [`Encoder`]: a multiple of 5, so that groups of 5 bytes are
+/// never split, and large enough to use the SIMD implementations.
+#[cfg(feature = "std")]
+const ENCODER_CHUNK_SIZE: usize = 5 * 128;
+
+/// A writer that encodes the bytes written to it to base32 and writes them to `W`, to encode
+/// large data without buffering it entirely.
+///
+/// The bytes are encoded by groups of 5, the last incomplete group being kept until more bytes are
+/// written or [`Encoder::finish`] is called. `finish` must be called to write the end of the
+/// encoded data, which is lost if the encoder is dropped instead.
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Write;
+///
+/// let mut encoder = base32::Encoder::new(Vec::new(), base32::Alphabet::Rfc4648);
+/// encoder.write_all(b"foo").unwrap();
+/// encoder.write_all(b"bar").unwrap();
+/// let encoded = encoder.finish().unwrap();
+/// assert_eq!(encoded, b"MZXW6YTBOI======");
+/// ```
+#[cfg(feature = "std")]
+pub struct Encoder<W: std::io::Write> {
+    writer: W,
+    alphabet: Alphabet,
+    pending: [u8; 5],
+    pending_len: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> Encoder<W> {
+    /// Returns an encoder that writes the data encoded with `alphabet` to `writer`.
+    pub fn new(writer: W, alphabet: Alphabet) -> Self {
+        Encoder {
+            writer,
+            alphabet,
+            pending: [0u8; 5],
+            pending_len: 0,
+        }
+    }
+
+    /// Writes the remaining bytes, padded if required by the alphabet, and returns the underlying
+    /// writer.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        let mut buf = [0u8; 8];
+        let data = &self.pending[..self.pending_len];
+        let len = encoded_length(data.len(), self.alphabet.is_padded()).expect("encoded length overflow");
+        encode_into(&mut buf, data, self.alphabet).expect("output buffer sized correctly");
+        self.writer.write_all(&buf[..len])?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Encodes `data`, whose length must be a multiple of 5, and writes it.
+    fn write_groups(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let mut buf = [0u8; ENCODER_CHUNK_SIZE / 5 * 8];
+        for chunk in data.chunks(ENCODER_CHUNK_SIZE) {
+            let len = chunk.len() / 5 * 8;
+            encode_into(&mut buf[..len], chunk, self.alphabet).expect("output buffer sized correctly");
+            self.writer.write_all(&buf[..len])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> std::io::Write for Encoder<W> {
+    fn write(&mut self, mut data: &[u8]) -> std::io::Result<usize> {
+        let written = data.len();
+
+        // complete the pending group first
+        if self.pending_len > 0 {
+            let len = (5 - self.pending_len).min(data.len());
+            self.pending[self.pending_len..self.pending_len + len].copy_from_slice(&data[..len]);
+            self.pending_len += len;
+            data = &data[len..];
+            if self.pending_len < 5 {
+                return Ok(written);
+            }
+            let group = self.pending;
+            self.write_groups(&group)?;
+            self.pending_len = 0;
+        }
+
+        let groups_len = data.len() - data.len() % 5;
+        self.write_groups(&data[..groups_len])?;
+
+        let rest = &data[groups_len..];
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+        Ok(written)
+    }
+
+    /// Flushes the underlying writer. The bytes of an incomplete group are only written by
+    /// [`Encoder::finish`].
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
     }
 }
 
+/// Encodes bytes to a base32 string using the given [`Alphabet`], padded with `pad` instead of
+/// `=`.
+///
+/// The output is always padded to a multiple of 8 characters, even if the alphabet is not padded
+/// (e.g. [`Alphabet::Crockford`]). Use [`encode`] for unpadded output.
+///
+/// # Errors
+///
+/// Returns [`EncodeError::InvalidPadding`] if `pad` is not ASCII or is a character of the
+/// alphabet.
+///
+/// # Example
+///
+/// ```rust
+/// let encoded = base32::encode_with_padding(b"foo", base32::Alphabet::Crockford, b'#').unwrap();
+/// assert_eq!(encoded, "CSQPY###");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_with_padding(
+    data: impl AsRef<[u8]>,
+    alphabet: Alphabet,
+    pad: u8,
+) -> Result<alloc::string::String, EncodeError> {
+    if !is_valid_padding(pad, alphabet) {
+        return Err(EncodeError::InvalidPadding);
+    }
+
+    let data = data.as_ref();
+    let len = encoded_length(data.len(), true).expect("encoded length overflow");
+    let mut output = alloc::vec![pad; len];
+    encode_into(&mut output, data, alphabet).expect("output buffer sized correctly");
+    // padded alphabets write `=`, which is never part of the encoded data
+    for b in &mut output[len.saturating_sub(6)..] {
+        if *b == PAD {
+            *b = pad;
+        }
+    }
+    // SAFETY: base32 only produces ASCII characters, and `pad` is ASCII.
+    Ok(unsafe { alloc::string::String::from_utf8_unchecked(output) })
+}
+
+/// Returns true if `pad` can be used as padding character with the given alphabet: it must be
+/// ASCII and must not collide with a character of the alphabet.
+#[cfg(feature = "alloc")]
+#[inline]
+const fn is_valid_padding(pad: u8, alphabet: Alphabet) -> bool {
+    pad.is_ascii() && char_to_quintet(pad, alphabet) >= 32
+}
+
 /// Returns 0x00 if lo <= v <= hi, 0xFF otherwise.
 /// Uses sign-bit propagation for branchless range checking.
 #[inline]
@@ -511,12 +696,33 @@ const fn decoded_length(encoded_content_len: usize) -> Result<usize, DecodeError
     }
 }
 
+/// Returns the maximum size in bytes of the data decoded from `encoded_len` base32 characters,
+/// e.g. to reserve the capacity of the buffer given to [`decode_into_vec`].
+///
+/// The actual size is smaller if the encoded data is padded.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(base32::decoded_length_upper_bound(8), 5);
+/// assert_eq!(base32::decoded_length_upper_bound(7), 4);
+/// ```
+pub const fn decoded_length_upper_bound(encoded_len: usize) -> usize {
+    (encoded_len / 8) * 5 + (encoded_len % 8) * 5 / 8
+}
+
 /// Decodes a base32 string into bytes.
 ///
+/// With a padded [`Alphabet`], the padding is strictly validated as in RFC 4648: `=` may only
+/// appear at the end of the input, as a run of 1, 3, 4 or 6 characters that completes the last
+/// block of 8 characters.
+///
 /// # Errors
 ///
 /// Returns [`DecodeError`] if any character is invalid for the chosen
-/// [`Alphabet`], the input length is not valid, or padding is incorrect.
+/// [`Alphabet`], the input length is not valid, or padding is incorrect:
+/// [`DecodeError::InvalidInput`] for `=` before the end of the input, and
+/// [`DecodeError::InvalidPadding`] for a trailing run of `=` of the wrong length.
 ///
 /// # Example
 ///
@@ -535,6 +741,132 @@ pub fn decode(data: impl AsRef<[u8]>, alphabet: Alphabet) -> Result<alloc::vec::
     Ok(output)
 }
 
+/// Decodes a base32 string into `output`, replacing its contents, e.g. to decode many values
+/// with the same buffer. `output` only allocates if its capacity is too small, see
+/// [`decoded_length_upper_bound`].
+///
+/// # Errors
+///
+/// Returns the same [`DecodeError`] as [`decode`], in which case `output` is left empty.
+///
+/// # Example
+///
+/// ```rust
+/// let mut buf = b"previous".to_vec();
+/// base32::decode_into_vec(&mut buf, b"NBSWY3DP", base32::Alphabet::Rfc4648).unwrap();
+/// assert_eq!(buf, b"hello");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_into_vec(
+    output: &mut alloc::vec::Vec<u8>,
+    data: impl AsRef<[u8]>,
+    alphabet: Alphabet,
+) -> Result<(), DecodeError> {
+    output.clear();
+
+    let data = data.as_ref();
+    let (content_len, _) = strip_padding_info(data, alphabet.is_padded())?;
+    output.resize(decoded_length(content_len)?, 0);
+    decode_into(output, data, alphabet).inspect_err(|_| output.clear())
+}
+
+/// Decodes a base32 string into bytes in constant time, like [`decode_into_constant_time`].
+///
+/// Use it instead of [`decode`] for secret material, e.g. the base32 keys of TOTP. Whatever the
+/// alphabet, characters are mapped to their value without secret-dependent branches or table
+/// lookups, and an invalid character doesn't stop the decoding early: the whole input is
+/// processed before the error is returned, so the time taken doesn't reveal where the first
+/// invalid character is. Only the length of the input and its padding, which are not secret, are
+/// checked with early returns.
+///
+/// # Errors
+///
+/// Returns the same [`DecodeError`] as [`decode`].
+///
+/// # Example
+///
+/// ```rust
+/// let key = base32::decode_constant_time(b"JBSWY3DPEHPK3PXP", base32::Alphabet::Rfc4648).unwrap();
+/// assert_eq!(key, b"Hello!\xde\xad\xbe\xef");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_constant_time(data: impl AsRef<[u8]>, alphabet: Alphabet) -> Result<alloc::vec::Vec<u8>, DecodeError> {
+    let data = data.as_ref();
+    let padding = alphabet.is_padded();
+    let (content_len, _) = strip_padding_info(data, padding)?;
+    let output_len = decoded_length(content_len)?;
+    let mut output = alloc::vec![0u8; output_len];
+    decode_into_constant_time(&mut output, data, alphabet)?;
+    Ok(output)
+}
+
+/// Decodes a base32 string into bytes, ignoring ASCII whitespace (spaces, tabs, `\r`, `\n` and
+/// form feeds) anywhere in the input, e.g. when the encoded data is split across several lines.
+///
+/// Unlike [`decode`], whitespace is not rejected, but other characters are validated the same
+/// way. Padding, if required by the alphabet, must still be at the end of the data.
+///
+/// # Errors
+///
+/// Returns [`DecodeError`] if any non-whitespace character is invalid for the chosen
+/// [`Alphabet`], the input length without whitespace is not valid, or padding is incorrect.
+///
+/// # Example
+///
+/// ```rust
+/// let decoded = base32::decode_ignore_whitespace(b"MZXW6\r\nYTBOI\n======", base32::Alphabet::Rfc4648).unwrap();
+/// assert_eq!(decoded, b"foobar");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_ignore_whitespace(
+    data: impl AsRef<[u8]>,
+    alphabet: Alphabet,
+) -> Result<alloc::vec::Vec<u8>, DecodeError> {
+    let data = data.as_ref();
+    if !data.iter().any(u8::is_ascii_whitespace) {
+        return decode(data, alphabet);
+    }
+
+    let data: alloc::vec::Vec<u8> = data.iter().copied().filter(|b| !b.is_ascii_whitespace()).collect();
+    decode(data, alphabet)
+}
+
+/// Decodes a base32 string padded with `pad` instead of `=`. See [`encode_with_padding`].
+///
+/// Padding is optional, but if present, it must be made of `pad` characters and complete the
+/// input to a multiple of 8 characters.
+///
+/// # Errors
+///
+/// Returns [`DecodeError::InvalidPadding`] if `pad` is not ASCII or is a character of the
+/// alphabet, or if the padding is incorrect, and the same errors as [`decode`] otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// let decoded = base32::decode_with_padding(b"CSQPY###", base32::Alphabet::Crockford, b'#').unwrap();
+/// assert_eq!(decoded, b"foo");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_with_padding(
+    data: impl AsRef<[u8]>,
+    alphabet: Alphabet,
+    pad: u8,
+) -> Result<alloc::vec::Vec<u8>, DecodeError> {
+    if !is_valid_padding(pad, alphabet) {
+        return Err(DecodeError::InvalidPadding);
+    }
+
+    let data = data.as_ref();
+    let (content_len, _) = strip_padding_info_with(data, true, pad)?;
+    let content = &data[..content_len];
+    // with a custom padding character, `=` is invalid, even at the end of the data
+    if content.last() == Some(&PAD) {
+        return Err(DecodeError::InvalidPadding);
+    }
+    decode(content, alphabet)
+}
+
 /// Decodes a base32 string into a fixed-size array at compile time.
 ///
 /// The generic parameter `OUT` is the output array length. It must be exactly
@@ -598,6 +930,143 @@ pub fn decode_into(output: &mut [u8], encoded_data: &[u8], alphabet: Alphabet) -
     decode_into_constant_time(output, encoded_data, alphabet)
 }
 
+/// Number of encoded characters decoded at once by [`Decoder`]: a multiple of 8, so that groups of
+/// 8 characters are never split, and large enough to use the SIMD implementations.
+#[cfg(feature = "std")]
+const DECODER_CHUNK_SIZE: usize = 8 * 128;
+
+/// A reader that decodes the base32 data read from `R`, to decode large data without buffering it
+/// entirely.
+///
+/// The characters are validated as soon as they are read: an invalid character fails the next
+/// call to `read`, even if its group of 8 characters is incomplete. The padding, if required by
+/// the alphabet, is validated at the end of the data, as by [`decode`]. Errors are
+/// [`std::io::ErrorKind::InvalidData`] errors wrapping a [`DecodeError`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::io::Read;
+///
+/// let mut decoder = base32::Decoder::new(&b"MZXW6YTBOI======"[..], base32::Alphabet::Rfc4648);
+/// let mut decoded = Vec::new();
+/// decoder.read_to_end(&mut decoded).unwrap();
+/// assert_eq!(decoded, b"foobar");
+/// ```
+#[cfg(feature = "std")]
+pub struct Decoder<R: std::io::Read> {
+    reader: R,
+    alphabet: Alphabet,
+    // characters read but not decoded yet
+    input: [u8; DECODER_CHUNK_SIZE],
+    input_len: usize,
+    // bytes decoded but not returned yet
+    output: [u8; DECODER_CHUNK_SIZE / 8 * 5],
+    output_start: usize,
+    output_end: usize,
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Decoder<R> {
+    /// Returns a decoder that reads the data encoded with `alphabet` from `reader`.
+    pub fn new(reader: R, alphabet: Alphabet) -> Self {
+        Decoder {
+            reader,
+            alphabet,
+            input: [0u8; DECODER_CHUNK_SIZE],
+            input_len: 0,
+            output: [0u8; DECODER_CHUNK_SIZE / 8 * 5],
+            output_start: 0,
+            output_end: 0,
+            eof: false,
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads more characters from the underlying reader, and decodes the complete groups.
+    fn fill(&mut self) -> std::io::Result<()> {
+        let read = loop {
+            match self.reader.read(&mut self.input[self.input_len..]) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        };
+
+        let new = &self.input[self.input_len..self.input_len + read];
+        if new.iter().any(|&c| c != PAD && char_to_quintet(c, self.alphabet) >= 32) {
+            return Err(decode_error(DecodeError::InvalidInput));
+        }
+        self.input_len += read;
+        let input = &self.input[..self.input_len];
+
+        if read == 0 {
+            // the last group, which may be incomplete or padded
+            self.eof = true;
+            let (content_len, _) = strip_padding_info(input, self.alphabet.is_padded()).map_err(decode_error)?;
+            let len = decoded_length(content_len).map_err(decode_error)?;
+            decode_into(&mut self.output[..len], input, self.alphabet).map_err(decode_error)?;
+            self.input_len = 0;
+            self.output_start = 0;
+            self.output_end = len;
+            return Ok(());
+        }
+
+        let mut groups_len = input.len() - input.len() % 8;
+        // padding can only end the data, so its group is kept until the end of the input
+        if let Some(pad) = input.iter().position(|&c| c == PAD) {
+            if input[pad..].iter().any(|&c| c != PAD) {
+                return Err(decode_error(DecodeError::InvalidInput));
+            }
+            groups_len = groups_len.min(pad - pad % 8);
+            // the groups before the padding are decoded to make room for the rest of the input,
+            // so a full buffer that starts with the padded group has too much padding
+            if groups_len == 0 && input.len() == self.input.len() {
+                return Err(decode_error(DecodeError::InvalidPadding));
+            }
+        }
+
+        let len = groups_len / 8 * 5;
+        decode_into(&mut self.output[..len], &input[..groups_len], self.alphabet).map_err(decode_error)?;
+        self.input.copy_within(groups_len..self.input_len, 0);
+        self.input_len -= groups_len;
+        self.output_start = 0;
+        self.output_end = len;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::Read for Decoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        while self.output_start == self.output_end {
+            if self.eof {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+
+        let len = buf.len().min(self.output_end - self.output_start);
+        buf[..len].copy_from_slice(&self.output[self.output_start..self.output_start + len]);
+        self.output_start += len;
+        Ok(len)
+    }
+}
+
+#[cfg(feature = "std")]
+fn decode_error(err: DecodeError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+}
+
 /// Constant-time base32 decoding. Processes all input data without
 /// secret-dependent branches or memory accesses, making it suitable
 /// for cryptographic applications.
@@ -758,6 +1227,11 @@ const fn decode_8quads(output: &mut [u8], alphabet: Alphabet, data: &[u8], i: &m
 
 #[inline]
 const fn strip_padding_info(data: &[u8], expect_padding: bool) -> Result<(usize, usize), DecodeError> {
+    strip_padding_info_with(data, expect_padding, PAD)
+}
+
+#[inline]
+const fn strip_padding_info_with(data: &[u8], expect_padding: bool, pad: u8) -> Result<(usize, usize), DecodeError> {
     let in_len = data.len();
 
     if expect_padding {
@@ -765,7 +1239,7 @@ const fn strip_padding_info(data: &[u8], expect_padding: bool) -> Result<(usize,
             return Ok((0, 0));
         }
 
-        let count = count_trailing_padding(data);
+        let count = count_trailing_padding(data, pad);
         let content_len = in_len - count;
 
         let err = (count > 0 && in_len % 8 != 0)
@@ -785,17 +1259,17 @@ const fn strip_padding_info(data: &[u8], expect_padding: bool) -> Result<(usize,
 
         Ok((content_len, count))
     } else {
-        if in_len > 0 && data[in_len - 1] == PAD {
+        if in_len > 0 && data[in_len - 1] == pad {
             return Err(DecodeError::InvalidPadding);
         }
         Ok((in_len, 0))
     }
 }
 
-/// Count trailing `=` padding characters in constant time.
+/// Count trailing `pad` padding characters in constant time.
 /// Scans at most 7 bytes from the end (max valid padding is 6).
 /// The loop always runs exactly `min(len, 7)` iterations.
-const fn count_trailing_padding(data: &[u8]) -> usize {
+const fn count_trailing_padding(data: &[u8], pad: u8) -> usize {
     let len = data.len();
     if len == 0 {
         return 0;
@@ -807,7 +1281,7 @@ const fn count_trailing_padding(data: &[u8]) -> usize {
     let mut k = 0;
     while k < max_check {
         let idx = len - 1 - k;
-        let is_pad = if data[idx] == PAD { 0xFFu8 } else { 0x00u8 };
+        let is_pad = if data[idx] == pad { 0xFFu8 } else { 0x00u8 };
         all_pad = all_pad & is_pad;
         let all_pad_ext = (all_pad as i8 >> 7) as usize;
         count = ((k + 1) as usize) & all_pad_ext | count & !all_pad_ext;
@@ -1041,6 +1515,72 @@ mod tests {
         assert_eq!(decode(&data, Alphabet::Rfc4648), Err(DecodeError::InvalidInput));
     }
 
+    #[test]
+    fn test_decode_constant_time() {
+        for &(encoded, alphabet, expected, desc) in DECODE_VECTORS {
+            let result = decode_constant_time(encoded, alphabet).unwrap();
+            assert_eq!(&result, expected, "decode_constant_time: {desc}");
+        }
+        for &len in ROUNDTRIP_SIZES.iter().chain(SIMD_BOUNDARY_SIZES) {
+            let data: Vec<u8> = (0..len as u8).collect();
+            for alphabet in ALL_ALPHABETS {
+                let encoded = encode(&data, *alphabet);
+                assert_eq!(
+                    decode_constant_time(&encoded, *alphabet),
+                    decode(&encoded, *alphabet),
+                    "len={len} alphabet={alphabet:?}"
+                );
+            }
+        }
+
+        for &(encoded, alphabet, expected_err, desc) in DECODE_ERROR_VECTORS {
+            let result = decode_constant_time(encoded, alphabet);
+            assert_eq!(result, Err(expected_err), "decode_constant_time error: {desc}");
+        }
+        // the error is the same wherever the invalid character is
+        for pos in 0..80 {
+            let mut input = [b'A'; 80];
+            input[pos] = b'1';
+            assert_eq!(
+                decode_constant_time(input, Alphabet::Rfc4648),
+                Err(DecodeError::InvalidInput),
+                "invalid char at position {pos}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_alphabet_from_name() {
+        let names = [
+            ("crockford", Alphabet::Crockford),
+            ("rfc4648", Alphabet::Rfc4648),
+            ("rfc4648-nopad", Alphabet::Rfc4648NoPadding),
+            ("rfc4648-lower", Alphabet::Rfc4648Lower),
+            ("rfc4648-lower-nopad", Alphabet::Rfc4648LowerNoPadding),
+            ("rfc4648-hex", Alphabet::Rfc4648Hex),
+            ("rfc4648-hex-nopad", Alphabet::Rfc4648HexNoPadding),
+            ("rfc4648-hex-lower", Alphabet::Rfc4648HexLower),
+            ("rfc4648-hex-lower-nopad", Alphabet::Rfc4648HexLowerNoPadding),
+        ];
+        for (name, alphabet) in names {
+            assert_eq!(Alphabet::from_name(name), Some(alphabet), "{name}");
+            assert_eq!(alphabet.is_padded(), !name.ends_with("-nopad") && name != "crockford", "{name}");
+        }
+
+        for name in [
+            "",
+            "z",
+            "base32",
+            "RFC4648",
+            "rfc4648-",
+            "crockford-nopad",
+            "rfc4648-nopad-lower",
+            " rfc4648",
+        ] {
+            assert_eq!(Alphabet::from_name(name), None, "{name:?}");
+        }
+    }
+
     #[test]
     fn test_encoded_length() {
         for &(data_len, padding, expected, desc) in ENCODED_LENGTH_VECTORS {
@@ -1073,6 +1613,168 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_into_string_reuse() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let capacity = encoded_length(data.len(), true).unwrap();
+        let mut s = alloc::string::String::with_capacity(capacity);
+        let ptr = s.as_ptr();
+
+        for alphabet in ALL_ALPHABETS {
+            for len in [0, 1, 4, 5, 39, 40, 41, 255, 256, 257, 1000] {
+                s.clear();
+                encode_into_string(&mut s, &data[..len], *alphabet);
+                assert_eq!(s, encode(&data[..len], *alphabet), "len={len} alphabet={alphabet:?}");
+            }
+        }
+
+        // the string was large enough for all the inputs and was never reallocated
+        assert_eq!(s.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn test_decode_into_vec() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+
+        for alphabet in ALL_ALPHABETS {
+            let mut preallocated = Vec::with_capacity(decoded_length_upper_bound(
+                encoded_length(data.len(), alphabet.is_padded()).unwrap(),
+            ));
+            let ptr = preallocated.as_ptr();
+
+            for len in [0, 1, 4, 5, 39, 40, 41, 255, 256, 257, 1000] {
+                let encoded = encode(&data[..len], *alphabet);
+                assert!(
+                    decoded_length_upper_bound(encoded.len()) >= len,
+                    "len={len} alphabet={alphabet:?}"
+                );
+
+                decode_into_vec(&mut preallocated, &encoded, *alphabet).unwrap();
+                assert_eq!(preallocated, &data[..len], "len={len} alphabet={alphabet:?}");
+
+                // the previous contents are replaced
+                let mut buf = b"previous contents".to_vec();
+                decode_into_vec(&mut buf, &encoded, *alphabet).unwrap();
+                assert_eq!(buf, &data[..len], "len={len} alphabet={alphabet:?}");
+            }
+
+            // the buffer was large enough for all the inputs and was never reallocated
+            assert_eq!(preallocated.as_ptr(), ptr, "alphabet={alphabet:?}");
+
+            // invalid inputs are rejected and leave the buffer empty
+            let mut buf = b"previous contents".to_vec();
+            assert_eq!(decode_into_vec(&mut buf, "NBSWY3D!", *alphabet), Err(DecodeError::InvalidInput));
+            assert!(buf.is_empty());
+            assert_eq!(
+                decode_into_vec(&mut buf, "NBSWY3\u{e9}", *alphabet),
+                Err(DecodeError::InvalidInput)
+            );
+            assert!(buf.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_encoder() {
+        use std::io::Write;
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(3000).collect();
+
+        for alphabet in ALL_ALPHABETS {
+            for chunk_size in [1, 2, 3, 4, 6, 7, 13, 39, 41, 64, 641, 3000] {
+                for len in [0, 1, 4, 5, 6, 636, 637, 1000, 1276, 3000] {
+                    let mut encoder = Encoder::new(Vec::new(), *alphabet);
+                    for chunk in data[..len].chunks(chunk_size) {
+                        encoder.write_all(chunk).unwrap();
+                    }
+                    let encoded = encoder.finish().unwrap();
+                    assert_eq!(
+                        encoded,
+                        encode(&data[..len], *alphabet).as_bytes(),
+                        "len={len} chunk_size={chunk_size} alphabet={alphabet:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// A reader that returns the given chunks, one per call to `read`.
+    struct ChunkedReader<'a> {
+        chunks: std::collections::VecDeque<&'a [u8]>,
+    }
+
+    impl std::io::Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let Some(chunk) = self.chunks.pop_front() else {
+                return Ok(0);
+            };
+            let len = chunk.len().min(buf.len());
+            buf[..len].copy_from_slice(&chunk[..len]);
+            if len < chunk.len() {
+                self.chunks.push_front(&chunk[len..]);
+            }
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_decoder() {
+        use std::io::Read;
+
+        let data: Vec<u8> = (0..=255u8).cycle().take(3000).collect();
+
+        for alphabet in ALL_ALPHABETS {
+            for chunk_size in [1, 2, 3, 7, 8, 9, 13, 64, 1025, 5000] {
+                for len in [0, 1, 4, 5, 6, 636, 637, 1000, 1276, 3000] {
+                    let encoded = encode(&data[..len], *alphabet);
+                    let reader = ChunkedReader {
+                        chunks: encoded.as_bytes().chunks(chunk_size).collect(),
+                    };
+                    let mut decoded = Vec::new();
+                    Decoder::new(reader, *alphabet).read_to_end(&mut decoded).unwrap();
+                    assert_eq!(decoded, &data[..len], "len={len} chunk_size={chunk_size} alphabet={alphabet:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_decoder_errors() {
+        use std::io::Read;
+
+        fn decode_error(err: std::io::Error) -> DecodeError {
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            *err.get_ref().unwrap().downcast_ref::<DecodeError>().unwrap()
+        }
+
+        // the invalid character is reported as soon as its chunk is read, before the end of the
+        // input and even if its group is incomplete
+        let chunks = [&b"MZXW6YTB"[..], b"MZX!", b"W6YTB", b"MZXW6YTB"];
+        let mut decoder = Decoder::new(ChunkedReader { chunks: chunks.into() }, Alphabet::Rfc4648);
+        let mut buf = [0u8; 16];
+        assert_eq!(decoder.read(&mut buf).unwrap(), 5);
+        assert_eq!(&buf[..5], b"fooba");
+        assert_eq!(decode_error(decoder.read(&mut buf).unwrap_err()), DecodeError::InvalidInput);
+        assert_eq!(decoder.into_inner().chunks.len(), 2);
+
+        // data after the padding
+        let chunks = [&b"MZXW6==="[..], b"MZXW6YTB"];
+        let mut decoder = Decoder::new(ChunkedReader { chunks: chunks.into() }, Alphabet::Rfc4648);
+        let err = decoder.read_to_end(&mut Vec::new()).unwrap_err();
+        assert_eq!(decode_error(err), DecodeError::InvalidInput);
+
+        // the padding and the length are validated at the end of the input, as by `decode`
+        for (input, alphabet) in [
+            (&b"MZXW6=="[..], Alphabet::Rfc4648),
+            (b"MZXW6===", Alphabet::Rfc4648NoPadding),
+            (b"MZXW6YTBO", Alphabet::Rfc4648NoPadding),
+            (b"MZXW6Y", Alphabet::Rfc4648),
+        ] {
+            let mut decoder = Decoder::new(input, alphabet);
+            let err = decoder.read_to_end(&mut Vec::new()).unwrap_err();
+            assert_eq!(Err(decode_error(err)), decode(input, alphabet), "{input:?} {alphabet:?}");
+        }
+    }
+
     #[test]
     fn test_roundtrip() {
         for &len in ROUNDTRIP_SIZES {
@@ -1085,6 +1787,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_decode_ignore_whitespace() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+
+        for alphabet in [Alphabet::Rfc4648, Alphabet::Rfc4648NoPadding, Alphabet::Crockford] {
+            let encoded = encode(&data, alphabet);
+
+            // split in lines of 64 characters, like PEM
+            let multiline = encoded
+                .as_bytes()
+                .chunks(64)
+                .map(|line| core::str::from_utf8(line).unwrap())
+                .collect::<Vec<_>>()
+                .join("\r\n");
+            assert!(decode(&multiline, alphabet).is_err());
+            assert_eq!(decode_ignore_whitespace(&multiline, alphabet).unwrap(), data);
+
+            let spaced = format!("  {}\n\t", encoded.replace("A", " A"));
+            assert_eq!(decode_ignore_whitespace(&spaced, alphabet).unwrap(), data);
+            assert_eq!(
+                decode_ignore_whitespace(&spaced, alphabet).unwrap(),
+                decode(&encoded, alphabet).unwrap()
+            );
+        }
+
+        assert_eq!(decode_ignore_whitespace("", Alphabet::Rfc4648).unwrap(), b"");
+        assert_eq!(decode_ignore_whitespace(" \n ", Alphabet::Rfc4648).unwrap(), b"");
+        assert_eq!(decode_ignore_whitespace(b"NBSWY3DP", Alphabet::Rfc4648).unwrap(), b"hello");
+        assert_eq!(
+            decode_ignore_whitespace(b"NBSW\nY3D!", Alphabet::Rfc4648),
+            Err(DecodeError::InvalidInput)
+        );
+        assert_eq!(
+            decode_ignore_whitespace(b"NBS\nWY3", Alphabet::Rfc4648NoPadding),
+            Err(DecodeError::InvalidLength)
+        );
+    }
+
     #[test]
     fn test_roundtrip_large() {
         let size = 4096;
@@ -1284,6 +2024,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_strict_padding() {
+        let padded = ALL_ALPHABETS.iter().filter(|alphabet| alphabet.is_padded());
+        for alphabet in padded {
+            let alphabet = *alphabet;
+            // the inputs are written with `A`, replaced by the character of the quintet 0
+            let zero = encode([0u8], alphabet);
+            let zero = &zero[..1];
+
+            // valid padding, for each length of the last block
+            for data in [&b"f"[..], b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+                let encoded = encode(data, alphabet);
+                assert_eq!(decode(&encoded, alphabet).unwrap(), data, "{encoded} {alphabet:?}");
+            }
+
+            // embedded padding
+            for input in ["A=AAAAAA", "AA=AAAAA", "AAAAAA=A", "AA======AAAAAAAA", "=AAAAAAA"] {
+                let input = input.replace('A', zero);
+                assert_eq!(decode(&input, alphabet), Err(DecodeError::InvalidInput), "{input} {alphabet:?}");
+            }
+
+            // padding of the wrong length
+            for input in ["AA=====", "AA====", "AAA=====", "A=======", "AAAAA=", "========"] {
+                let input = input.replace('A', zero);
+                let result = decode(&input, alphabet);
+                assert!(result.is_err(), "{input} {alphabet:?}");
+                assert_ne!(result, Err(DecodeError::InvalidInput), "{input} {alphabet:?}");
+            }
+        }
+
+        // embedded padding is also rejected by the SIMD decoders, which handle long inputs
+        let encoded = encode([7u8; 100], Alphabet::Rfc4648);
+        for i in [0, 10, 40, 63, 100, 150] {
+            let mut input = encoded.clone().into_bytes();
+            input[i] = b'=';
+            assert_eq!(decode(&input, Alphabet::Rfc4648), Err(DecodeError::InvalidInput), "i={i}");
+        }
+    }
+
     #[test]
     fn test_roundtrip_simd_boundary_sizes() {
         let mut data_buf = Vec::new();
@@ -1381,6 +2160,68 @@ mod tests {
             format!("{}", EncodeError::InvalidOutputLength),
             "output buffer size is not valid"
         );
+        assert_eq!(format!("{}", EncodeError::InvalidPadding), "invalid base32 padding character");
+    }
+
+    #[test]
+    fn test_custom_padding() {
+        // (input_bytes, alphabet, pad, expected_encoded_str)
+        let vectors: &[(&[u8], Alphabet, u8, &str)] = &[
+            (b"", Alphabet::Rfc4648, b'#', ""),
+            (b"f", Alphabet::Rfc4648, b'#', "MY######"),
+            (b"foo", Alphabet::Rfc4648, b'#', "MZXW6###"),
+            (b"fooba", Alphabet::Rfc4648, b'#', "MZXW6YTB"),
+            (b"foobar", Alphabet::Rfc4648Lower, b'*', "mzxw6ytboi******"),
+            (b"foob", Alphabet::Rfc4648HexNoPadding, b'#', "CPNMUOG#"),
+            (b"foo", Alphabet::Crockford, b'#', "CSQPY###"),
+            (b"foo", Alphabet::Crockford, b'=', "CSQPY==="),
+        ];
+        for &(input, alphabet, pad, expected) in vectors {
+            let encoded = encode_with_padding(input, alphabet, pad).unwrap();
+            assert_eq!(encoded, expected, "encode {input:?} {alphabet:?}");
+            assert_eq!(
+                decode_with_padding(&encoded, alphabet, pad).unwrap(),
+                input,
+                "decode {expected}"
+            );
+        }
+
+        // `=` is the default padding character
+        assert_eq!(
+            encode_with_padding(b"foo", Alphabet::Rfc4648, b'=').unwrap(),
+            encode(b"foo", Alphabet::Rfc4648)
+        );
+        // padding is optional when decoding
+        assert_eq!(decode_with_padding(b"MZXW6", Alphabet::Rfc4648, b'#').unwrap(), b"foo");
+
+        for (input, pad) in [(&b"MZXW6==="[..], b'#'), (b"MZXW6##", b'#'), (b"MZXW6#=#", b'#')] {
+            assert!(decode_with_padding(input, Alphabet::Rfc4648, pad).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_custom_padding_rejects_alphabet_characters() {
+        for (alphabet, pad) in [
+            (Alphabet::Rfc4648, b'A'),
+            (Alphabet::Rfc4648, b'7'),
+            (Alphabet::Rfc4648Lower, b'a'),
+            (Alphabet::Rfc4648Hex, b'0'),
+            (Alphabet::Crockford, b'Z'),
+            (Alphabet::Rfc4648, 0xC3),
+        ] {
+            assert_eq!(
+                encode_with_padding(b"foo", alphabet, pad),
+                Err(EncodeError::InvalidPadding),
+                "{alphabet:?} {pad}"
+            );
+            assert_eq!(
+                decode_with_padding(b"MZXW6===", alphabet, pad),
+                Err(DecodeError::InvalidPadding),
+                "{alphabet:?} {pad}"
+            );
+        }
+        // characters of other alphabets are fine
+        assert!(encode_with_padding(b"foo", Alphabet::Rfc4648, b'a').is_ok());
     }
 
     #[cfg(feature = "serde")]
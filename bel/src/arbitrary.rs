@@ -0,0 +1,203 @@
+//! Generation of arbitrary values for fuzzing, enabled by the `arbitrary` feature.
+//!
+//! [`Value`] implements `Arbitrary` by hand so that the nesting of lists, maps and functions is
+//! bounded, see [`MAX_DEPTH`]. The types of its time, regex and ip variants come from other
+//! crates, so they are generated by the functions of this module. They only generate valid values,
+//! e.g. timestamps in the range supported by CEL, so that the fuzzer exercises the operators
+//! instead of the constructors of these types.
+
+use std::{collections::HashMap, sync::Arc};
+
+use arbitrary::{Arbitrary, Error, Result, Unstructured};
+
+use crate::{Value, objects::Map};
+
+/// Maximum nesting of the values generated for lists, maps and function targets. Deeper values
+/// don't exercise more code, and could overflow the stack when they are compared or dropped.
+const MAX_DEPTH: usize = 4;
+
+/// Maximum number of items of the lists and maps generated.
+const MAX_LEN: usize = 16;
+
+type Generator = fn(&mut Unstructured, usize) -> Result<Value>;
+
+/// The generators of the variants of [`Value`], called with the depth of the value. The lists and
+/// maps come last, so that they can be excluded at [`MAX_DEPTH`].
+const GENERATORS: &[Generator] = &[
+    |_, _| Ok(Value::Null),
+    |u, _| Ok(Value::Bool(u.arbitrary()?)),
+    |u, _| Ok(Value::Int(u.arbitrary()?)),
+    |u, _| Ok(Value::UInt(u.arbitrary()?)),
+    |u, _| Ok(Value::Float(u.arbitrary()?)),
+    |u, _| Ok(Value::String(u.arbitrary()?)),
+    |u, _| Ok(Value::Bytes(u.arbitrary()?)),
+    #[cfg(feature = "time")]
+    |u, _| Ok(Value::Duration(duration(u)?)),
+    #[cfg(feature = "time")]
+    |u, _| Ok(Value::Timestamp(timestamp(u)?)),
+    #[cfg(feature = "regex")]
+    |u, _| Ok(Value::Regex(regex(u)?)),
+    #[cfg(feature = "ip")]
+    |u, _| Ok(Value::Ip(ip(u)?)),
+    function,
+    list,
+    map,
+];
+
+/// Number of generators at the end of [`GENERATORS`] that generate nested values.
+const CONTAINERS: usize = 2;
+
+impl<'a> Arbitrary<'a> for Value {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        value(u, 0)
+    }
+}
+
+fn value(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    let generators = if depth < MAX_DEPTH {
+        GENERATORS
+    } else {
+        &GENERATORS[..GENERATORS.len() - CONTAINERS]
+    };
+    generators[u.choose_index(generators.len())?](u, depth)
+}
+
+fn function(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    let name: Arc<String> = u.arbitrary()?;
+    let target = if depth < MAX_DEPTH && u.arbitrary()? {
+        Some(Box::new(value(u, depth + 1)?))
+    } else {
+        None
+    };
+    Ok(Value::Function(name, target))
+}
+
+fn list(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    let mut items = Vec::new();
+    // like `Unstructured::arbitrary_iter`, generate items while the input says so
+    while items.len() < MAX_LEN && u.arbitrary()? {
+        items.push(value(u, depth + 1)?);
+    }
+    Ok(Value::List(Arc::new(items)))
+}
+
+fn map(u: &mut Unstructured, depth: usize) -> Result<Value> {
+    let mut map = HashMap::new();
+    while map.len() < MAX_LEN && u.arbitrary()? {
+        map.insert(u.arbitrary()?, value(u, depth + 1)?);
+    }
+    Ok(Value::Map(Map { map: Arc::new(map) }))
+}
+
+#[cfg(feature = "time")]
+pub(crate) fn duration(u: &mut Unstructured) -> Result<chrono::Duration> {
+    let range = chrono::Duration::MIN.num_milliseconds()..=chrono::Duration::MAX.num_milliseconds();
+    let duration = chrono::Duration::try_milliseconds(u.int_in_range(range)?).ok_or(Error::IncorrectFormat)?;
+    let nanos = chrono::Duration::nanoseconds(u.int_in_range(0..=999_999)?);
+    Ok(duration.checked_add(&nanos).unwrap_or(duration))
+}
+
+#[cfg(feature = "time")]
+pub(crate) fn timestamp(u: &mut Unstructured) -> Result<chrono::DateTime<chrono::FixedOffset>> {
+    use crate::objects::{MAX_TIMESTAMP, MIN_TIMESTAMP};
+
+    let seconds = u.int_in_range(MIN_TIMESTAMP.timestamp()..=MAX_TIMESTAMP.timestamp())?;
+    let nanos = u.int_in_range(0..=999_999_999)?;
+    let utc = chrono::DateTime::from_timestamp(seconds, nanos).ok_or(Error::IncorrectFormat)?;
+    // offsets are strictly less than a day
+    let offset = chrono::FixedOffset::east_opt(u.int_in_range(-86_399..=86_399)?).ok_or(Error::IncorrectFormat)?;
+    Ok(utc.with_timezone(&offset))
+}
+
+#[cfg(feature = "regex")]
+pub(crate) fn regex(u: &mut Unstructured) -> Result<regex::Regex> {
+    let pattern: &str = u.arbitrary()?;
+    // most arbitrary strings are not valid patterns, they are matched literally instead
+    regex::Regex::new(pattern)
+        .or_else(|_| regex::Regex::new(&regex::escape(pattern)))
+        .map_err(|_| Error::IncorrectFormat)
+}
+
+#[cfg(feature = "ip")]
+pub(crate) fn ip(u: &mut Unstructured) -> Result<ipnetwork::IpNetwork> {
+    let ip: std::net::IpAddr = u.arbitrary()?;
+    let max_prefix = if ip.is_ipv4() { 32 } else { 128 };
+    ipnetwork::IpNetwork::new(ip, u.int_in_range(0..=max_prefix)?).map_err(|_| Error::IncorrectFormat)
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::Value;
+
+    /// Returns deterministic pseudo-random inputs of various lengths, including an empty one.
+    fn inputs() -> Vec<Vec<u8>> {
+        let mut state: u64 = 0x2545f4914f6cdd1d;
+        (0..200)
+            .map(|len| {
+                (0..len)
+                    .map(|_| {
+                        // xorshift64
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        state as u8
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn arbitrary_variants() {
+        for input in inputs() {
+            #[cfg(feature = "time")]
+            {
+                use crate::objects::{MAX_TIMESTAMP, MIN_TIMESTAMP};
+
+                super::duration(&mut Unstructured::new(&input)).unwrap();
+                let timestamp = super::timestamp(&mut Unstructured::new(&input)).unwrap();
+                assert!(*MIN_TIMESTAMP <= timestamp && timestamp <= *MAX_TIMESTAMP);
+            }
+            #[cfg(feature = "regex")]
+            super::regex(&mut Unstructured::new(&input)).unwrap();
+            #[cfg(feature = "ip")]
+            super::ip(&mut Unstructured::new(&input)).unwrap();
+
+            // generating a whole value may fail when the input is too short, but must not panic
+            if let Ok(value) = Value::arbitrary(&mut Unstructured::new(&input)) {
+                assert!(depth(&value) <= super::MAX_DEPTH, "{value:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn arbitrary_is_deterministic() {
+        // an empty input generates the first variant
+        assert_eq!(Value::arbitrary(&mut Unstructured::new(&[])).unwrap(), Value::Null);
+
+        for input in inputs() {
+            let first = Value::arbitrary(&mut Unstructured::new(&input)).map(|value| format!("{value:?}"));
+            let second = Value::arbitrary(&mut Unstructured::new(&input)).map(|value| format!("{value:?}"));
+            assert_eq!(first.ok(), second.ok());
+        }
+
+        // the inputs are long enough to generate nested values
+        let nested = inputs()
+            .iter()
+            .filter_map(|input| Value::arbitrary(&mut Unstructured::new(input)).ok())
+            .any(|value| depth(&value) > 0);
+        assert!(nested);
+    }
+
+    /// Returns the nesting depth of `value`, 0 for the atoms.
+    fn depth(value: &Value) -> usize {
+        match value {
+            Value::List(items) => items.iter().map(|item| depth(item) + 1).max().unwrap_or(0),
+            Value::Map(map) => map.map.values().map(|item| depth(item) + 1).max().unwrap_or(0),
+            Value::Function(_, Some(target)) => depth(target) + 1,
+            _ => 0,
+        }
+    }
+}
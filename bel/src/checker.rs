@@ -0,0 +1,458 @@
+use thiserror::Error;
+
+use crate::{
+    Context,
+    common::{
+        ast::{EntryExpr, Expr, operators},
+        value::CelVal,
+    },
+    objects::ValueType,
+    parser::Expression,
+};
+
+/// A type mismatch found by [`Program::validate`](crate::Program::validate) without executing the
+/// program.
+#[derive(Error, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum TypeError {
+    /// A binary operator is applied to two types for which it is not supported, for example
+    /// `int + string`.
+    #[error("Unsupported binary operator '{operator}': {left}, {right}")]
+    UnsupportedBinaryOperator {
+        operator: &'static str,
+        left: ValueType,
+        right: ValueType,
+    },
+    /// A unary operator is applied to a type for which it is not supported, for example `-"foo"`.
+    #[error("Unsupported unary operator '{operator}': {operand}")]
+    UnsupportedUnaryOperator { operator: &'static str, operand: ValueType },
+    /// Two values of types that can not be compared are compared, for example `1 < "foo"`.
+    #[error("{left} can not be compared to {right}")]
+    ValuesNotComparable { left: ValueType, right: ValueType },
+    /// A value of a given type is indexed with an unsupported index type, for example `[1][true]`.
+    #[error("Cannot use {index} to index {value}")]
+    UnsupportedIndex { value: ValueType, index: ValueType },
+    /// An expression has a different type than the one expected by its parent, for example a
+    /// condition that is not a `bool`.
+    #[error("Unexpected type: got '{got}', want '{want}'")]
+    UnexpectedType { got: ValueType, want: ValueType },
+}
+
+/// Infers the types of the expression's nodes and reports the obvious type mismatches.
+///
+/// The checker is best-effort: the type of variables that are neither declared nor present in
+/// the context, of function calls and of field selections is unknown, and any operation involving
+/// an unknown type is accepted.
+pub(crate) fn check(expr: &Expression, ctx: &Context) -> Result<(), Vec<TypeError>> {
+    let mut checker = Checker {
+        ctx,
+        locals: Vec::new(),
+        errors: Vec::new(),
+    };
+    checker.infer(expr);
+    if checker.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(checker.errors)
+    }
+}
+
+struct Checker<'a, 'c> {
+    ctx: &'a Context<'c>,
+    /// Variables declared by comprehensions, in scope for the expression being checked. They
+    /// shadow the variables of the context, and their type is unknown.
+    locals: Vec<&'a str>,
+    errors: Vec<TypeError>,
+}
+
+impl<'a> Checker<'a, '_> {
+    /// Returns the type of `expr`, or `None` if it could not be inferred.
+    fn infer(&mut self, expr: &'a Expression) -> Option<ValueType> {
+        match &expr.expr {
+            Expr::Literal(val) => literal_type(val),
+            Expr::Ident(name) => {
+                if self.locals.contains(&name.as_str()) {
+                    None
+                } else {
+                    self.ctx.get_variable_type(name)
+                }
+            }
+            Expr::List(list) => {
+                for element in &list.elements {
+                    self.infer(element);
+                }
+                Some(ValueType::List)
+            }
+            Expr::Map(map) => {
+                for entry in &map.entries {
+                    if let EntryExpr::MapEntry(entry) = &entry.expr {
+                        self.infer(&entry.key);
+                        self.infer(&entry.value);
+                    }
+                }
+                Some(ValueType::Map)
+            }
+            Expr::Select(select) => {
+                self.infer(&select.operand);
+                if select.test { Some(ValueType::Bool) } else { None }
+            }
+            Expr::Comprehension(comprehension) => {
+                self.infer(&comprehension.iter_range);
+                self.infer(&comprehension.accu_init);
+
+                let scope = self.locals.len();
+                self.locals.push(&comprehension.accu_var);
+                self.locals.push(&comprehension.iter_var);
+                if let Some(iter_var2) = &comprehension.iter_var2 {
+                    self.locals.push(iter_var2);
+                }
+                self.infer(&comprehension.loop_cond);
+                self.infer(&comprehension.loop_step);
+                self.infer(&comprehension.result);
+                self.locals.truncate(scope);
+                None
+            }
+            Expr::Call(call) => {
+                if call.args.len() == 3 && call.func_name == operators::CONDITIONAL {
+                    let cond = self.infer(&call.args[0]);
+                    self.expect(cond, ValueType::Bool);
+                    let left = self.infer(&call.args[1]);
+                    let right = self.infer(&call.args[2]);
+                    return if left == right { left } else { None };
+                }
+                if call.args.len() == 2 {
+                    if let Some(operator) = binary_operator_name(&call.func_name) {
+                        let left = self.infer(&call.args[0]);
+                        let right = self.infer(&call.args[1]);
+                        let (Some(left), Some(right)) = (left, right) else {
+                            return None;
+                        };
+                        return match binary_operator_type(operator, left, right) {
+                            Some(result) => Some(result),
+                            None => {
+                                self.errors.push(TypeError::UnsupportedBinaryOperator {
+                                    operator,
+                                    left,
+                                    right,
+                                });
+                                None
+                            }
+                        };
+                    }
+                    match call.func_name.as_str() {
+                        operators::EQUALS | operators::NOT_EQUALS => {
+                            self.infer(&call.args[0]);
+                            self.infer(&call.args[1]);
+                            return Some(ValueType::Bool);
+                        }
+                        operators::LESS | operators::LESS_EQUALS | operators::GREATER | operators::GREATER_EQUALS => {
+                            let left = self.infer(&call.args[0]);
+                            let right = self.infer(&call.args[1]);
+                            if let (Some(left), Some(right)) = (left, right)
+                                && !comparable(left, right)
+                            {
+                                self.errors.push(TypeError::ValuesNotComparable {
+                                    left,
+                                    right,
+                                });
+                            }
+                            return Some(ValueType::Bool);
+                        }
+                        operators::IN => {
+                            let left = self.infer(&call.args[0]);
+                            let right = self.infer(&call.args[1]);
+                            if let (Some(left), Some(right)) = (left, right)
+                                && !matches!(
+                                    (left, right),
+                                    (ValueType::String, ValueType::String) | (_, ValueType::List | ValueType::Map)
+                                )
+                            {
+                                self.errors.push(TypeError::ValuesNotComparable {
+                                    left,
+                                    right,
+                                });
+                            }
+                            return Some(ValueType::Bool);
+                        }
+                        operators::LOGICAL_OR | operators::LOGICAL_AND => {
+                            let left = self.infer(&call.args[0]);
+                            self.expect(left, ValueType::Bool);
+                            let right = self.infer(&call.args[1]);
+                            self.expect(right, ValueType::Bool);
+                            return Some(ValueType::Bool);
+                        }
+                        operators::INDEX => {
+                            let value = self.infer(&call.args[0]);
+                            let index = self.infer(&call.args[1]);
+                            let (Some(value), Some(index)) = (value, index) else {
+                                return None;
+                            };
+                            return match (value, index) {
+                                (ValueType::String, ValueType::Int) => Some(ValueType::String),
+                                (ValueType::List, ValueType::Int | ValueType::UInt)
+                                | (
+                                    ValueType::Map,
+                                    ValueType::String
+                                    | ValueType::Bool
+                                    | ValueType::Int
+                                    | ValueType::UInt
+                                    | ValueType::Float,
+                                ) => None,
+                                (value, index) => {
+                                    self.errors.push(TypeError::UnsupportedIndex {
+                                        value,
+                                        index,
+                                    });
+                                    None
+                                }
+                            };
+                        }
+                        _ => (),
+                    }
+                }
+                if call.args.len() == 1 {
+                    match call.func_name.as_str() {
+                        operators::LOGICAL_NOT => {
+                            let operand = self.infer(&call.args[0]);
+                            self.expect(operand, ValueType::Bool);
+                            return Some(ValueType::Bool);
+                        }
+                        operators::NEGATE => {
+                            return match self.infer(&call.args[0]) {
+                                Some(ValueType::Int) => Some(ValueType::Int),
+                                Some(ValueType::Float) => Some(ValueType::Float),
+                                Some(operand) => {
+                                    self.errors.push(TypeError::UnsupportedUnaryOperator {
+                                        operator: "minus",
+                                        operand,
+                                    });
+                                    None
+                                }
+                                None => None,
+                            };
+                        }
+                        operators::NOT_STRICTLY_FALSE => {
+                            self.infer(&call.args[0]);
+                            return Some(ValueType::Bool);
+                        }
+                        _ => (),
+                    }
+                }
+
+                // functions can be overridden in the context, so we don't make any assumption
+                // about their signature.
+                if let Some(target) = &call.target {
+                    self.infer(target);
+                }
+                for arg in &call.args {
+                    self.infer(arg);
+                }
+                None
+            }
+            // struct literals are evaluated to maps of their fields
+            Expr::Struct(structure) => {
+                for entry in &structure.entries {
+                    if let EntryExpr::StructField(field) = &entry.expr {
+                        self.infer(&field.value);
+                    }
+                }
+                Some(ValueType::Map)
+            }
+            Expr::Unspecified => None,
+        }
+    }
+
+    fn expect(&mut self, got: Option<ValueType>, want: ValueType) {
+        if let Some(got) = got
+            && got != want
+        {
+            self.errors.push(TypeError::UnexpectedType {
+                got,
+                want,
+            });
+        }
+    }
+}
+
+fn literal_type(val: &CelVal) -> Option<ValueType> {
+    match val {
+        CelVal::String(_) => Some(ValueType::String),
+        CelVal::Boolean(_) => Some(ValueType::Bool),
+        CelVal::Int(_) => Some(ValueType::Int),
+        CelVal::UInt(_) => Some(ValueType::UInt),
+        CelVal::Float(_) => Some(ValueType::Float),
+        CelVal::Bytes(_) => Some(ValueType::Bytes),
+        CelVal::Null => Some(ValueType::Null),
+        _ => None,
+    }
+}
+
+/// Returns the name used in errors for the arithmetic operator `func_name`, matching the names
+/// used by [`ExecutionError::UnsupportedBinaryOperator`](crate::ExecutionError).
+fn binary_operator_name(func_name: &str) -> Option<&'static str> {
+    match func_name {
+        operators::ADD => Some("add"),
+        operators::SUBSTRACT => Some("sub"),
+        operators::MULTIPLY => Some("mul"),
+        operators::DIVIDE => Some("div"),
+        operators::MODULO => Some("rem"),
+        _ => None,
+    }
+}
+
+/// Returns the type of the result of an arithmetic operator, or `None` if the operator is not
+/// supported for these types. Mirrors the `std::ops` implementations of [`Value`](crate::Value).
+fn binary_operator_type(operator: &str, left: ValueType, right: ValueType) -> Option<ValueType> {
+    use ValueType::*;
+
+    match (operator, left, right) {
+        ("add" | "sub" | "mul" | "div" | "rem", Int, Int) => Some(Int),
+        ("add" | "sub" | "mul" | "div" | "rem", UInt, UInt) => Some(UInt),
+        ("add" | "sub" | "mul" | "div", Float, Float) => Some(Float),
+        ("add", List, List) => Some(List),
+        ("add", String, String) => Some(String),
+        ("add" | "sub", Duration, Duration) => Some(Duration),
+        ("add" | "sub", Timestamp, Duration) => Some(Timestamp),
+        ("add", Duration, Timestamp) => Some(Timestamp),
+        ("sub", Timestamp, Timestamp) => Some(Duration),
+        _ => None,
+    }
+}
+
+/// Mirrors the `PartialOrd` implementation of [`Value`](crate::Value).
+fn comparable(left: ValueType, right: ValueType) -> bool {
+    use ValueType::*;
+
+    matches!(
+        (left, right),
+        (Int, Int)
+            | (UInt, UInt)
+            | (Float, Float)
+            | (String, String)
+            | (Bytes, Bytes)
+            | (Bool, Bool)
+            | (Null, Null)
+            | (Duration, Duration)
+            | (Timestamp, Timestamp)
+            | (Ip, Ip)
+            | (Int, UInt)
+            | (Int, Float)
+            | (UInt, Int)
+            | (UInt, Float)
+            | (Float, Int)
+            | (Float, UInt)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Context, Program, checker::TypeError, objects::ValueType};
+
+    fn validate(script: &str, ctx: &Context) -> Result<(), Vec<TypeError>> {
+        Program::compile(script).unwrap().validate(ctx)
+    }
+
+    #[test]
+    fn int_plus_string() {
+        let mut ctx = Context::default();
+        ctx.declare_variable("foo", ValueType::Int);
+
+        assert_eq!(
+            validate("foo + \"x\"", &ctx),
+            Err(vec![TypeError::UnsupportedBinaryOperator {
+                operator: "add",
+                left: ValueType::Int,
+                right: ValueType::String,
+            }])
+        );
+    }
+
+    #[test]
+    fn well_typed() {
+        let mut ctx = Context::default();
+        ctx.declare_variable("foo", ValueType::Int);
+        ctx.declare_variable("name", ValueType::String);
+        ctx.declare_variable("items", ValueType::List);
+
+        let scripts = [
+            "foo + 1 > 2 && name + \"x\" == \"yx\"",
+            "-foo < 1.5",
+            "name[0] == \"f\" ? foo : 0",
+            "items.map(x, x + \"a\").filter(x, x > 1)",
+            "length(items) + foo",
+            "unknown + 1",
+            "foo in items && name in \"foobar\"",
+            "{1: \"a\"}[1.0] == \"a\"",
+            "Foo{a: foo + 1}.a == 2",
+        ];
+        for script in scripts {
+            assert_eq!(validate(script, &ctx), Ok(()), "{script}");
+        }
+    }
+
+    #[test]
+    fn mismatches() {
+        let mut ctx = Context::default();
+        ctx.declare_variable("foo", ValueType::Int);
+        ctx.add_variable_from_value("name", "foobar");
+
+        let tests = [
+            (
+                "foo < name",
+                TypeError::ValuesNotComparable {
+                    left: ValueType::Int,
+                    right: ValueType::String,
+                },
+            ),
+            (
+                "name in foo",
+                TypeError::ValuesNotComparable {
+                    left: ValueType::String,
+                    right: ValueType::Int,
+                },
+            ),
+            (
+                "foo ? 1 : 2",
+                TypeError::UnexpectedType {
+                    got: ValueType::Int,
+                    want: ValueType::Bool,
+                },
+            ),
+            (
+                "!name",
+                TypeError::UnexpectedType {
+                    got: ValueType::String,
+                    want: ValueType::Bool,
+                },
+            ),
+            (
+                "-name",
+                TypeError::UnsupportedUnaryOperator {
+                    operator: "minus",
+                    operand: ValueType::String,
+                },
+            ),
+            (
+                "[1, 2][name]",
+                TypeError::UnsupportedIndex {
+                    value: ValueType::List,
+                    index: ValueType::String,
+                },
+            ),
+        ];
+        for (script, error) in tests {
+            assert_eq!(validate(script, &ctx), Err(vec![error]), "{script}");
+        }
+    }
+
+    #[test]
+    fn declarations_override_values() {
+        let mut ctx = Context::default();
+        ctx.add_variable_from_value("foo", "bar");
+        ctx.declare_variable("foo", ValueType::Int);
+        assert_eq!(validate("foo + 1", &ctx), Ok(()));
+
+        // comprehension variables shadow the context
+        assert_eq!(validate("[\"a\"].map(foo, foo + \"b\")", &ctx), Ok(()));
+    }
+}
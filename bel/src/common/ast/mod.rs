@@ -9,6 +9,7 @@ pub struct Ast {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Expr {
     #[default]
     /// UnspecifiedExprKind represents an unset expression with no specified properties.
@@ -40,24 +41,28 @@ pub enum Expr {
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntryExpr {
     StructField(StructFieldExpr),
     MapEntry(MapEntryExpr),
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct IdedExpr {
     pub id: u64,
     pub expr: Expr,
 }
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct IdedEntryExpr {
     pub id: u64,
     pub expr: EntryExpr,
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct CallExpr {
     pub func_name: String,
     pub target: Option<Box<IdedExpr>>,
@@ -65,6 +70,7 @@ pub struct CallExpr {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct SelectExpr {
     pub operand: Box<IdedExpr>,
     pub field: String,
@@ -72,22 +78,26 @@ pub struct SelectExpr {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct StructExpr {
     pub type_name: String,
     pub entries: Vec<IdedEntryExpr>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapExpr {
     pub entries: Vec<IdedEntryExpr>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ListExpr {
     pub elements: Vec<IdedExpr>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct StructFieldExpr {
     pub field: String,
     pub value: IdedExpr,
@@ -95,6 +105,7 @@ pub struct StructFieldExpr {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapEntryExpr {
     pub key: IdedExpr,
     pub value: IdedExpr,
@@ -102,6 +113,7 @@ pub struct MapEntryExpr {
 }
 
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComprehensionExpr {
     pub iter_range: IdedExpr,
     pub iter_var: String,
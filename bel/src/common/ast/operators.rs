@@ -33,11 +33,37 @@ pub const ALL: &str = "all";
 pub const ANY: &str = "any";
 pub const MAP: &str = "map";
 pub const FILTER: &str = "filter";
+pub const SORT_BY: &str = "sortBy";
+pub const REDUCE: &str = "reduce";
 
 pub const NOT_STRICTLY_FALSE: &str = "@not_strictly_false";
-// pub const IN: &str = "@in";
+pub const IN: &str = "@in";
+pub const SORT_BY_KEY: &str = "@sortByKey";
 
-const OPERATORS: [(&str, &str); 11] = [
+/// The operators evaluated by the interpreter itself, which are not functions of the context.
+pub(crate) const BUILTINS: [&str; 19] = [
+    CONDITIONAL,
+    LOGICAL_AND,
+    LOGICAL_OR,
+    LOGICAL_NOT,
+    SUBSTRACT,
+    ADD,
+    MULTIPLY,
+    DIVIDE,
+    MODULO,
+    EQUALS,
+    NOT_EQUALS,
+    GREATER_EQUALS,
+    LESS_EQUALS,
+    GREATER,
+    LESS,
+    NEGATE,
+    INDEX,
+    NOT_STRICTLY_FALSE,
+    IN,
+];
+
+const OPERATORS: [(&str, &str); 12] = [
     ("-", SUBSTRACT),
     ("+", ADD),
     ("*", MULTIPLY),
@@ -49,5 +75,5 @@ const OPERATORS: [(&str, &str); 11] = [
     ("<=", LESS_EQUALS),
     (">", GREATER),
     ("<", LESS),
-    // ("in", IN),
+    ("in", IN),
 ];
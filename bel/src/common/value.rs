@@ -7,6 +7,7 @@ use std::{
 use crate::common::{types, types::Type};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum CelVal {
     Unspecified,
     Error,
@@ -1,10 +1,21 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    },
+};
+
+use serde::Serialize;
 
 use crate::{
-    ExecutionError, functions,
+    ExecutionError, SerializationError,
+    common::ast::{Expr, operators},
+    functions::{self, FunctionContext},
     magic::{Function, FunctionRegistry, IntoFunction},
-    objects::{TryIntoValue, Value},
+    objects::{Key, ResolveResult, TryIntoValue, Value, ValueType},
     parser::Expression,
+    to_value,
 };
 
 /// Context is a collection of variables and functions that can be used
@@ -36,13 +47,115 @@ pub enum Context<'a> {
     Root {
         functions: FunctionRegistry,
         variables: HashMap<String, Value>,
+        declarations: HashMap<String, ValueType>,
+        variable_resolver: Option<Box<VariableResolver>>,
+        #[cfg(feature = "regex")]
+        regex_limits: RegexLimits,
+        evaluation_limits: EvaluationLimits,
     },
     Child {
         parent: &'a Context<'a>,
         variables: HashMap<String, Value>,
+        declarations: HashMap<String, ValueType>,
+        cancellation: Option<&'a AtomicBool>,
+        budget: Option<&'a EvaluationBudget>,
+        // values returned by the variable resolver during the evaluation, so that it's called
+        // only once per variable
+        resolved_variables: Option<&'a Mutex<HashMap<String, Value>>>,
     },
 }
 
+/// A callback returning the value of the variables that were not added to a context, see
+/// [`Context::set_variable_resolver`].
+pub type VariableResolver = dyn Fn(&str) -> Option<Value> + Send + Sync;
+
+/// Limits applied when compiling regular expressions with the `Regex` function, see
+/// [`regex::RegexBuilder::size_limit`] and [`regex::RegexBuilder::dfa_size_limit`].
+///
+/// Patterns coming from untrusted input can compile to huge automata. A pattern exceeding
+/// `size_limit` is rejected with an [`ExecutionError::FunctionError`].
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegexLimits {
+    /// Approximate maximum size in bytes of a compiled regular expression.
+    pub size_limit: usize,
+    /// Approximate maximum size in bytes of the cache used by the lazy DFA of each regular
+    /// expression.
+    pub dfa_size_limit: usize,
+}
+
+#[cfg(feature = "regex")]
+impl Default for RegexLimits {
+    /// 1 MiB for both limits, enough for the patterns usually found in expressions.
+    fn default() -> Self {
+        RegexLimits {
+            size_limit: 1 << 20,
+            dfa_size_limit: 1 << 20,
+        }
+    }
+}
+
+/// Limits applied to each evaluation of an expression, see [`Context::set_evaluation_limits`].
+///
+/// Expressions coming from untrusted input can be deeply nested, which may overflow the stack,
+/// or iterate over huge lists with nested macros, which may take a very long time. An evaluation
+/// exceeding a limit fails with an [`ExecutionError::ResourceExhausted`]. Both limits are
+/// disabled by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvaluationLimits {
+    /// Maximum number of steps of an evaluation: each evaluated node of the expression, and each
+    /// iteration of a comprehension (generated by macros such as `all` or `map`), is a step.
+    pub max_steps: Option<u64>,
+    /// Maximum number of nested nodes being evaluated at once.
+    pub max_depth: Option<usize>,
+}
+
+/// The steps and depth of an evaluation, checked against the [`EvaluationLimits`] of its context.
+///
+/// It's created when an evaluation starts, and shared by all the scopes of the evaluation.
+#[derive(Debug)]
+pub struct EvaluationBudget {
+    limits: EvaluationLimits,
+    steps: AtomicU64,
+    depth: AtomicUsize,
+}
+
+impl EvaluationBudget {
+    pub(crate) fn new(limits: EvaluationLimits) -> Self {
+        EvaluationBudget {
+            limits,
+            steps: AtomicU64::new(0),
+            depth: AtomicUsize::new(0),
+        }
+    }
+
+    /// Counts a step, failing if there are too many.
+    pub(crate) fn step(&self) -> Result<(), ExecutionError> {
+        // an evaluation runs on a single thread, relaxed atomics are only used to keep contexts `Sync`
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.limits.max_steps {
+            Some(max_steps) if steps > max_steps => Err(ExecutionError::ResourceExhausted("max_steps")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts a step and enters a node, failing if there are too many steps or nested nodes. The
+    /// node must be left with [`EvaluationBudget::exit`] if this succeeds.
+    pub(crate) fn enter(&self) -> Result<(), ExecutionError> {
+        self.step()?;
+        let depth = self.depth.load(Ordering::Relaxed) + 1;
+        if self.limits.max_depth.is_some_and(|max_depth| depth > max_depth) {
+            return Err(ExecutionError::ResourceExhausted("max_depth"));
+        }
+        self.depth.store(depth, Ordering::Relaxed);
+        Ok(())
+    }
+
+    pub(crate) fn exit(&self) {
+        self.depth.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 impl Context<'_> {
     pub fn add_variable<S, V>(&mut self, name: S, value: V) -> Result<(), <V as TryIntoValue>::Error>
     where
@@ -83,26 +196,262 @@ impl Context<'_> {
         }
     }
 
+    /// Adds all the `(name, value)` pairs of `variables`, e.g. of a `HashMap<String, Value>`.
+    pub fn add_variables<I, S, V>(&mut self, variables: I)
+    where
+        I: IntoIterator<Item = (S, V)>,
+        S: Into<String>,
+        V: Into<Value>,
+    {
+        for (name, value) in variables {
+            self.add_variable_from_value(name, value);
+        }
+    }
+
+    /// Serializes `value`, usually a struct or a map, and adds each of its top-level fields as a
+    /// variable. Fails if `value` isn't serialized as a map, or if one of its keys isn't a string, in
+    /// which case no variable is added.
+    pub fn add_variables_from_serialize<T>(&mut self, value: T) -> Result<(), SerializationError>
+    where
+        T: Serialize,
+    {
+        let map = match to_value(value)? {
+            Value::Map(map) => Arc::unwrap_or_clone(map.map),
+            value => {
+                return Err(SerializationError::SerdeError(format!(
+                    "variables must be serialized as a map, got {}",
+                    value.type_of()
+                )));
+            }
+        };
+
+        // the keys are validated before adding any variable, to leave the context unchanged on error
+        let variables = map
+            .into_iter()
+            .map(|(key, value)| match key {
+                Key::String(name) => Ok((Arc::unwrap_or_clone(name), value)),
+                key => Err(SerializationError::InvalidKey(format!("invalid variable name: {key}"))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.add_variables(variables);
+        Ok(())
+    }
+
     pub fn get_variable<S>(&self, name: S) -> Result<Value, ExecutionError>
     where
         S: AsRef<str>,
     {
         let name = name.as_ref();
+        self.find_variable(name)
+            .or_else(|| self.resolve_variable(name))
+            .ok_or_else(|| ExecutionError::UndeclaredReference(name.to_string().into()))
+    }
+
+    /// Returns the value of a variable added to this scope or to its parents.
+    fn find_variable(&self, name: &str) -> Option<Value> {
         match self {
             Context::Child {
                 variables,
                 parent,
+                ..
             } => variables
                 .get(name)
                 .cloned()
-                .or_else(|| parent.get_variable(name).ok())
-                .ok_or_else(|| ExecutionError::UndeclaredReference(name.to_string().into())),
+                .or_else(|| parent.find_variable(name)),
             Context::Root {
                 variables, ..
-            } => variables
+            } => variables.get(name).cloned(),
+        }
+    }
+
+    /// Returns the value of a variable supplied by the variable resolver of the root context,
+    /// cached for the rest of the evaluation.
+    fn resolve_variable(&self, name: &str) -> Option<Value> {
+        let resolver = self.variable_resolver()?;
+        let Some(resolved_variables) = self.resolved_variables() else {
+            return resolver(name);
+        };
+        // a panic in the resolver can't leave the cache half-updated
+        let cached = resolved_variables
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(name)
+            .cloned();
+        if cached.is_some() {
+            return cached;
+        }
+
+        // the lock is not held while calling the resolver, which may be slow
+        let value = resolver(name)?;
+        resolved_variables
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(name.to_string(), value.clone());
+        Some(value)
+    }
+
+    fn variable_resolver(&self) -> Option<&VariableResolver> {
+        match self {
+            Context::Root {
+                variable_resolver, ..
+            } => variable_resolver.as_deref(),
+            Context::Child {
+                parent, ..
+            } => parent.variable_resolver(),
+        }
+    }
+
+    /// Removes a variable from this scope, returning its value if it was set.
+    ///
+    /// The variables of the parent scopes are not affected, so removing a variable that shadows
+    /// another one makes the shadowed variable visible again. As only the variables are removed,
+    /// a single root context can be reused across evaluations without registering its functions
+    /// again:
+    ///
+    /// ```
+    /// # use bel::{Context, Program};
+    /// let mut context = Context::default();
+    /// let program = Program::compile("length(path)").unwrap();
+    /// for path in ["/", "/index.html"] {
+    ///     context.add_variable_from_value("path", path);
+    ///     program.execute(&context).unwrap();
+    ///     context.remove_variable("path");
+    /// }
+    /// assert!(context.variable_names().is_empty());
+    /// ```
+    pub fn remove_variable(&mut self, name: &str) -> Option<Value> {
+        match self {
+            Context::Root {
+                variables, ..
+            } => variables.remove(name),
+            Context::Child {
+                variables, ..
+            } => variables.remove(name),
+        }
+    }
+
+    /// Returns the sorted names of the variables of this scope and of its parents, without
+    /// duplicates. The variables supplied by a [variable resolver](Context::set_variable_resolver)
+    /// are not included.
+    pub fn variable_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        let mut context = self;
+        loop {
+            match context {
+                Context::Root {
+                    variables, ..
+                } => {
+                    names.extend(variables.keys().map(String::as_str));
+                    break;
+                }
+                Context::Child {
+                    variables,
+                    parent,
+                    ..
+                } => {
+                    names.extend(variables.keys().map(String::as_str));
+                    context = parent;
+                }
+            }
+        }
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    /// Sets a callback returning the value of the variables that were not added to the context,
+    /// e.g. to look them up on demand in a database or a large struct instead of adding all of
+    /// them before executing a program. If the callback returns `None`, the variable is undeclared.
+    ///
+    /// The values returned by the callback are cached for the evaluation, so the callback is called
+    /// at most once per variable and per execution of a program, even if the variable is
+    /// referenced several times. Like
+    /// functions, the resolver can only be set on a root context, and is shared with its child
+    /// contexts.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::{Context, Program, Value};
+    /// let mut context = Context::default();
+    /// context.set_variable_resolver(|name| name.strip_prefix("env_").map(|var| var.to_uppercase().into()));
+    ///
+    /// let program = Program::compile("env_home").unwrap();
+    /// assert_eq!(program.execute(&context), Ok(Value::from("HOME")));
+    /// ```
+    pub fn set_variable_resolver<F>(&mut self, resolver: F)
+    where
+        F: Fn(&str) -> Option<Value> + Send + Sync + 'static,
+    {
+        if let Context::Root {
+            variable_resolver, ..
+        } = self
+        {
+            *variable_resolver = Some(Box::new(resolver));
+        }
+    }
+
+    /// Declares the type of a variable without giving it a value, so that [`Program::validate`] can
+    /// type-check expressions before the actual values are known.
+    ///
+    /// Declarations are only used by the type checker: the variable still needs to be added with
+    /// [`Context::add_variable`] before the program is executed.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::{Context, Program, objects::ValueType};
+    /// let mut context = Context::default();
+    /// context.declare_variable("foo", ValueType::Int);
+    ///
+    /// let program = Program::compile("foo + \"x\"").unwrap();
+    /// assert!(program.validate(&context).is_err());
+    /// ```
+    ///
+    /// [`Program::validate`]: crate::Program::validate
+    pub fn declare_variable<S>(&mut self, name: S, value_type: ValueType)
+    where
+        S: Into<String>,
+    {
+        match self {
+            Context::Root {
+                declarations, ..
+            } => {
+                declarations.insert(name.into(), value_type);
+            }
+            Context::Child {
+                declarations, ..
+            } => {
+                declarations.insert(name.into(), value_type);
+            }
+        }
+    }
+
+    /// Returns the type of a variable: its declared type if it was declared with
+    /// [`Context::declare_variable`], or the type of its value otherwise. Returns `None` if the
+    /// variable is unknown.
+    pub fn get_variable_type<S>(&self, name: S) -> Option<ValueType>
+    where
+        S: AsRef<str>,
+    {
+        let name = name.as_ref();
+        match self {
+            Context::Child {
+                variables,
+                declarations,
+                parent,
+                ..
+            } => declarations
                 .get(name)
-                .cloned()
-                .ok_or_else(|| ExecutionError::UndeclaredReference(name.to_string().into())),
+                .copied()
+                .or_else(|| variables.get(name).map(Value::type_of))
+                .or_else(|| parent.get_variable_type(name)),
+            Context::Root {
+                variables,
+                declarations,
+                ..
+            } => declarations
+                .get(name)
+                .copied()
+                .or_else(|| variables.get(name).map(Value::type_of)),
         }
     }
 
@@ -129,6 +478,166 @@ impl Context<'_> {
         };
     }
 
+    /// Registers a function like [`Context::add_function`], unless a function with the same name
+    /// is already registered, built-in or not, in which case the existing function is kept.
+    ///
+    /// Returns `true` if the function was added, `false` if the name was already registered or if
+    /// the context is a child context, to which functions can't be added.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::Context;
+    /// let mut context = Context::default();
+    /// assert!(context.add_function_checked("add", |a: i64, b: i64| a + b));
+    /// assert!(!context.add_function_checked("length", |a: i64| a));
+    /// ```
+    pub fn add_function_checked<T: 'static, F>(&mut self, name: &str, value: F) -> bool
+    where
+        F: IntoFunction<T> + 'static + Send + Sync,
+    {
+        if self.has_function(name) {
+            return false;
+        }
+        match self {
+            Context::Root {
+                functions, ..
+            } => {
+                functions.add(name, value);
+                true
+            }
+            Context::Child { .. } => false,
+        }
+    }
+
+    /// Sets the limits applied when compiling regular expressions with `Regex(...)`.
+    ///
+    /// Like functions, the limits can only be set on a root context, and are shared with its child
+    /// contexts.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::{Context, context::RegexLimits};
+    /// let mut context = Context::default();
+    /// context.set_regex_limits(RegexLimits {
+    ///     size_limit: 64 * 1024,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn set_regex_limits(&mut self, limits: RegexLimits) {
+        if let Context::Root {
+            regex_limits, ..
+        } = self
+        {
+            *regex_limits = limits;
+        }
+    }
+
+    /// Returns the limits applied when compiling regular expressions, see
+    /// [`Context::set_regex_limits`].
+    #[cfg(feature = "regex")]
+    pub fn regex_limits(&self) -> RegexLimits {
+        match self {
+            Context::Root {
+                regex_limits, ..
+            } => *regex_limits,
+            Context::Child {
+                parent, ..
+            } => parent.regex_limits(),
+        }
+    }
+
+    /// Sets the limits applied to each evaluation of an expression with this context.
+    ///
+    /// Like functions, the limits can only be set on a root context, and are shared with its child
+    /// contexts. See [`Context::with_limits`] to set them when building a context.
+    pub fn set_evaluation_limits(&mut self, limits: EvaluationLimits) {
+        if let Context::Root {
+            evaluation_limits, ..
+        } = self
+        {
+            *evaluation_limits = limits;
+        }
+    }
+
+    /// Sets the limits applied to each evaluation of an expression with this context, see
+    /// [`Context::set_evaluation_limits`].
+    ///
+    /// # Example
+    /// ```
+    /// use bel::{Context, ExecutionError, Program, context::EvaluationLimits};
+    /// let context = Context::default().with_limits(EvaluationLimits {
+    ///     max_steps: Some(100),
+    ///     ..Default::default()
+    /// });
+    ///
+    /// let program = Program::compile("[1, 2, 3].map(x, x * 2)").unwrap();
+    /// assert!(program.execute(&context).is_ok());
+    ///
+    /// let program = Program::compile("[1, 2, 3].map(x, [1, 2, 3].map(y, [1, 2, 3].map(z, x * y * z)))")
+    ///     .unwrap();
+    /// assert_eq!(program.execute(&context), Err(ExecutionError::ResourceExhausted("max_steps")));
+    /// ```
+    pub fn with_limits(mut self, limits: EvaluationLimits) -> Self {
+        self.set_evaluation_limits(limits);
+        self
+    }
+
+    /// Returns the limits applied to each evaluation of an expression, see
+    /// [`Context::set_evaluation_limits`].
+    pub fn evaluation_limits(&self) -> EvaluationLimits {
+        match self {
+            Context::Root {
+                evaluation_limits, ..
+            } => *evaluation_limits,
+            Context::Child {
+                parent, ..
+            } => parent.evaluation_limits(),
+        }
+    }
+
+    /// Returns `true` if a function with this name is registered in the context or its parents.
+    pub fn has_function(&self, name: &str) -> bool {
+        self.get_function(name).is_some()
+    }
+
+    /// Calls the function registered as `name` with values built by the host, for example to
+    /// invoke a [`Value::Function`] returned by a program.
+    ///
+    /// `this` is the target of a method call, like the list in `[1, 2].length()`. The arguments
+    /// are bound to variables of a child scope, so the function resolves them like the arguments
+    /// of a call in a program.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::{Context, Value};
+    /// let context = Context::default();
+    /// let list = Value::from(vec![1i64, 2, 3]);
+    /// assert_eq!(context.call_function("length", None, vec![list.clone()]), Ok(Value::Int(3)));
+    /// assert_eq!(context.call_function("contains", Some(list), vec![2.into()]), Ok(Value::Bool(true)));
+    /// ```
+    pub fn call_function(&self, name: &str, this: Option<Value>, args: Vec<Value>) -> ResolveResult {
+        let func = self
+            .get_function(name)
+            .ok_or_else(|| ExecutionError::undeclared_reference(name))?;
+        let mut scope = self.new_inner_scope();
+        let args = args
+            .into_iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                // `@` can't start an identifier in a program, so these don't shadow its variables
+                let variable = format!("@arg{i}");
+                scope.add_variable_from_value(&variable, arg);
+                Expression {
+                    id: 0,
+                    expr: Expr::Ident(variable),
+                }
+            })
+            .collect();
+        let mut ftx = FunctionContext::new(name.to_string().into(), this, &scope, args);
+        (func)(&mut ftx)
+    }
+
     pub fn resolve(&self, expr: &Expression) -> Result<Value, ExecutionError> {
         Value::resolve(expr, self)
     }
@@ -141,6 +650,98 @@ impl Context<'_> {
         Context::Child {
             parent: self,
             variables: Default::default(),
+            declarations: Default::default(),
+            cancellation: self.cancellation(),
+            budget: self.budget(),
+            resolved_variables: self.resolved_variables(),
+        }
+    }
+
+    /// Returns a child context whose evaluation is cancelled when `cancelled` is set.
+    pub(crate) fn new_cancellable_scope<'b>(&'b self, cancelled: &'b AtomicBool) -> Context<'b> {
+        Context::Child {
+            parent: self,
+            variables: Default::default(),
+            declarations: Default::default(),
+            cancellation: Some(cancelled),
+            budget: self.budget(),
+            resolved_variables: self.resolved_variables(),
+        }
+    }
+
+    /// Returns a child context whose evaluation is checked against `budget`.
+    pub(crate) fn new_budgeted_scope<'b>(&'b self, budget: &'b EvaluationBudget) -> Context<'b> {
+        Context::Child {
+            parent: self,
+            variables: Default::default(),
+            declarations: Default::default(),
+            cancellation: self.cancellation(),
+            budget: Some(budget),
+            resolved_variables: self.resolved_variables(),
+        }
+    }
+
+    /// Returns a child context for an evaluation, which caches the values returned by the variable
+    /// resolver in `resolved_variables`.
+    pub(crate) fn new_evaluation_scope<'b>(
+        &'b self,
+        resolved_variables: &'b Mutex<HashMap<String, Value>>,
+    ) -> Context<'b> {
+        Context::Child {
+            parent: self,
+            variables: Default::default(),
+            declarations: Default::default(),
+            cancellation: self.cancellation(),
+            budget: self.budget(),
+            resolved_variables: Some(resolved_variables),
+        }
+    }
+
+    fn resolved_variables(&self) -> Option<&Mutex<HashMap<String, Value>>> {
+        match self {
+            Context::Root { .. } => None,
+            Context::Child {
+                resolved_variables, ..
+            } => *resolved_variables,
+        }
+    }
+
+    /// Returns the budget of the current evaluation, if the context has [`EvaluationLimits`].
+    pub(crate) fn budget(&self) -> Option<&EvaluationBudget> {
+        match self {
+            Context::Root { .. } => None,
+            Context::Child {
+                budget, ..
+            } => *budget,
+        }
+    }
+
+    fn cancellation(&self) -> Option<&AtomicBool> {
+        match self {
+            Context::Root { .. } => None,
+            Context::Child {
+                cancellation, ..
+            } => *cancellation,
+        }
+    }
+
+    /// Returns `true` if the evaluation has been cancelled, see [`Program::execute_cancellable`].
+    ///
+    /// [`Program::execute_cancellable`]: crate::Program::execute_cancellable
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation()
+            .is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+    }
+
+    /// Checks that the evaluation can run another iteration of a comprehension: that it's not
+    /// cancelled, and doesn't exceed its [`EvaluationLimits`].
+    pub(crate) fn check_iteration(&self) -> Result<(), ExecutionError> {
+        if self.is_cancelled() {
+            return Err(ExecutionError::Cancelled);
+        }
+        match self.budget() {
+            Some(budget) => budget.step(),
+            None => Ok(()),
         }
     }
 
@@ -159,6 +760,11 @@ impl Context<'_> {
         Context::Root {
             variables: Default::default(),
             functions: Default::default(),
+            declarations: Default::default(),
+            variable_resolver: None,
+            #[cfg(feature = "regex")]
+            regex_limits: Default::default(),
+            evaluation_limits: Default::default(),
         }
     }
 }
@@ -168,20 +774,52 @@ impl Default for Context<'_> {
         let mut ctx = Context::Root {
             variables: Default::default(),
             functions: Default::default(),
+            declarations: Default::default(),
+            variable_resolver: None,
+            #[cfg(feature = "regex")]
+            regex_limits: Default::default(),
+            evaluation_limits: Default::default(),
         };
 
         ctx.add_function("contains", functions::contains);
         ctx.add_function("length", functions::length);
         ctx.add_function("max", functions::max);
         ctx.add_function("min", functions::min);
+        ctx.add_function("sum", functions::sum);
         ctx.add_function("starts_with", functions::starts_with);
         ctx.add_function("ends_with", functions::ends_with);
+        ctx.add_function("compareIgnoreCase", functions::compare_ignore_case);
+        ctx.add_function("lowerAscii", functions::lower_ascii);
+        ctx.add_function("upperAscii", functions::upper_ascii);
+        ctx.add_function("trim", functions::trim);
+        ctx.add_function("trimStart", functions::trim_start);
+        ctx.add_function("trimEnd", functions::trim_end);
+        ctx.add_function("substring", functions::substring);
+        ctx.add_function("indexOf", functions::index_of);
+        ctx.add_function("lastIndexOf", functions::last_index_of);
+        ctx.add_function("replace", functions::replace);
+        ctx.add_function("split", functions::split);
+        ctx.add_function("join", functions::join);
+        ctx.add_function("format", functions::format);
+        ctx.add_function("sort", functions::sort);
+        ctx.add_function("distinct", functions::distinct);
+        ctx.add_function("reverse", functions::reverse);
+        ctx.add_function(operators::SORT_BY_KEY, functions::sort_by_key);
+        ctx.add_function("append", functions::append);
+        ctx.add_function("push", functions::push);
+        ctx.add_function("put", functions::put);
+        ctx.add_function("union", functions::union);
+        ctx.add_function("intersection", functions::intersection);
+        ctx.add_function("difference", functions::difference);
+        ctx.add_function("getPath", functions::get_path);
+        ctx.add_function("type", functions::type_fn);
+        ctx.add_function("dyn", functions::dyn_fn);
 
         ctx.add_function("String", functions::string);
         ctx.add_function("Bytes", functions::bytes);
         ctx.add_function("Float", functions::float);
         ctx.add_function("Int", functions::int);
-        // ctx.add_function("Uint", functions::uint);
+        ctx.add_function("Uint", functions::uint);
 
         #[cfg(feature = "regex")]
         {
@@ -205,15 +843,258 @@ impl Default for Context<'_> {
             ctx.add_function("getDayOfMonth", functions::time::timestamp_month_day);
             ctx.add_function("getDate", functions::time::timestamp_date);
             ctx.add_function("getDayOfWeek", functions::time::timestamp_weekday);
-            ctx.add_function("getHours", functions::time::timestamp_hours);
-            ctx.add_function("getMinutes", functions::time::timestamp_minutes);
+            ctx.add_function("getHours", functions::time::hours);
+            ctx.add_function("getMinutes", functions::time::minutes);
+            ctx.add_function("getSeconds", functions::time::seconds);
+            ctx.add_function("getMilliseconds", functions::time::millis);
+            ctx.add_function("truncateToDay", functions::time::truncate_to_day);
+            ctx.add_function("truncateToHour", functions::time::truncate_to_hour);
+            ctx.add_function("truncateToMinute", functions::time::truncate_to_minute);
         }
 
         #[cfg(feature = "ip")]
         {
             ctx.add_function("Ip", functions::ip);
+            ctx.add_function("family", functions::ip_family);
+            ctx.add_function("prefixLength", functions::ip_prefix_length);
+            ctx.add_function("isLoopback", functions::ip_is_loopback);
+            ctx.add_function("isPrivate", functions::ip_is_private);
         }
 
         ctx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, HashMap},
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+    };
+
+    use serde::Serialize;
+
+    use crate::{Context, ExecutionError, Program, SerializationError, Value};
+
+    #[test]
+    fn call_function() {
+        let mut context = Context::default();
+        context.add_function("multiply", |a: i64, b: i64| a * b);
+        let list = Value::from(vec![1i64, 2, 3]);
+
+        assert_eq!(context.call_function("length", None, vec![list.clone()]), Ok(Value::Int(3)));
+        assert_eq!(context.call_function("length", Some(list.clone()), vec![]), Ok(Value::Int(3)));
+        assert_eq!(
+            context.call_function("multiply", None, vec![6.into(), 7.into()]),
+            Ok(Value::Int(42))
+        );
+        assert_eq!(
+            context.call_function("contains", Some(list.clone()), vec![2.into()]),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(
+            context.call_function("unknown", None, vec![list]),
+            Err(ExecutionError::undeclared_reference("unknown"))
+        );
+
+        // a function value and its target
+        let function = Value::Function(Arc::new("length".to_string()), Some(Box::new(list)));
+        let Value::Function(name, target) = function else {
+            unreachable!()
+        };
+        assert_eq!(context.call_function(&name, target.map(|t| *t), vec![]), Ok(Value::Int(3)));
+    }
+
+    #[test]
+    fn remove_variable() {
+        let mut context = Context::default();
+        context.add_variable_from_value("a", 1);
+        context.add_variable_from_value("b", 2);
+        assert_eq!(context.variable_names(), vec!["a", "b"]);
+
+        assert_eq!(context.remove_variable("a"), Some(Value::Int(1)));
+        assert_eq!(context.remove_variable("a"), None);
+        assert_eq!(context.variable_names(), vec!["b"]);
+        assert_eq!(context.get_variable("a"), Err(ExecutionError::undeclared_reference("a")));
+
+        // functions are kept
+        context.add_variable_from_value("a", 3);
+        let program = Program::compile("length([a, b])").unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Int(2)));
+        assert_eq!(context.get_variable("a"), Ok(Value::Int(3)));
+    }
+
+    #[test]
+    fn remove_shadowed_variable() {
+        let mut context = Context::default();
+        context.add_variable_from_value("a", 1);
+        context.add_variable_from_value("b", 2);
+
+        let mut child = context.new_inner_scope();
+        child.add_variable_from_value("a", 10);
+        child.add_variable_from_value("c", 3);
+        assert_eq!(child.variable_names(), vec!["a", "b", "c"]);
+        assert_eq!(child.get_variable("a"), Ok(Value::Int(10)));
+
+        // the variable of the parent is visible again
+        assert_eq!(child.remove_variable("a"), Some(Value::Int(10)));
+        assert_eq!(child.get_variable("a"), Ok(Value::Int(1)));
+        assert_eq!(child.variable_names(), vec!["a", "b", "c"]);
+
+        // and can't be removed from the child
+        assert_eq!(child.remove_variable("a"), None);
+        assert_eq!(child.remove_variable("b"), None);
+        assert_eq!(child.get_variable("a"), Ok(Value::Int(1)));
+    }
+
+    #[test]
+    fn add_function_checked() {
+        let mut context = Context::default();
+        assert!(context.has_function("length"));
+        assert!(!context.has_function("double"));
+
+        assert!(!context.add_function_checked("length", |_: i64| 42));
+        // the built-in is kept
+        let program = Program::compile("length([1, 2, 3])").unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Int(3)));
+
+        assert!(context.add_function_checked("double", |a: i64| a * 2));
+        assert!(context.has_function("double"));
+        assert!(!context.add_function_checked("double", |a: i64| a * 3));
+        let program = Program::compile("double(2)").unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Int(4)));
+
+        // `add_function` still overwrites
+        context.add_function("length", |_: i64| 42);
+        let program = Program::compile("length(1)").unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Int(42)));
+    }
+
+    #[test]
+    fn has_function_in_child() {
+        let mut context = Context::default();
+        context.add_function("double", |a: i64| a * 2);
+
+        let mut child = context.new_inner_scope();
+        assert!(child.has_function("double"));
+        assert!(!child.add_function_checked("triple", |a: i64| a * 3));
+        assert!(!child.has_function("triple"));
+    }
+
+    #[test]
+    fn variable_resolver() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut context = Context::default();
+        context.add_variable_from_value("a", 1);
+        context.set_variable_resolver({
+            let calls = calls.clone();
+            move |name| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                (name == "b").then_some(Value::Int(2))
+            }
+        });
+
+        // `b` is supplied only by the resolver, and is resolved only once per evaluation
+        let program = Program::compile("a + b + b").unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Int(5)));
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert_eq!(program.execute(&context), Ok(Value::Int(5)));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        // variables added to the context don't call the resolver
+        let program = Program::compile("a").unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Int(1)));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        // child contexts fall back to the resolver of their root
+        let program = Program::compile("[1, 2].map(x, x * b)").unwrap();
+        assert_eq!(program.execute(&context), Ok(vec![Value::Int(2), Value::Int(4)].into()));
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn add_variables() {
+        let mut context = Context::default();
+        context.add_variables(HashMap::from([
+            ("a".to_string(), Value::Int(1)),
+            ("b".to_string(), Value::from("two")),
+        ]));
+        context.add_variables([("c", 3.5)]);
+
+        let program = Program::compile(r#"a == 1 && b == "two" && c == 3.5"#).unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Bool(true)));
+    }
+
+    #[test]
+    fn add_variables_from_serialize() {
+        #[derive(Serialize)]
+        struct Request {
+            method: &'static str,
+            path: &'static str,
+            headers: HashMap<&'static str, &'static str>,
+            size: u64,
+        }
+
+        let mut context = Context::default();
+        context
+            .add_variables_from_serialize(Request {
+                method: "GET",
+                path: "/index.html",
+                headers: HashMap::from([("host", "example.com")]),
+                size: 42,
+            })
+            .unwrap();
+
+        let program = Program::compile(
+            r#"method == "GET" && path == "/index.html" && headers.host == "example.com" && size == 42"#,
+        )
+        .unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Bool(true)));
+
+        // maps work too, but their keys must be strings
+        context
+            .add_variables_from_serialize(HashMap::from([("method", "POST")]))
+            .unwrap();
+        let program = Program::compile(r#"method == "POST""#).unwrap();
+        assert_eq!(program.execute(&context), Ok(Value::Bool(true)));
+        assert_eq!(
+            context.add_variables_from_serialize(HashMap::from([(1, "one")])),
+            Err(SerializationError::InvalidKey("invalid variable name: 1".to_string()))
+        );
+
+        // an invalid key doesn't add the valid ones
+        #[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+        #[serde(untagged)]
+        enum Name {
+            String(&'static str),
+            Int(i64),
+        }
+        assert_eq!(
+            context.add_variables_from_serialize(BTreeMap::from([(Name::String("fresh"), 1), (Name::Int(2), 2)])),
+            Err(SerializationError::InvalidKey("invalid variable name: 2".to_string()))
+        );
+        assert!(context.get_variable("fresh").is_err());
+
+        assert_eq!(
+            context.add_variables_from_serialize(42),
+            Err(SerializationError::SerdeError(
+                "variables must be serialized as a map, got int".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn variable_resolver_returns_none() {
+        let mut context = Context::default();
+        context.set_variable_resolver(|_| None);
+
+        let program = Program::compile("c").unwrap();
+        assert_eq!(
+            program.execute(&context),
+            Err(ExecutionError::UndeclaredReference("c".to_string().into()))
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{
+    common::ast::{EntryExpr, Expr, IdedEntryExpr},
+    parser::Expression,
+};
+
+/// Cost of evaluating a literal, reading a variable or selecting a field.
+const ACCESS_COST: u64 = 1;
+
+/// Cost of a function call or an operator, not including the cost of its arguments.
+const CALL_COST: u64 = 1;
+
+/// Number of iterations assumed for comprehensions over a range whose size is not known
+/// statically, for example a variable.
+const ASSUMED_ITERATIONS: u64 = 100;
+
+/// Returns a static upper bound of the number of operations needed to evaluate `expr`.
+///
+/// Each node of the expression has a cost, and the loop condition and step of comprehensions
+/// (generated by macros such as `all` or `map`) are multiplied by the number of iterations: the
+/// length of the range if it is a list or map literal, or [`ASSUMED_ITERATIONS`] otherwise. The
+/// arithmetic saturates, so the cost of pathological expressions is `u64::MAX`.
+pub(crate) fn estimate(expr: &Expression) -> u64 {
+    match &expr.expr {
+        Expr::Literal(_) | Expr::Ident(_) | Expr::Unspecified => ACCESS_COST,
+        Expr::Select(select) => ACCESS_COST.saturating_add(estimate(&select.operand)),
+        Expr::List(list) => list
+            .elements
+            .iter()
+            .fold(ACCESS_COST, |cost, element| cost.saturating_add(estimate(element))),
+        Expr::Map(map) => ACCESS_COST.saturating_add(entries(&map.entries)),
+        Expr::Struct(structure) => ACCESS_COST.saturating_add(entries(&structure.entries)),
+        Expr::Call(call) => {
+            let target = call.target.as_deref().map(estimate).unwrap_or(0);
+            call.args
+                .iter()
+                .fold(CALL_COST.saturating_add(target), |cost, arg| cost.saturating_add(estimate(arg)))
+        }
+        Expr::Comprehension(comprehension) => {
+            let iterations = match &comprehension.iter_range.expr {
+                Expr::List(list) => list.elements.len() as u64,
+                Expr::Map(map) => map.entries.len() as u64,
+                _ => ASSUMED_ITERATIONS,
+            };
+            let iteration = estimate(&comprehension.loop_cond).saturating_add(estimate(&comprehension.loop_step));
+
+            estimate(&comprehension.iter_range)
+                .saturating_add(estimate(&comprehension.accu_init))
+                .saturating_add(iterations.saturating_mul(iteration))
+                .saturating_add(estimate(&comprehension.result))
+        }
+    }
+}
+
+fn entries(entries: &[IdedEntryExpr]) -> u64 {
+    entries.iter().fold(0, |cost, entry| match &entry.expr {
+        EntryExpr::MapEntry(entry) => cost
+            .saturating_add(estimate(&entry.key))
+            .saturating_add(estimate(&entry.value)),
+        EntryExpr::StructField(field) => cost.saturating_add(estimate(&field.value)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Program;
+
+    fn cost(script: &str) -> u64 {
+        Program::compile(script).unwrap().cost()
+    }
+
+    #[test]
+    fn flat_expressions() {
+        assert_eq!(cost("1"), 1);
+        assert_eq!(cost("a"), 1);
+        // one call and two literals
+        assert_eq!(cost("1 + 2"), 3);
+        assert!(cost("1 + 2 * 3 - 4") > cost("1 + 2"));
+        assert!(cost("a.b.c") > cost("a"));
+    }
+
+    #[test]
+    fn comprehensions() {
+        let flat = cost("a + b * c - d / e % f");
+        let comprehension = cost("items.all(x, x > 0)");
+        let nested = cost("items.all(x, x.values.all(y, y > 0))");
+
+        assert!(comprehension > flat);
+        assert!(nested > comprehension);
+        // the inner comprehension is evaluated at each iteration of the outer one
+        assert!(nested > 50 * comprehension);
+    }
+
+    #[test]
+    fn literal_ranges() {
+        let small = cost("[1, 2].map(x, x * 2)");
+        let large = cost("[1, 2, 3, 4, 5, 6, 7, 8].map(x, x * 2)");
+        let unknown = cost("items.map(x, x * 2)");
+
+        assert!(small < large);
+        assert!(large < unknown);
+    }
+}
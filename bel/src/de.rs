@@ -0,0 +1,401 @@
+// The reverse of `ser.rs`: deserializes our Value enum into any Rust type, similarly to
+// [serde_json](https://github.com/serde-rs/json/blob/master/src/value/de.rs).
+
+use std::{fmt::Display, sync::Arc};
+
+use serde::{
+    de::{
+        self, DeserializeOwned, IntoDeserializer, Visitor,
+        value::{MapDeserializer, SeqDeserializer},
+    },
+    forward_to_deserialize_any,
+};
+use thiserror::Error;
+
+use crate::{Value, objects::Key};
+
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum DeserializationError {
+    SerdeError(String),
+}
+
+impl de::Error for DeserializationError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        DeserializationError::SerdeError(msg.to_string())
+    }
+}
+
+impl Display for DeserializationError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeserializationError::SerdeError(msg) => formatter.write_str(msg),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, DeserializationError>;
+
+/// Deserializes a [`Value`], for example the result of a program, into any type implementing
+/// [`serde::Deserialize`]. This is the reverse of [`to_value`](crate::to_value).
+///
+/// Maps are deserialized like JSON objects, and enums are externally tagged. The types that serde
+/// can't represent are deserialized as strings: durations like `1h30m0s`, timestamps in RFC 3339,
+/// regexes as their pattern and IPs in CIDR notation. Functions can't be deserialized.
+///
+/// # Example
+/// ```
+/// use bel::{Context, Program, from_value};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Upstream {
+///     host: String,
+///     ports: Vec<u16>,
+/// }
+///
+/// let program = Program::compile(r#"{"host": "example.com", "ports": [80, 443]}"#).unwrap();
+/// let value = program.execute(&Context::default()).unwrap();
+/// let upstream: Upstream = from_value(value).unwrap();
+/// assert_eq!(upstream, Upstream { host: "example.com".to_string(), ports: vec![80, 443] });
+/// ```
+pub fn from_value<T>(value: Value) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(value)
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = DeserializationError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::List(list) => {
+                let mut seq = SeqDeserializer::<_, DeserializationError>::new(Arc::unwrap_or_clone(list).into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Map(map) => {
+                let mut map =
+                    MapDeserializer::<_, DeserializationError>::new(Arc::unwrap_or_clone(map.map).into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+            Value::Function(name, _) => Err(de::Error::custom(format_args!("cannot deserialize function {name}"))),
+            Value::Int(v) => visitor.visit_i64(v),
+            Value::UInt(v) => visitor.visit_u64(v),
+            Value::Float(v) => visitor.visit_f64(v),
+            Value::String(v) => visitor.visit_string(Arc::unwrap_or_clone(v)),
+            Value::Bytes(v) => visitor.visit_byte_buf(Arc::unwrap_or_clone(v)),
+            Value::Bool(v) => visitor.visit_bool(v),
+            #[cfg(feature = "time")]
+            Value::Duration(v) => visitor.visit_string(crate::duration::format_duration(&v)),
+            #[cfg(feature = "time")]
+            Value::Timestamp(v) => visitor.visit_string(v.to_rfc3339()),
+            #[cfg(feature = "regex")]
+            Value::Regex(v) => visitor.visit_str(v.as_str()),
+            #[cfg(feature = "ip")]
+            Value::Ip(v) => visitor.visit_string(v.to_string()),
+            Value::Null => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            // unit variants are serialized as their name, the others as a map with a single entry
+            Value::String(variant) => visitor.visit_enum(Arc::unwrap_or_clone(variant).into_deserializer()),
+            Value::Map(map) if map.map.len() == 1 => {
+                let (variant, value) = Arc::unwrap_or_clone(map.map).into_iter().next().unwrap();
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value,
+                })
+            }
+            value => Err(de::Error::custom(format_args!(
+                "expected a string or a map with a single entry for an enum, got {}",
+                value.type_of()
+            ))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeserializationError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Key {
+    type Error = DeserializationError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Key::Int(v) => visitor.visit_i64(v),
+            Key::Uint(v) => visitor.visit_u64(v),
+            Key::Bool(v) => visitor.visit_bool(v),
+            Key::String(v) => visitor.visit_string(Arc::unwrap_or_clone(v)),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Key::String(variant) => visitor.visit_enum(Arc::unwrap_or_clone(variant).into_deserializer()),
+            key => Err(de::Error::custom(format_args!("expected a string for an enum, got {key}"))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeserializationError> for Key {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+struct EnumDeserializer {
+    variant: Key,
+    value: Value,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = DeserializationError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, VariantDeserializer)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.variant)?;
+        Ok((variant, VariantDeserializer(self.value)))
+    }
+}
+
+struct VariantDeserializer(Value);
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = DeserializationError;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self.0)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.0)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self.0, visitor)
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_map(self.0, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Serialize};
+    use serde_bytes::ByteBuf;
+
+    use super::DeserializationError;
+    use crate::{Context, Program, Value, from_value, to_value};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Action {
+        Allow,
+        Redirect(String),
+        Limit(u32, u32),
+        Rewrite { path: String, query: Option<String> },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Rule {
+        name: String,
+        enabled: bool,
+        priority: i8,
+        weight: f64,
+        initial: char,
+        tags: Vec<String>,
+        headers: HashMap<String, Vec<String>>,
+        codes: HashMap<u16, String>,
+        body: ByteBuf,
+        timeout: Option<u64>,
+        actions: Vec<Action>,
+        nested: Option<Box<Rule>>,
+    }
+
+    fn rule(name: &str) -> Rule {
+        Rule {
+            name: name.to_string(),
+            enabled: true,
+            priority: -3,
+            weight: 0.5,
+            initial: 'é',
+            tags: vec!["a".to_string(), "b".to_string()],
+            headers: HashMap::from([("accept".to_string(), vec!["text/html".to_string()])]),
+            codes: HashMap::from([(404, "not found".to_string())]),
+            body: ByteBuf::from(vec![0, 1, 255]),
+            timeout: None,
+            actions: vec![
+                Action::Allow,
+                Action::Redirect("/login".to_string()),
+                Action::Limit(10, 60),
+                Action::Rewrite {
+                    path: "/".to_string(),
+                    query: Some("a=b".to_string()),
+                },
+            ],
+            nested: None,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut original = rule("outer");
+        original.timeout = Some(30);
+        original.nested = Some(Box::new(rule("inner")));
+
+        let value = to_value(&original).unwrap();
+        let deserialized: Rule = from_value(value.clone()).unwrap();
+        assert_eq!(deserialized, original);
+        assert_eq!(to_value(&deserialized).unwrap(), value);
+
+        let primitives: (i64, u64, String, (), Vec<bool>) =
+            from_value(to_value((1, 2u8, "s", (), [true])).unwrap()).unwrap();
+        assert_eq!(primitives, (1, 2, "s".to_string(), (), vec![true]));
+    }
+
+    #[test]
+    fn program_result() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Decision {
+            allow: bool,
+            reason: Option<String>,
+            action: Action,
+        }
+
+        let program =
+            Program::compile(r#"{"allow": 1 < 2, "reason": null, "action": {"Redirect": "/" + "x"}}"#).unwrap();
+        let decision: Decision = from_value(program.execute(&Context::default()).unwrap()).unwrap();
+        assert_eq!(
+            decision,
+            Decision {
+                allow: true,
+                reason: None,
+                action: Action::Redirect("/x".to_string()),
+            }
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_as_strings() {
+        use crate::{Duration, Timestamp};
+
+        let timestamp = chrono::DateTime::parse_from_rfc3339("2025-01-01T12:30:00+02:00").unwrap();
+        let value = to_value(Timestamp(timestamp)).unwrap();
+        assert_eq!(from_value::<String>(value), Ok("2025-01-01T12:30:00+02:00".to_string()));
+
+        let value = to_value(Duration(chrono::Duration::minutes(90))).unwrap();
+        assert_eq!(from_value::<String>(value), Ok("1h30m0s".to_string()));
+    }
+
+    #[cfg(feature = "ip")]
+    #[test]
+    fn ip_as_string() {
+        let value = to_value(crate::Ip("10.0.0.0/8".parse().unwrap())).unwrap();
+        assert_eq!(from_value::<String>(value), Ok("10.0.0.0/8".to_string()));
+    }
+
+    #[test]
+    fn errors() {
+        let err = |message: &str| Err(DeserializationError::SerdeError(message.to_string()));
+
+        assert_eq!(from_value::<i64>("x".into()), err("invalid type: string \"x\", expected i64"));
+        assert_eq!(
+            from_value::<u8>(Value::Int(300)),
+            err("invalid value: integer `300`, expected u8")
+        );
+        assert_eq!(
+            from_value::<(i64, i64)>(vec![1i64].into()),
+            err("invalid length 1, expected a tuple of size 2")
+        );
+        assert_eq!(
+            from_value::<Action>(Value::Null),
+            err("expected a string or a map with a single entry for an enum, got null")
+        );
+        assert_eq!(
+            from_value::<String>(Value::Function(std::sync::Arc::new("size".to_string()), None)),
+            err("cannot deserialize function size")
+        );
+    }
+}
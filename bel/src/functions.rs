@@ -4,7 +4,7 @@ use crate::{
     ExecutionError,
     context::Context,
     magic::{Arguments, This},
-    objects::Value,
+    objects::{Key, Value},
     parser::Expression,
     resolvers::Resolver,
 };
@@ -64,7 +64,7 @@ impl<'context> FunctionContext<'context> {
 /// The following [`Value`] variants are supported:
 /// * [`Value::List`]
 /// * [`Value::Map`]
-/// * [`Value::String`]
+/// * [`Value::String`] - The number of characters, like the indexes of `str[i]` and `substring`.
 /// * [`Value::Bytes`]
 ///
 /// # Examples
@@ -78,7 +78,7 @@ pub fn length(ftx: &FunctionContext, This(this): This<Value>) -> Result<i64> {
     let length = match this {
         Value::List(l) => l.len(),
         Value::Map(m) => m.map.len(),
-        Value::String(s) => s.len(),
+        Value::String(s) => s.chars().count(),
         Value::Bytes(b) => b.len(),
         value => return Err(ftx.error(format!("cannot determine the length of {value:?}"))),
     };
@@ -153,6 +153,31 @@ pub fn contains(This(this): This<Value>, arg: Value) -> Result<Value> {
     .into())
 }
 
+/// Returns the name of the runtime type of a value, as displayed by [`ValueType`], e.g. `"int"`,
+/// `"list"` or `"map"`.
+///
+/// [`ValueType`]: crate::objects::ValueType
+///
+/// # Example
+/// ```cel
+/// type(1) == "int"
+/// type({"a": 1}) == "map"
+/// ```
+pub fn type_fn(value: Value) -> Result<Value> {
+    Ok(Value::String(value.type_of().to_string().into()))
+}
+
+/// Returns its argument unchanged. In cel-go, `dyn` erases the static type of a value for the
+/// type checker, so this function lets such scripts run unmodified.
+///
+/// # Example
+/// ```cel
+/// dyn([1, 2]) == [1, 2]
+/// ```
+pub fn dyn_fn(value: Value) -> Result<Value> {
+    Ok(value)
+}
+
 // Performs a type conversion on the target. The following conversions are currently
 // supported:
 // * `string` - Returns a copy of the target string.
@@ -170,7 +195,7 @@ pub fn string(ftx: &FunctionContext, value: Value) -> Result<Value> {
         #[cfg(feature = "time")]
         Value::Duration(v) => Value::String(crate::duration::format_duration(&v).into()),
         Value::Int(v) => Value::String(v.to_string().into()),
-        // Value::UInt(v) => Value::String(v.to_string().into()),
+        Value::UInt(v) => Value::String(v.to_string().into()),
         Value::Float(v) => Value::String(v.to_string().into()),
         Value::Bytes(v) => Value::String(Arc::new(String::from_utf8_lossy(v.as_slice()).into())),
         #[cfg(feature = "regex")]
@@ -194,38 +219,35 @@ pub fn float(ftx: &FunctionContext, value: Value) -> Result<Value> {
             .map_err(|e| ftx.error(format!("string parse error: {e}")))?,
         Value::Float(v) => Value::Float(v),
         Value::Int(v) => Value::Float(v as f64),
-        // Value::UInt(v) => Value::Float(v as f64),
+        Value::UInt(v) => Value::Float(v as f64),
         v => return Err(ftx.error(format!("cannot convert {v:?} to Float"))),
     })
 }
 
 // Performs a type conversion on the target.
-// pub fn uint(ftx: &FunctionContext, value: Value) -> Result<Value> {
-//     Ok(match value {
-//         Value::String(v) => v
-//             .parse::<u64>()
-//             .map(Value::UInt)
-//             .map_err(|e| ftx.error(format!("string parse error: {e}")))?,
-//         Value::Float(v) => {
-//             if v > u64::MAX as f64 || v < u64::MIN as f64 {
-//                 return Err(ftx.error("unsigned integer overflow"));
-//             }
-//             Value::UInt(v as u64)
-//         }
-//         Value::Int(v) => Value::UInt(
-//             v.try_into()
-//                 .map_err(|_| ftx.error("unsigned integer overflow"))?,
-//         ),
-//         Value::UInt(v) => Value::UInt(v),
-//         v => return Err(ftx.error(format!("cannot convert {v:?} to uint"))),
-//     })
-// }
+pub fn uint(ftx: &FunctionContext, value: Value) -> Result<Value> {
+    Ok(match value {
+        Value::String(v) => v
+            .parse::<u64>()
+            .map(Value::UInt)
+            .map_err(|e| ftx.error(format!("string parse error: {e}")))?,
+        Value::Float(v) => {
+            if v > u64::MAX as f64 || v < u64::MIN as f64 {
+                return Err(ftx.error("unsigned integer overflow"));
+            }
+            Value::UInt(v as u64)
+        }
+        Value::Int(v) => Value::UInt(v.try_into().map_err(|_| ftx.error("unsigned integer overflow"))?),
+        Value::UInt(v) => Value::UInt(v),
+        v => return Err(ftx.error(format!("cannot convert {v:?} to uint"))),
+    })
+}
 
-// Performs a type conversion on the target.
+// Performs a type conversion on the target. Strings are trimmed, and may be written in
+// hexadecimal with a `0x` prefix.
 pub fn int(ftx: &FunctionContext, value: Value) -> Result<Value> {
     Ok(match value {
-        Value::String(v) => v
-            .parse::<i64>()
+        Value::String(v) => parse_int(v.trim())
             .map(Value::Int)
             .map_err(|e| ftx.error(format!("string parse error: {e}")))?,
         Value::Float(v) => {
@@ -235,11 +257,24 @@ pub fn int(ftx: &FunctionContext, value: Value) -> Result<Value> {
             Value::Int(v as i64)
         }
         Value::Int(v) => Value::Int(v),
-        // Value::UInt(v) => Value::Int(v.try_into().map_err(|_| ftx.error("integer overflow"))?),
+        Value::UInt(v) => Value::Int(v.try_into().map_err(|_| ftx.error("integer overflow"))?),
         v => return Err(ftx.error(format!("cannot convert {v:?} to int"))),
     })
 }
 
+/// Parses a decimal integer, or a hexadecimal one with a `0x` prefix, both with an optional sign.
+fn parse_int(s: &str) -> std::result::Result<i64, std::num::ParseIntError> {
+    let unsigned = s.strip_prefix(['-', '+']).unwrap_or(s);
+    match unsigned.strip_prefix("0x").or_else(|| unsigned.strip_prefix("0X")) {
+        // the sign is kept so that `-0x8000000000000000` doesn't overflow
+        Some(hex) if !hex.starts_with(['-', '+']) => {
+            let sign = &s[..s.len() - unsigned.len()];
+            i64::from_str_radix(&format!("{sign}{hex}"), 16)
+        }
+        _ => s.parse(),
+    }
+}
+
 /// Returns true if a string starts with another string.
 ///
 /// # Example
@@ -260,6 +295,531 @@ pub fn ends_with(This(this): This<Arc<String>>, suffix: Arc<String>) -> bool {
     this.ends_with(suffix.as_str())
 }
 
+/// Compares two strings, ignoring the case of ASCII letters. Returns `-1` if the first string is
+/// less than the second one, `0` if they are equal and `1` if it is greater. Non-ASCII characters
+/// are compared as-is.
+///
+/// # Example
+/// ```cel
+/// compareIgnoreCase("A", "a") == 0
+/// "Apple".compareIgnoreCase("banana") == -1
+/// ```
+pub fn compare_ignore_case(This(this): This<Arc<String>>, other: Arc<String>) -> i64 {
+    let this = this.bytes().map(|b| b.to_ascii_lowercase());
+    let other = other.bytes().map(|b| b.to_ascii_lowercase());
+    match this.cmp(other) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
+
+/// Converts the ASCII letters of a string to lowercase. Non-ASCII characters are left untouched.
+///
+/// # Example
+/// ```cel
+/// "ABC".lowerAscii() == "abc"
+/// "ÀB".lowerAscii() == "Àb"
+/// ```
+pub fn lower_ascii(This(this): This<Arc<String>>) -> Value {
+    Value::String(Arc::new(this.to_ascii_lowercase()))
+}
+
+/// Converts the ASCII letters of a string to uppercase. Non-ASCII characters are left untouched.
+///
+/// # Example
+/// ```cel
+/// "abc".upperAscii() == "ABC"
+/// "àb".upperAscii() == "àB"
+/// ```
+pub fn upper_ascii(This(this): This<Arc<String>>) -> Value {
+    Value::String(Arc::new(this.to_ascii_uppercase()))
+}
+
+/// Removes the leading and trailing whitespace of a string, as defined by the Unicode
+/// `White_Space` property.
+///
+/// # Example
+/// ```cel
+/// "  hello world\n".trim() == "hello world"
+/// ```
+pub fn trim(This(this): This<Arc<String>>) -> Value {
+    trimmed(&this, this.trim())
+}
+
+/// Removes the leading whitespace of a string, as defined by the Unicode `White_Space` property.
+///
+/// # Example
+/// ```cel
+/// "  hello ".trimStart() == "hello "
+/// ```
+pub fn trim_start(This(this): This<Arc<String>>) -> Value {
+    trimmed(&this, this.trim_start())
+}
+
+/// Removes the trailing whitespace of a string, as defined by the Unicode `White_Space` property.
+///
+/// # Example
+/// ```cel
+/// "  hello ".trimEnd() == "  hello"
+/// ```
+pub fn trim_end(This(this): This<Arc<String>>) -> Value {
+    trimmed(&this, this.trim_end())
+}
+
+/// Returns `trimmed`, a substring of `this`, reusing the allocation of `this` if nothing was
+/// trimmed.
+fn trimmed(this: &Arc<String>, trimmed: &str) -> Value {
+    if trimmed.len() == this.len() {
+        Value::String(this.clone())
+    } else {
+        trimmed.into()
+    }
+}
+
+/// Splits a string into a list of strings around each occurrence of a separator. An empty
+/// separator splits the string into its characters.
+///
+/// Like in cel-go, splitting an empty string returns a list containing an empty string, unless
+/// the separator is empty too.
+///
+/// # Example
+/// ```cel
+/// "a,b,c".split(",") == ["a", "b", "c"]
+/// "abc".split("") == ["a", "b", "c"]
+/// ```
+pub fn split(This(this): This<Arc<String>>, separator: Arc<String>) -> Value {
+    let parts: Vec<Value> = if separator.is_empty() {
+        this.chars().map(|c| c.to_string().into()).collect()
+    } else {
+        this.split(separator.as_str()).map(Value::from).collect()
+    };
+    Value::List(Arc::new(parts))
+}
+
+/// Returns the part of a string between a start index (inclusive) and an optional end index
+/// (exclusive), which defaults to the length of the string. Indices are counted in characters,
+/// not in bytes. Returns an error if the indices are out of range.
+///
+/// # Example
+/// ```cel
+/// "tacocat".substring(4) == "cat"
+/// "tacocat".substring(0, 4) == "taco"
+/// ```
+pub fn substring(ftx: &FunctionContext, This(this): This<Arc<String>>, Arguments(args): Arguments) -> Result<Value> {
+    // when called as a function, the target string is the first argument
+    let args = if ftx.this.is_some() { &args[..] } else { &args[1..] };
+    let length = this.chars().count() as i64;
+    let (start, end) = match args {
+        [Value::Int(start)] => (*start, length),
+        [Value::Int(start), Value::Int(end)] => (*start, *end),
+        [_] | [_, _] => return Err(ftx.error(format!("invalid arguments {args:?}"))),
+        _ => return Err(ftx.error(format!("expected 1 or 2 arguments, got {}", args.len()))),
+    };
+
+    if start < 0 || start > length {
+        return Err(ftx.error(format!("index out of range: {start}")));
+    }
+    if end < 0 || end > length {
+        return Err(ftx.error(format!("index out of range: {end}")));
+    }
+    if start > end {
+        return Err(ftx.error(format!("invalid substring range: start {start}, end {end}")));
+    }
+
+    let start = byte_index(&this, start as usize);
+    let end = byte_index(&this, end as usize);
+    Ok(this[start..end].into())
+}
+
+/// Returns the index, in characters, of the first occurrence of a substring, or -1 if the string
+/// doesn't contain it.
+///
+/// # Example
+/// ```cel
+/// "tacocat".indexOf("cat") == 4
+/// "tacocat".indexOf("dog") == -1
+/// ```
+pub fn index_of(This(this): This<Arc<String>>, substring: Arc<String>) -> i64 {
+    match this.find(substring.as_str()) {
+        Some(idx) => this[..idx].chars().count() as i64,
+        None => -1,
+    }
+}
+
+/// Returns the index, in characters, of the last occurrence of a substring, or -1 if the string
+/// doesn't contain it.
+///
+/// # Example
+/// ```cel
+/// "tacocat".lastIndexOf("a") == 5
+/// "tacocat".lastIndexOf("dog") == -1
+/// ```
+pub fn last_index_of(This(this): This<Arc<String>>, substring: Arc<String>) -> i64 {
+    match this.rfind(substring.as_str()) {
+        Some(idx) => this[..idx].chars().count() as i64,
+        None => -1,
+    }
+}
+
+/// Converts an index in characters into an index in bytes. `idx` must not be greater than the
+/// number of characters of `s`.
+fn byte_index(s: &str, idx: usize) -> usize {
+    s.char_indices().nth(idx).map_or(s.len(), |(i, _)| i)
+}
+
+/// Replaces the occurrences of a substring with another string. An optional third argument
+/// limits the number of replacements, starting from the beginning of the string. Like in cel-go,
+/// a negative limit replaces all the occurrences.
+///
+/// # Example
+/// ```cel
+/// "hello".replace("l", "L") == "heLLo"
+/// "aaa".replace("a", "b", 2) == "bba"
+/// "a-b-c".replace("-", "") == "abc"
+/// ```
+pub fn replace(ftx: &FunctionContext, This(this): This<Arc<String>>, Arguments(args): Arguments) -> Result<Value> {
+    // when called as a function, the target string is the first argument
+    let args = if ftx.this.is_some() { &args[..] } else { &args[1..] };
+    let (from, to, limit) = match args {
+        [Value::String(from), Value::String(to)] => (from, to, -1),
+        [Value::String(from), Value::String(to), Value::Int(limit)] => (from, to, *limit),
+        [_, _] | [_, _, _] => return Err(ftx.error(format!("invalid arguments {args:?}"))),
+        _ => return Err(ftx.error(format!("expected 2 or 3 arguments, got {}", args.len()))),
+    };
+
+    let replaced = if limit < 0 {
+        this.replace(from.as_str(), to)
+    } else {
+        this.replacen(from.as_str(), to, limit as usize)
+    };
+    Ok(replaced.into())
+}
+
+/// Formats a string, substituting its verbs with the elements of a list, in order:
+/// * `%s`: a string, a bool, or any value that can be converted with `string()`
+/// * `%d`: an int or a uint
+/// * `%f`: a float, an int or a uint, with 6 decimals
+/// * `%%`: a literal `%`, without consuming an element
+///
+/// Returns an error if the number of verbs and elements differ, or if an element doesn't match
+/// its verb.
+///
+/// # Example
+/// ```cel
+/// "%s-%d".format(["a", 1]) == "a-1"
+/// "%f".format([1.5]) == "1.500000"
+/// "100%%".format([]) == "100%"
+/// ```
+pub fn format(ftx: &FunctionContext, This(this): This<Arc<String>>, args: Arc<Vec<Value>>) -> Result<Value> {
+    let mut formatted = String::with_capacity(this.len());
+    let mut values = args.iter();
+    let mut chars = this.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            formatted.push(c);
+            continue;
+        }
+        let verb = match chars.next() {
+            Some('%') => {
+                formatted.push('%');
+                continue;
+            }
+            Some(verb @ ('s' | 'd' | 'f')) => verb,
+            Some(verb) => return Err(ftx.error(format!("unsupported verb %{verb}"))),
+            None => return Err(ftx.error("missing verb after %")),
+        };
+        let value = values
+            .next()
+            .ok_or_else(|| ftx.error(format!("not enough arguments: got {}", args.len())))?;
+        match (verb, value) {
+            ('s', Value::String(s)) => formatted.push_str(s),
+            ('s', Value::Bool(b)) => formatted.push_str(if *b { "true" } else { "false" }),
+            ('s', Value::List(_) | Value::Map(_) | Value::Function(..) | Value::Null) => {
+                return Err(ftx.error(format!("invalid argument for %s: {value:?}")));
+            }
+            ('s', value) => match string(ftx, value.clone())? {
+                Value::String(s) => formatted.push_str(&s),
+                _ => unreachable!("string() returns a string"),
+            },
+            ('d', Value::Int(i)) => formatted.push_str(&i.to_string()),
+            ('d', Value::UInt(u)) => formatted.push_str(&u.to_string()),
+            ('f', Value::Float(f)) => formatted.push_str(&format!("{f:.6}")),
+            ('f', Value::Int(i)) => formatted.push_str(&format!("{:.6}", *i as f64)),
+            ('f', Value::UInt(u)) => formatted.push_str(&format!("{:.6}", *u as f64)),
+            (verb, value) => return Err(ftx.error(format!("invalid argument for %{verb}: {value:?}"))),
+        }
+    }
+    let unused = values.len();
+    if unused > 0 {
+        return Err(ftx.error(format!(
+            "too many arguments: expected {}, got {}",
+            args.len() - unused,
+            args.len()
+        )));
+    }
+    Ok(formatted.into())
+}
+
+/// Concatenates a list of strings, placing a separator between the elements. Returns an error
+/// if the target is not a list or if an element is not a string.
+///
+/// # Example
+/// ```cel
+/// ["a", "b"].join("-") == "a-b"
+/// [].join("-") == ""
+/// ```
+pub fn join(ftx: &FunctionContext, This(this): This<Value>, separator: Arc<String>) -> Result<Value> {
+    let items = match this {
+        Value::List(items) => items,
+        v => return Err(ftx.error(format!("cannot join {v:?}"))),
+    };
+
+    let mut joined = String::new();
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            joined.push_str(&separator);
+        }
+        match item {
+            Value::String(s) => joined.push_str(s),
+            v => return Err(ftx.error(format!("cannot join non-string element {v:?}"))),
+        }
+    }
+    Ok(joined.into())
+}
+
+/// Sorts a list in ascending order, using the same ordering as the `<` operator. The sort is
+/// stable. Returns a [`ExecutionError::ValuesNotComparable`] error if the elements can't be
+/// compared with each other, e.g. strings and ints.
+///
+/// # Example
+/// ```cel
+/// [3, 1, 2].sort() == [1, 2, 3]
+/// ["b", "a"].sort() == ["a", "b"]
+/// ```
+pub fn sort(ftx: &FunctionContext, This(this): This<Value>) -> Result<Value> {
+    let mut items = match this {
+        Value::List(items) => items,
+        v => return Err(ftx.error(format!("cannot sort {v:?}"))),
+    };
+    check_comparable(items.iter())?;
+
+    // the list is sorted in place if nothing else references it
+    Arc::make_mut(&mut items).sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Ok(Value::List(items))
+}
+
+/// Returns the elements of a list without duplicates, in the order in which they first appear.
+/// Elements are compared like with the `==` operator, so `1` and `1.0` are duplicates.
+///
+/// # Example
+/// ```cel
+/// [1, 1, 2].distinct() == [1, 2]
+/// ```
+pub fn distinct(ftx: &FunctionContext, This(this): This<Value>) -> Result<Value> {
+    let items = match this {
+        Value::List(items) => items,
+        v => return Err(ftx.error(format!("cannot deduplicate {v:?}"))),
+    };
+
+    // values are not all hashable (e.g. floats and lists), so the duplicates are searched
+    // linearly
+    let mut distinct: Vec<Value> = Vec::with_capacity(items.len());
+    for item in items.iter() {
+        if !distinct.contains(item) {
+            distinct.push(item.clone());
+        }
+    }
+    Ok(Value::List(Arc::new(distinct)))
+}
+
+/// Returns the elements of a list in reverse order.
+///
+/// # Example
+/// ```cel
+/// [1, 2, 3].reverse() == [3, 2, 1]
+/// ```
+pub fn reverse(ftx: &FunctionContext, This(this): This<Value>) -> Result<Value> {
+    let mut items = match this {
+        Value::List(items) => items,
+        v => return Err(ftx.error(format!("cannot reverse {v:?}"))),
+    };
+
+    // the list is reversed in place if nothing else references it
+    Arc::make_mut(&mut items).reverse();
+    Ok(Value::List(items))
+}
+
+/// Sorts the `[key, element]` pairs generated by the `sortBy` macro by key, and returns the
+/// elements.
+///
+/// # Example
+/// ```cel
+/// [{"n": 2}, {"n": 1}].sortBy(x, x.n) == [{"n": 1}, {"n": 2}]
+/// ```
+pub(crate) fn sort_by_key(ftx: &FunctionContext, This(this): This<Arc<Vec<Value>>>) -> Result<Value> {
+    let mut pairs = this
+        .iter()
+        .map(|pair| match pair {
+            Value::List(pair) if pair.len() == 2 => Ok((&pair[0], &pair[1])),
+            v => Err(ftx.error(format!("invalid sort pair {v:?}"))),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    check_comparable(pairs.iter().map(|(key, _)| *key))?;
+
+    pairs.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Ok(Value::List(Arc::new(pairs.into_iter().map(|(_, item)| item.clone()).collect())))
+}
+
+/// Returns an error if some of the `values` can't be compared with each other. Values that are
+/// comparable with the first one are comparable with each other, except NaN, which isn't
+/// comparable with anything.
+fn check_comparable<'a>(mut values: impl Iterator<Item = &'a Value>) -> Result<()> {
+    let Some(first) = values.next() else {
+        return Ok(());
+    };
+    for value in values {
+        if first.partial_cmp(value).is_none() {
+            return Err(ExecutionError::ValuesNotComparable(first.clone(), value.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Returns a list containing the elements of the target list followed by the elements of the
+/// argument list, like `list + other`.
+///
+/// The target list is extended in place if nothing else references it, and the elements of the
+/// argument list are moved instead of cloned if nothing else references it. Otherwise, the list
+/// is copied first and the original is left untouched.
+///
+/// # Example
+/// ```cel
+/// [1, 2, 3].append([4, 5]) == [1, 2, 3, 4, 5]
+/// ```
+pub fn append(This(mut this): This<Arc<Vec<Value>>>, mut other: Arc<Vec<Value>>) -> Arc<Vec<Value>> {
+    let list = Arc::make_mut(&mut this);
+    match Arc::get_mut(&mut other) {
+        Some(other) => list.append(other),
+        None => list.extend(other.iter().cloned()),
+    }
+    this
+}
+
+/// Returns a list containing the elements of the target list followed by the argument.
+///
+/// The target list is extended in place if nothing else references it, and copied otherwise.
+///
+/// # Example
+/// ```cel
+/// [1, 2, 3].push(4) == [1, 2, 3, 4]
+/// ```
+pub fn push(This(mut this): This<Arc<Vec<Value>>>, value: Value) -> Arc<Vec<Value>> {
+    Arc::make_mut(&mut this).push(value);
+    this
+}
+
+/// Returns a map containing the entries of the target map, with `key` set to `value`. An
+/// existing entry with the same key is replaced.
+///
+/// The target map is updated in place if nothing else references it, and copied otherwise.
+/// Returns an error if the target is not a map or if the key is not a valid map key.
+///
+/// # Example
+/// ```cel
+/// {"a": 1}.put("b", 2) == {"a": 1, "b": 2}
+/// put({"a": 1}, "a", 2) == {"a": 2}
+/// ```
+pub fn put(ftx: &FunctionContext, This(this): This<Value>, key: Value, value: Value) -> Result<Value> {
+    let Value::Map(mut map) = this else {
+        return Err(ftx.error(format!("cannot put a key into {this:?}")));
+    };
+    let key: Key = key.try_into().map_err(ExecutionError::UnsupportedKeyType)?;
+    Arc::make_mut(&mut map.map).insert(key, value);
+    Ok(Value::Map(map))
+}
+
+/// Returns the elements of the target list followed by the elements of the argument list that
+/// are not in the target list, treating both lists as sets.
+///
+/// Elements are compared with `==` and duplicates are removed, keeping the first occurrence.
+///
+/// # Example
+/// ```cel
+/// ["admin", "user"].union(["user", "guest"]) == ["admin", "user", "guest"]
+/// ```
+pub fn union(This(this): This<Arc<Vec<Value>>>, other: Arc<Vec<Value>>) -> Value {
+    set_operation(this.iter().chain(other.iter()), |_| true)
+}
+
+/// Returns the elements of the target list that are also in the argument list, treating both
+/// lists as sets.
+///
+/// Elements are compared with `==` and duplicates are removed, keeping the first occurrence.
+///
+/// # Example
+/// ```cel
+/// ["admin", "user"].intersection(["user", "guest"]) == ["user"]
+/// ```
+pub fn intersection(This(this): This<Arc<Vec<Value>>>, other: Arc<Vec<Value>>) -> Value {
+    set_operation(this.iter(), |value| other.contains(value))
+}
+
+/// Returns the elements of the target list that are not in the argument list, treating both
+/// lists as sets.
+///
+/// Elements are compared with `==` and duplicates are removed, keeping the first occurrence.
+///
+/// # Example
+/// ```cel
+/// ["admin", "user"].difference(["user", "guest"]) == ["admin"]
+/// ```
+pub fn difference(This(this): This<Arc<Vec<Value>>>, other: Arc<Vec<Value>>) -> Value {
+    set_operation(this.iter(), |value| !other.contains(value))
+}
+
+/// Walks a dotted path through nested maps and lists, returning `default` instead of an error if
+/// a segment is missing.
+///
+/// Each segment is looked up as a string key in maps, falling back to an integer key if the
+/// segment is a number, and as an index in lists. Indexing any other value returns `default`.
+///
+/// # Example
+/// ```cel
+/// {"a": {"b": [1, 2]}}.getPath("a.b.1", 0) == 2
+/// getPath({"a": {}}, "a.b.c", "none") == "none"
+/// ```
+pub fn get_path(This(this): This<Value>, path: Arc<String>, default: Value) -> Value {
+    let mut current = &this;
+    for segment in path.split('.') {
+        let next = match current {
+            Value::Map(map) => map.get(&Key::from(segment)).or_else(|| {
+                let index: i64 = segment.parse().ok()?;
+                map.get(&Key::Int(index))
+            }),
+            Value::List(list) => segment.parse::<usize>().ok().and_then(|index| list.get(index)),
+            _ => None,
+        };
+        match next {
+            Some(value) => current = value,
+            None => return default,
+        }
+    }
+    current.clone()
+}
+
+/// Collects the distinct values matching `keep`, in first-seen order. Values are not hashable
+/// (floats, lists, maps), so duplicates are detected with a linear scan.
+fn set_operation<'a>(values: impl Iterator<Item = &'a Value>, keep: impl Fn(&Value) -> bool) -> Value {
+    let mut set: Vec<Value> = Vec::new();
+    for value in values {
+        if keep(value) && !set.contains(value) {
+            set.push(value.clone());
+        }
+    }
+    Value::List(Arc::new(set))
+}
+
 /// Returns true if a string matches the regular expression.
 ///
 /// # Example
@@ -283,10 +843,26 @@ pub fn matches(
     // }
 }
 
+/// Compiles a string to a regular expression, within the [`RegexLimits`] of the context.
+///
+/// [`RegexLimits`]: crate::context::RegexLimits
+///
+/// # Example
+/// ```cel
+/// "abc".matches(Regex("^[a-z]*$")) == true
+/// ```
 #[cfg(feature = "regex")]
 pub fn regex(ftx: &FunctionContext, This(this): This<Value>) -> Result<Value> {
     Ok(match this {
-        Value::String(v) => Value::Regex(regex::Regex::new(v.as_str()).map_err(|e| ftx.error(e.to_string()))?),
+        Value::String(v) => {
+            let limits = ftx.ptx.regex_limits();
+            let regex = regex::RegexBuilder::new(v.as_str())
+                .size_limit(limits.size_limit)
+                .dfa_size_limit(limits.dfa_size_limit)
+                .build()
+                .map_err(|e| ftx.error(e.to_string()))?;
+            Value::Regex(regex)
+        }
         v => return Err(ftx.error(format!("cannot convert {v:?} to Regex"))),
     })
 }
@@ -311,17 +887,78 @@ pub fn ip(ftx: &FunctionContext, value: Value) -> Result<Value> {
     }
 }
 
-#[cfg(feature = "time")]
-pub mod time {
-    use std::sync::Arc;
-
-    use chrono::{Datelike, Days, Months, Timelike, Utc};
-
-    use super::Result;
-    use crate::{ExecutionError, Value, magic::This};
+/// Returns the IP version of an address or network, `4` or `6`.
+///
+/// # Example
+/// ```cel
+/// Ip("::1").family() == 6
+/// ```
+#[cfg(feature = "ip")]
+pub fn ip_family(This(this): This<ipnetwork::IpNetwork>) -> Result<i64> {
+    Ok(if this.is_ipv4() { 4 } else { 6 })
+}
 
-    /// Duration parses the provided argument into a [`Value::Duration`] value.
-    ///
+/// Returns the prefix length of a network, which is 32 for IPv4 addresses and 128 for IPv6
+/// addresses written without a prefix.
+///
+/// # Example
+/// ```cel
+/// Ip("10.0.0.0/8").prefixLength() == 8
+/// ```
+#[cfg(feature = "ip")]
+pub fn ip_prefix_length(This(this): This<ipnetwork::IpNetwork>) -> Result<i64> {
+    Ok(this.prefix() as i64)
+}
+
+/// Returns true if every address of the network is a loopback address: `127.0.0.0/8` for IPv4
+/// and `::1` for IPv6.
+///
+/// # Example
+/// ```cel
+/// Ip("127.0.0.1").isLoopback() == true
+/// ```
+#[cfg(feature = "ip")]
+pub fn ip_is_loopback(This(this): This<ipnetwork::IpNetwork>) -> Result<bool> {
+    // the ranges are networks, so they contain the whole network if they contain both its ends
+    Ok(ip_all(this, |ip| ip.is_loopback()))
+}
+
+/// Returns true if every address of the network is a private address: `10.0.0.0/8`,
+/// `172.16.0.0/12` and `192.168.0.0/16` for IPv4 (RFC 1918), and the unique local addresses
+/// `fc00::/7` for IPv6 (RFC 4193).
+///
+/// # Example
+/// ```cel
+/// Ip("192.168.1.0/24").isPrivate() == true
+/// ```
+#[cfg(feature = "ip")]
+pub fn ip_is_private(This(this): This<ipnetwork::IpNetwork>) -> Result<bool> {
+    Ok(ip_all(this, |ip| match ip {
+        std::net::IpAddr::V4(ip) => ip.is_private(),
+        std::net::IpAddr::V6(ip) => (ip.segments()[0] & 0xfe00) == 0xfc00,
+    }))
+}
+
+/// Returns true if `predicate` is true for the first and the last address of `network`.
+#[cfg(feature = "ip")]
+fn ip_all(network: ipnetwork::IpNetwork, predicate: impl Fn(std::net::IpAddr) -> bool) -> bool {
+    predicate(network.network()) && predicate(network.broadcast())
+}
+
+#[cfg(feature = "time")]
+pub mod time {
+    use std::sync::Arc;
+
+    use chrono::{DateTime, Datelike, Days, FixedOffset, Months, NaiveTime, Timelike, Utc};
+
+    use super::{Result, with_target};
+    use crate::{
+        ExecutionError, FunctionContext, Value,
+        magic::{Arguments, This},
+    };
+
+    /// Duration parses the provided argument into a [`Value::Duration`] value.
+    ///
     /// The argument must be string, and must be in the format of a duration. See
     /// the [`parse_duration`] documentation for more information on the supported
     /// formats.
@@ -359,15 +996,61 @@ pub mod time {
         chrono::DateTime::parse_from_rfc3339(i).map_err(|e| ExecutionError::function_error("timestamp", e.to_string()))
     }
 
-    pub fn timestamp_year(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    /// Converts `this` to the timezone passed after the timestamp to the getters, if any, e.g.
+    /// `ts.getHours("America/New_York")` or `getHours(ts, "+05:30")`.
+    ///
+    /// The timezone is either the name of an IANA timezone, or a fixed offset from UTC like in
+    /// RFC 3339 timestamps. Named timezones follow their current daylight saving time rules, see
+    /// [`crate::timezones`]. Without a timezone, the offset of the timestamp is used.
+    fn in_timezone(
+        ftx: &FunctionContext,
+        this: DateTime<FixedOffset>,
+        args: Arc<Vec<Value>>,
+    ) -> Result<DateTime<FixedOffset>> {
+        let args = with_target(ftx, args);
+        match args.as_slice() {
+            [_] => Ok(this),
+            [_, Value::String(timezone)] => {
+                let offset = match timezone.parse::<FixedOffset>() {
+                    Ok(offset) => offset,
+                    Err(_) => crate::timezones::utc_offset(timezone, this.timestamp())
+                        .and_then(FixedOffset::east_opt)
+                        .ok_or_else(|| ftx.error(format!("unknown timezone {timezone:?}")))?,
+                };
+                Ok(this.with_timezone(&offset))
+            }
+            [_, timezone] => Err(ExecutionError::UnexpectedType {
+                got: timezone.type_of().to_string(),
+                want: "string".to_string(),
+            }),
+            _ => Err(ExecutionError::invalid_argument_count(2, args.len())),
+        }
+    }
+
+    pub fn timestamp_year(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok(this.year().into())
     }
 
-    pub fn timestamp_month(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_month(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.month0() as i32).into())
     }
 
-    pub fn timestamp_year_day(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_year_day(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         let year = this
             .checked_sub_days(Days::new(this.day0() as u64))
             .unwrap()
@@ -376,34 +1059,160 @@ pub mod time {
         Ok(this.signed_duration_since(year).num_days().into())
     }
 
-    pub fn timestamp_month_day(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_month_day(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.day0() as i32).into())
     }
 
-    pub fn timestamp_date(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_date(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.day() as i32).into())
     }
 
-    pub fn timestamp_weekday(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_weekday(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.weekday().num_days_from_sunday() as i32).into())
     }
 
-    pub fn timestamp_hours(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_hours(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.hour() as i32).into())
     }
 
-    pub fn timestamp_minutes(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_minutes(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.minute() as i32).into())
     }
 
-    pub fn timestamp_seconds(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_seconds(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.second() as i32).into())
     }
 
-    pub fn timestamp_millis(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+    pub fn timestamp_millis(
+        ftx: &FunctionContext,
+        This(this): This<DateTime<FixedOffset>>,
+        Arguments(args): Arguments,
+    ) -> Result<Value> {
+        let this = in_timezone(ftx, this, args)?;
         Ok((this.timestamp_subsec_millis() as i32).into())
     }
 
+    /// Returns the whole number of hours of a duration, e.g. `Duration("90m").getHours() == 1`.
+    pub fn duration_hours(This(this): This<chrono::Duration>) -> Result<Value> {
+        Ok(this.num_hours().into())
+    }
+
+    /// Returns the whole number of minutes of a duration, e.g. `Duration("1h").getMinutes() == 60`.
+    pub fn duration_minutes(This(this): This<chrono::Duration>) -> Result<Value> {
+        Ok(this.num_minutes().into())
+    }
+
+    /// Returns the whole number of seconds of a duration, e.g. `Duration("1.5s").getSeconds() == 1`.
+    pub fn duration_seconds(This(this): This<chrono::Duration>) -> Result<Value> {
+        Ok(this.num_seconds().into())
+    }
+
+    /// Returns the whole number of milliseconds of a duration, e.g.
+    /// `Duration("1.5s").getMilliseconds() == 1500`.
+    pub fn duration_millis(This(this): This<chrono::Duration>) -> Result<Value> {
+        Ok(this.num_milliseconds().into())
+    }
+
+    /// Calls the timestamp or the duration getter depending on the type of `this`, as both share
+    /// the names of their getters, e.g. `getHours`. The duration getters don't take a timezone.
+    fn timestamp_or_duration(
+        ftx: &FunctionContext,
+        this: Value,
+        args: Arguments,
+        timestamp: fn(&FunctionContext, This<DateTime<FixedOffset>>, Arguments) -> Result<Value>,
+        duration: fn(This<chrono::Duration>) -> Result<Value>,
+    ) -> Result<Value> {
+        match this {
+            Value::Timestamp(this) => timestamp(ftx, This(this), args),
+            Value::Duration(this) => {
+                let args = with_target(ftx, args.0);
+                if args.len() != 1 {
+                    return Err(ExecutionError::invalid_argument_count(1, args.len()));
+                }
+                duration(This(this))
+            }
+            value => Err(ExecutionError::UnexpectedType {
+                got: value.type_of().to_string(),
+                want: "timestamp or duration".to_string(),
+            }),
+        }
+    }
+
+    pub fn hours(ftx: &FunctionContext, This(this): This<Value>, args: Arguments) -> Result<Value> {
+        timestamp_or_duration(ftx, this, args, timestamp_hours, duration_hours)
+    }
+
+    pub fn minutes(ftx: &FunctionContext, This(this): This<Value>, args: Arguments) -> Result<Value> {
+        timestamp_or_duration(ftx, this, args, timestamp_minutes, duration_minutes)
+    }
+
+    pub fn seconds(ftx: &FunctionContext, This(this): This<Value>, args: Arguments) -> Result<Value> {
+        timestamp_or_duration(ftx, this, args, timestamp_seconds, duration_seconds)
+    }
+
+    pub fn millis(ftx: &FunctionContext, This(this): This<Value>, args: Arguments) -> Result<Value> {
+        timestamp_or_duration(ftx, this, args, timestamp_millis, duration_millis)
+    }
+
+    /// Returns the timestamp at midnight of the same day, in the offset of the timestamp.
+    pub fn truncate_to_day(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+        Ok(truncate(this, 0, 0))
+    }
+
+    /// Returns the timestamp at the start of the same hour, in the offset of the timestamp.
+    pub fn truncate_to_hour(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+        Ok(truncate(this, this.hour(), 0))
+    }
+
+    /// Returns the timestamp at the start of the same minute, in the offset of the timestamp.
+    pub fn truncate_to_minute(This(this): This<chrono::DateTime<chrono::FixedOffset>>) -> Result<Value> {
+        Ok(truncate(this, this.hour(), this.minute()))
+    }
+
+    /// Replaces the local time of `this` with `hour:minute:00`, keeping its date and offset.
+    fn truncate(this: chrono::DateTime<chrono::FixedOffset>, hour: u32, minute: u32) -> Value {
+        // hour and minute come from a valid time, and a local time always maps to a single
+        // instant with a fixed offset
+        let time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        let truncated = this
+            .date_naive()
+            .and_time(time)
+            .and_local_timezone(*this.offset())
+            .single()
+            .unwrap();
+        Value::Timestamp(truncated)
+    }
+
     pub fn now() -> Result<Value> {
         Ok(Value::Timestamp(Utc::now().fixed_offset()))
     }
@@ -413,7 +1222,39 @@ pub mod time {
     }
 }
 
-pub fn max(Arguments(args): Arguments) -> Result<Value> {
+/// Returns the sum of the elements of a list, like chaining the `+` operator, so the elements
+/// must have the same type, e.g. only ints or only floats. The sum of an empty list is `0`.
+///
+/// # Example
+/// ```cel
+/// [1, 2, 3].sum() == 6
+/// sum([1.5, 2.5]) == 4.0
+/// [].sum() == 0
+/// ```
+pub fn sum(ftx: &FunctionContext, This(this): This<Value>) -> Result<Value> {
+    let items = match this {
+        Value::List(items) => items,
+        v => return Err(ftx.error(format!("cannot sum {v:?}"))),
+    };
+
+    let mut items = items.iter().cloned();
+    let Some(first) = items.next() else {
+        return Ok(Value::Int(0));
+    };
+    items.try_fold(first, |acc, item| acc + item)
+}
+
+/// Returns the arguments of a function, preceded by its target when it's called with the
+/// method syntax, e.g. `[1, 2].max()`.
+fn with_target(ftx: &FunctionContext, args: Arc<Vec<Value>>) -> Arc<Vec<Value>> {
+    match &ftx.this {
+        Some(this) => Arc::new(std::iter::once(this.clone()).chain(args.iter().cloned()).collect()),
+        None => args,
+    }
+}
+
+pub fn max(ftx: &FunctionContext, Arguments(args): Arguments) -> Result<Value> {
+    let args = with_target(ftx, args);
     // If items is a list of values, then operate on the list
     let items = if args.len() == 1 {
         match &args[0] {
@@ -435,7 +1276,8 @@ pub fn max(Arguments(args): Arguments) -> Result<Value> {
         .cloned()
 }
 
-pub fn min(Arguments(args): Arguments) -> Result<Value> {
+pub fn min(ftx: &FunctionContext, Arguments(args): Arguments) -> Result<Value> {
+    let args = with_target(ftx, args);
     // If items is a list of values, then operate on the list
     let items = if args.len() == 1 {
         match &args[0] {
@@ -475,136 +1317,683 @@ mod tests {
     }
 
     #[test]
-    fn test_length() {
+    fn test_length() {
+        [
+            ("length of list", "length([1, 2, 3]) == 3"),
+            ("length of map", r#"length({"a": 1, "b": 2, "c": 3}) == 3"#),
+            ("length of string", r#"length("foo") == 3"#),
+            ("length of bytes", r#"length(b"foo") == 3"#),
+            ("length as a list method", "[1, 2, 3].length() == 3"),
+            ("length as a string method", r#""foobar".length() == 6"#),
+            ("length of a non-ASCII string", r#""héllo".length() == 5"#),
+            ("length and indexes agree", r#""héllo"["héllo".length() - 1] == "o""#),
+            ("length of non-ASCII bytes", r#"length(Bytes("é")) == 2"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_has() {
+        let tests = vec![
+            ("map has", "has(foo.bar) == true"),
+            ("map not has", "has(foo.baz) == false"),
+            ("nested map has", "has(nested.foo.bar) && !has(nested.foo.baz)"),
+            ("list element has", "has(items[0].bar) && !has(items[1].bar)"),
+            ("list has", "has(items.bar) == false"),
+            ("null has", "has(nullval.bar) == false"),
+            ("null literal has", "has(null.bar) == false"),
+            ("string has", r#"has("bar".bar) == false"#),
+        ];
+
+        for (name, script) in tests {
+            let foo = std::collections::HashMap::from([("bar", 1)]);
+            let mut ctx = Context::default();
+            ctx.add_variable_from_value("foo", foo.clone());
+            ctx.add_variable_from_value("nested", std::collections::HashMap::from([("foo", foo.clone())]));
+            ctx.add_variable_from_value(
+                "items",
+                Value::from_iter([
+                    Value::from(foo.clone()),
+                    Value::from(std::collections::HashMap::<&str, i64>::new()),
+                ]),
+            );
+            ctx.add_variable_from_value("nullval", Value::Null);
+            assert_eq!(test_script(script, Some(ctx)), Ok(true.into()), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_map() {
+        [
+            ("map list", "[1, 2, 3].map(x, x * 2) == [2, 4, 6]"),
+            ("map list 2", "[1, 2, 3].map(y, y + 1) == [2, 3, 4]"),
+            ("map list filter", "[1, 2, 3].map(y, y + 1) == [2, 3, 4]"),
+            ("nested map", "[[1, 2], [2, 3]].map(x, x.map(x, x * 2)) == [[2, 4], [4, 6]]"),
+            ("map to list", r#"{"John": "smart"}.map(key, key) == ["John"]"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_filter() {
+        [("filter list", "[1, 2, 3].filter(x, x > 2) == [3]")]
+            .iter()
+            .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_reduce() {
+        [
+            ("sum", "[1, 2, 3].reduce(acc, x, 0, acc + x) == 6"),
+            ("product", "[2, 3, 4].reduce(acc, x, 1, acc * x) == 24"),
+            ("empty list returns init", "[].reduce(acc, x, 42, acc + x) == 42"),
+            ("collect", "[1, 2].reduce(acc, x, [], acc + [x * 2]) == [2, 4]"),
+            ("map keys", r#"{"a": 1, "b": 2}.reduce(acc, k, "", acc + k).length() == 2"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_sort() {
+        [
+            ("ints", "[3, 1, 2].sort() == [1, 2, 3]"),
+            ("negative ints", "[0, -5, 3, -1].sort() == [-5, -1, 0, 3]"),
+            ("uints", "[3u, 1u, 2u].sort() == [1u, 2u, 3u]"),
+            ("floats", "[2.5, -1.0, 0.5].sort() == [-1.0, 0.5, 2.5]"),
+            ("mixed numbers", "[2.5, 1, 2u].sort() == [1, 2u, 2.5]"),
+            (
+                "strings",
+                r#"["banana", "apple", "cherry"].sort() == ["apple", "banana", "cherry"]"#,
+            ),
+            ("duplicates", "[2, 1, 2, 1].sort() == [1, 1, 2, 2]"),
+            ("empty list", "[].sort() == []"),
+            ("single element", "[1].sort() == [1]"),
+            ("function call", "sort([2, 1]) == [1, 2]"),
+            (
+                "sort by",
+                r#"[{"n": 2}, {"n": 3}, {"n": 1}].sortBy(x, x.n) == [{"n": 1}, {"n": 2}, {"n": 3}]"#,
+            ),
+            ("sort by derived key", "[1, -3, 2].sortBy(x, x * x) == [1, 2, -3]"),
+            ("sort by descending", "[1, 3, 2].sortBy(x, -x) == [3, 2, 1]"),
+            (
+                "sort by is stable",
+                r#"["bb", "a", "cc", "d"].sortBy(s, length(s)) == ["a", "d", "bb", "cc"]"#,
+            ),
+            ("sort by empty list", "[].sortBy(x, x) == []"),
+        ]
+        .iter()
+        .for_each(assert_script);
+
+        [
+            (
+                "mixed strings and ints",
+                r#"[1, "a"].sort()"#,
+                r#"Int(1) can not be compared to String("a")"#,
+            ),
+            (
+                "sort non-list",
+                r#""ab".sort()"#,
+                "Error executing function 'sort': cannot sort String(\"ab\")",
+            ),
+            (
+                "sort by incomparable keys",
+                r#"[1, 2].sortBy(x, x == 1 ? "a" : x)"#,
+                r#"String("a") can not be compared to Int(2)"#,
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
+    }
+
+    #[test]
+    fn test_distinct_reverse() {
+        [
+            ("distinct", "[1, 1, 2].distinct() == [1, 2]"),
+            ("distinct keeps first-seen order", "[3, 1, 3, 2, 1].distinct() == [3, 1, 2]"),
+            ("distinct floats", "[1.5, 2.0, 1.5, 2.0].distinct() == [1.5, 2.0]"),
+            ("distinct mixed numbers", "[1, 1.0, 1u, 2].distinct() == [1, 2]"),
+            ("distinct strings", r#"["a", "b", "a"].distinct() == ["a", "b"]"#),
+            ("distinct lists", "[[1], [2], [1]].distinct() == [[1], [2]]"),
+            ("distinct maps", r#"[{"a": 1}, {"a": 1}].distinct() == [{"a": 1}]"#),
+            ("distinct empty list", "[].distinct() == []"),
+            ("distinct function call", "distinct([1, 1]) == [1]"),
+            ("reverse", "[1, 2, 3].reverse() == [3, 2, 1]"),
+            ("reverse mixed", r#"[1, "a", 2.5].reverse() == [2.5, "a", 1]"#),
+            ("reverse empty list", "[].reverse() == []"),
+            ("reverse twice", "[1, 2, 3].reverse().reverse() == [1, 2, 3]"),
+            ("reverse function call", "reverse([1, 2]) == [2, 1]"),
+        ]
+        .iter()
+        .for_each(assert_script);
+
+        [
+            (
+                "distinct non-list",
+                r#""ab".distinct()"#,
+                "Error executing function 'distinct': cannot deduplicate String(\"ab\")",
+            ),
+            (
+                "reverse non-list",
+                "true.reverse()",
+                "Error executing function 'reverse': cannot reverse Bool(true)",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
+    }
+
+    #[test]
+    fn test_all() {
+        [
+            ("all list #1", "[0, 1, 2].all(x, x >= 0)"),
+            ("all list #2", "[0, 1, 2].all(x, x > 0) == false"),
+            ("all map", "{0: 0, 1:1, 2:2}.all(x, x >= 0) == true"),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_any() {
+        [
+            ("exist list #1", "[0, 1, 2].any(x, x > 0)"),
+            ("exist list #2", "[0, 1, 2].any(x, x == 3) == false"),
+            ("exist list #3", "[0, 1, 2, 2].any(x, x == 2)"),
+            ("exist map", "{0: 0, 1:1, 2:2}.any(x, x > 0)"),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    // #[test]
+    // fn test_exists_one() {
+    //     [
+    //         ("exist list #1", "[0, 1, 2].exists_one(x, x > 0) == false"),
+    //         ("exist list #2", "[0, 1, 2].exists_one(x, x == 0)"),
+    //         ("exist map", "{0: 0, 1:1, 2:2}.exists_one(x, x == 2)"),
+    //     ]
+    //     .iter()
+    //     .for_each(assert_script);
+    // }
+
+    #[test]
+    fn test_max() {
+        [
+            ("max single", "max(1) == 1"),
+            ("max multiple", "max(1, 2, 3) == 3"),
+            ("max negative", "max(-1, 0) == 0"),
+            ("max float", "max(-1.0, 0.0) == 0.0"),
+            ("max list", "max([1, 2, 3]) == 3"),
+            ("max empty list", "max([]) == null"),
+            ("max no args", "max() == null"),
+            ("max method", "[1, 3, 2].max() == 3"),
+            ("max method with arguments", "(1.0).max(3.0, 2.0) == 3.0"),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_sum() {
+        [
+            ("sum ints", "[1, 2, 3].sum() == 6"),
+            ("sum negative ints", "[1, -2, -3].sum() == -4"),
+            ("sum uints", "[1u, 2u].sum() == 3u"),
+            ("sum floats", "[1.5, 2.25].sum() == 3.75"),
+            ("sum single element", "[2.5].sum() == 2.5"),
+            ("sum empty list", "[].sum() == 0"),
+            ("sum function call", "sum([1, 2]) == 3"),
+            ("sum of map", "[1, 2, 3].map(x, x * 2).sum() == 12"),
+        ]
+        .iter()
+        .for_each(assert_script);
+
+        [
+            (
+                "sum mixed numbers",
+                "[1, 2.5].sum()",
+                "Unsupported binary operator 'add': Int(1), Float(2.5)",
+            ),
+            (
+                "sum invalid element",
+                "[1, [2]].sum()",
+                "Unsupported binary operator 'add': Int(1), List([Int(2)])",
+            ),
+            (
+                "sum overflow",
+                "[9223372036854775807, 1].sum()",
+                "Overflow from binary operator 'add': Int(9223372036854775807), Int(1)",
+            ),
+            (
+                "sum non-list",
+                "true.sum()",
+                "Error executing function 'sum': cannot sum Bool(true)",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
+    }
+
+    #[test]
+    fn test_min() {
+        [
+            ("min single", "min(1) == 1"),
+            ("min multiple", "min(1, 2, 3) == 1"),
+            ("min negative", "min(-1, 0) == -1"),
+            ("min float", "min(-1.0, 0.0) == -1.0"),
+            ("min float multiple", "min(1.61803, 3.1415, 2.71828, 1.41421) == 1.41421"),
+            ("min list", "min([1, 2, 3]) == 1"),
+            ("min empty list", "min([]) == null"),
+            ("min no args", "min() == null"),
+            ("min method", "[2, 1, 3].min() == 1"),
+            ("min method empty list", "[].min() == null"),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_starts_with() {
+        [
+            ("starts with true", r#""foobar".starts_with("foo") == true"#),
+            ("starts with false", r#""foobar".starts_with("bar") == false"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_ends_with() {
+        [
+            ("ends with true", r#""foobar".ends_with("bar") == true"#),
+            ("ends with false", r#""foobar".ends_with("foo") == false"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_compare_ignore_case() {
+        [
+            ("equal", r#"compareIgnoreCase("A", "a") == 0"#),
+            ("equal strings", r#"compareIgnoreCase("Hello World", "hELLO wORLD") == 0"#),
+            ("less", r#"compareIgnoreCase("Apple", "banana") == -1"#),
+            ("greater", r#"compareIgnoreCase("banana", "Apple") == 1"#),
+            ("case sensitive ordering is different", r#""Zebra" < "apple""#),
+            ("ignoring case", r#"compareIgnoreCase("Zebra", "apple") == 1"#),
+            ("prefix", r#"compareIgnoreCase("app", "APPLE") == -1"#),
+            ("method", r#""Apple".compareIgnoreCase("BANANA") == -1"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_lower_upper_ascii() {
+        [
+            ("lower", r#""ABC".lowerAscii() == "abc""#),
+            ("lower mixed case", r#""Hello World 42!".lowerAscii() == "hello world 42!""#),
+            ("lower unicode passthrough", r#""ÀÉÎ Straße".lowerAscii() == "ÀÉÎ straße""#),
+            ("upper", r#""abc".upperAscii() == "ABC""#),
+            ("upper mixed case", r#""Hello World 42!".upperAscii() == "HELLO WORLD 42!""#),
+            ("upper unicode passthrough", r#""àéî straße".upperAscii() == "àéî STRAßE""#),
+            ("empty", r#""".lowerAscii() == "" && "".upperAscii() == """#),
+            ("function call", r#"lowerAscii("ABC") == upperAscii("abc").lowerAscii()"#),
+            (
+                "case-insensitive comparison",
+                r#""Content-Type".lowerAscii() == "CONTENT-TYPE".lowerAscii()"#,
+            ),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_substring() {
+        [
+            ("start", r#""tacocat".substring(4) == "cat""#),
+            ("start and end", r#""tacocat".substring(0, 4) == "taco""#),
+            ("empty range", r#""tacocat".substring(3, 3) == """#),
+            ("whole string", r#""tacocat".substring(0, 7) == "tacocat""#),
+            ("end of string", r#""tacocat".substring(7) == """#),
+            ("unicode", r#""héllo wörld".substring(1, 5) == "éllo""#),
+            ("unicode start", r#""héllo wörld".substring(7) == "örld""#),
+            ("function call", r#"substring("tacocat", 4) == "cat""#),
+        ]
+        .iter()
+        .for_each(assert_script);
+
+        [
+            (
+                "start out of range",
+                r#""tacocat".substring(8)"#,
+                "Error executing function 'substring': index out of range: 8",
+            ),
+            (
+                "negative start",
+                r#""tacocat".substring(-1)"#,
+                "Error executing function 'substring': index out of range: -1",
+            ),
+            (
+                "end out of range",
+                r#""tacocat".substring(0, 8)"#,
+                "Error executing function 'substring': index out of range: 8",
+            ),
+            (
+                "end before start",
+                r#""tacocat".substring(4, 2)"#,
+                "Error executing function 'substring': invalid substring range: start 4, end 2",
+            ),
+            (
+                "missing argument",
+                r#""tacocat".substring()"#,
+                "Error executing function 'substring': expected 1 or 2 arguments, got 0",
+            ),
+            (
+                "invalid start",
+                r#""tacocat".substring("a")"#,
+                "Error executing function 'substring': invalid arguments [String(\"a\")]",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
+    }
+
+    #[test]
+    fn test_index_of() {
         [
-            ("length of list", "length([1, 2, 3]) == 3"),
-            ("length of map", r#"length({"a": 1, "b": 2, "c": 3}) == 3"#),
-            ("length of string", r#"length("foo") == 3"#),
-            ("length of bytes", r#"length(b"foo") == 3"#),
-            ("length as a list method", "[1, 2, 3].length() == 3"),
-            ("length as a string method", r#""foobar".length() == 6"#),
+            ("index of", r#""tacocat".indexOf("cat") == 4"#),
+            ("first occurrence", r#""tacocat".indexOf("a") == 1"#),
+            ("missing", r#""tacocat".indexOf("dog") == -1"#),
+            ("empty substring", r#""tacocat".indexOf("") == 0"#),
+            ("unicode", r#""héllo wörld".indexOf("wö") == 6"#),
+            ("last index of", r#""tacocat".lastIndexOf("a") == 5"#),
+            ("last index of missing", r#""tacocat".lastIndexOf("dog") == -1"#),
+            ("last index of empty substring", r#""tacocat".lastIndexOf("") == 7"#),
+            ("last index of unicode", r#""éaéa".lastIndexOf("é") == 2"#),
+            ("function call", r#"indexOf("tacocat", "cat") == lastIndexOf("tacocat", "cat")"#),
+            (
+                "with substring",
+                r#""https://example.com/path".substring("https://example.com/path".indexOf("/path")) == "/path""#,
+            ),
         ]
         .iter()
         .for_each(assert_script);
     }
 
     #[test]
-    fn test_has() {
-        let tests = vec![
-            ("map has", "has(foo.bar) == true"),
-            ("map not has", "has(foo.baz) == false"),
-        ];
+    fn test_format() {
+        [
+            ("string verb", r#""hello %s!".format(["world"]) == "hello world!""#),
+            ("string verb with other types", r#""%s %s %s".format([true, 1, 1.5]) == "true 1 1.5""#),
+            ("int verb", r#""%d-%d".format([-1, 2u]) == "-1-2""#),
+            ("float verb", r#""%f".format([1.5]) == "1.500000""#),
+            ("float verb with an int", r#""%f".format([2]) == "2.000000""#),
+            ("literal percent", r#""%d%%".format([100]) == "100%""#),
+            ("no verbs", r#""100%%".format([]) == "100%""#),
+            ("unicode", r#""é%sè".format(["à"]) == "éàè""#),
+            ("function call", r#"format("%s=%d", ["a", 1]) == "a=1""#),
+        ]
+        .iter()
+        .for_each(assert_script);
 
-        for (name, script) in tests {
-            let mut ctx = Context::default();
-            ctx.add_variable_from_value("foo", std::collections::HashMap::from([("bar", 1)]));
-            assert_eq!(test_script(script, Some(ctx)), Ok(true.into()), "{name}");
-        }
+        [
+            (
+                "not enough arguments",
+                r#""%s-%s".format(["a"])"#,
+                "Error executing function 'format': not enough arguments: got 1",
+            ),
+            (
+                "too many arguments",
+                r#""%s".format(["a", "b"])"#,
+                "Error executing function 'format': too many arguments: expected 1, got 2",
+            ),
+            (
+                "type mismatch",
+                r#""%d".format(["a"])"#,
+                "Error executing function 'format': invalid argument for %d: String(\"a\")",
+            ),
+            (
+                "list for a string verb",
+                r#""%s".format([[1]])"#,
+                "Error executing function 'format': invalid argument for %s: List([Int(1)])",
+            ),
+            (
+                "unsupported verb",
+                r#""%x".format([1])"#,
+                "Error executing function 'format': unsupported verb %x",
+            ),
+            (
+                "trailing percent",
+                r#""%".format([])"#,
+                "Error executing function 'format': missing verb after %",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
     }
 
     #[test]
-    fn test_map() {
+    fn test_replace() {
         [
-            ("map list", "[1, 2, 3].map(x, x * 2) == [2, 4, 6]"),
-            ("map list 2", "[1, 2, 3].map(y, y + 1) == [2, 3, 4]"),
-            ("map list filter", "[1, 2, 3].map(y, y + 1) == [2, 3, 4]"),
-            ("nested map", "[[1, 2], [2, 3]].map(x, x.map(x, x * 2)) == [[2, 4], [4, 6]]"),
-            ("map to list", r#"{"John": "smart"}.map(key, key) == ["John"]"#),
+            ("replace all", r#""hello".replace("l", "L") == "heLLo""#),
+            ("replace substring", r#""a::b::c".replace("::", "-") == "a-b-c""#),
+            ("replace with limit", r#""aaa".replace("a", "b", 2) == "bba""#),
+            ("replace with zero limit", r#""aaa".replace("a", "b", 0) == "aaa""#),
+            ("replace with large limit", r#""aaa".replace("a", "b", 10) == "bbb""#),
+            ("negative limit is unbounded", r#""aaa".replace("a", "b", -1) == "bbb""#),
+            ("delete occurrences", r#""a-b-c".replace("-", "") == "abc""#),
+            ("no occurrence", r#""abc".replace("x", "y") == "abc""#),
+            ("function call", r#"replace("hello", "l", "L", 1) == "heLlo""#),
         ]
         .iter()
         .for_each(assert_script);
-    }
 
-    #[test]
-    fn test_filter() {
-        [("filter list", "[1, 2, 3].filter(x, x > 2) == [3]")]
-            .iter()
-            .for_each(assert_script);
+        [
+            (
+                "missing argument",
+                r#""abc".replace("a")"#,
+                "Error executing function 'replace': expected 2 or 3 arguments, got 1",
+            ),
+            (
+                "invalid limit",
+                r#""abc".replace("a", "b", "c")"#,
+                "Error executing function 'replace': invalid arguments [String(\"a\"), String(\"b\"), String(\"c\")]",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
     }
 
     #[test]
-    fn test_all() {
+    fn test_trim() {
         [
-            ("all list #1", "[0, 1, 2].all(x, x >= 0)"),
-            ("all list #2", "[0, 1, 2].all(x, x > 0) == false"),
-            ("all map", "{0: 0, 1:1, 2:2}.all(x, x >= 0) == true"),
+            ("trim", r#""  hello  ".trim() == "hello""#),
+            ("trim start", r#""  hello  ".trimStart() == "hello  ""#),
+            ("trim end", r#""  hello  ".trimEnd() == "  hello""#),
+            (
+                "internal whitespace is preserved",
+                r#"" hello \t world ".trim() == "hello \t world""#,
+            ),
+            ("tabs and newlines", r#""\t\r\nhello\n".trim() == "hello""#),
+            ("unicode whitespace", r#""\u00a0\u2003hello\u3000".trim() == "hello""#),
+            ("all whitespace", r#"" \t\n ".trim() == """#),
+            ("all whitespace start", r#"" \t\n ".trimStart() == """#),
+            ("all whitespace end", r#"" \t\n ".trimEnd() == """#),
+            ("nothing to trim", r#""hello".trim() == "hello""#),
+            ("empty", r#""".trim() == """#),
+            ("function call", r#"trim(" a ") == trimStart(trimEnd(" a "))"#),
         ]
         .iter()
         .for_each(assert_script);
     }
 
     #[test]
-    fn test_any() {
+    fn test_split_join() {
         [
-            ("exist list #1", "[0, 1, 2].any(x, x > 0)"),
-            ("exist list #2", "[0, 1, 2].any(x, x == 3) == false"),
-            ("exist list #3", "[0, 1, 2, 2].any(x, x == 2)"),
-            ("exist map", "{0: 0, 1:1, 2:2}.any(x, x > 0)"),
+            ("split", r#""a,b,c".split(",") == ["a", "b", "c"]"#),
+            ("split multi-character separator", r#""a::b".split("::") == ["a", "b"]"#),
+            ("split without separator", r#""abc".split(",") == ["abc"]"#),
+            ("split empty parts", r#"",a,".split(",") == ["", "a", ""]"#),
+            ("split into characters", r#""héllo".split("") == ["h", "é", "l", "l", "o"]"#),
+            ("split empty string", r#""".split(",") == [""]"#),
+            ("split empty string into characters", r#""".split("") == []"#),
+            ("split function", r#"split("a b", " ") == ["a", "b"]"#),
+            ("join", r#"["a", "b"].join("-") == "a-b""#),
+            ("join empty separator", r#"["a", "b", "c"].join("") == "abc""#),
+            ("join single element", r#"["a"].join("-") == "a""#),
+            ("join empty list", r#"[].join("-") == """#),
+            ("split then join", r#""a,b,c".split(",").join(";") == "a;b;c""#),
         ]
         .iter()
         .for_each(assert_script);
-    }
-
-    // #[test]
-    // fn test_exists_one() {
-    //     [
-    //         ("exist list #1", "[0, 1, 2].exists_one(x, x > 0) == false"),
-    //         ("exist list #2", "[0, 1, 2].exists_one(x, x == 0)"),
-    //         ("exist map", "{0: 0, 1:1, 2:2}.exists_one(x, x == 2)"),
-    //     ]
-    //     .iter()
-    //     .for_each(assert_script);
-    // }
 
-    #[test]
-    fn test_max() {
         [
-            ("max single", "max(1) == 1"),
-            ("max multiple", "max(1, 2, 3) == 3"),
-            ("max negative", "max(-1, 0) == 0"),
-            ("max float", "max(-1.0, 0.0) == 0.0"),
-            ("max list", "max([1, 2, 3]) == 3"),
-            ("max empty list", "max([]) == null"),
-            ("max no args", "max() == null"),
+            (
+                "join non-string element",
+                r#"["a", 1].join("-")"#,
+                "Error executing function 'join': cannot join non-string element Int(1)",
+            ),
+            (
+                "join non-list",
+                r#""ab".join("-")"#,
+                "Error executing function 'join': cannot join String(\"ab\")",
+            ),
         ]
         .iter()
-        .for_each(assert_script);
+        .for_each(assert_error);
     }
 
     #[test]
-    fn test_min() {
+    fn test_append_push_put() {
         [
-            ("min single", "min(1) == 1"),
-            ("min multiple", "min(1, 2, 3) == 1"),
-            ("min negative", "min(-1, 0) == -1"),
-            ("min float", "min(-1.0, 0.0) == -1.0"),
-            ("min float multiple", "min(1.61803, 3.1415, 2.71828, 1.41421) == 1.41421"),
-            ("min list", "min([1, 2, 3]) == 1"),
-            ("min empty list", "min([]) == null"),
-            ("min no args", "min() == null"),
+            ("append", "[1, 2, 3].append([4, 5]) == [1, 2, 3, 4, 5]"),
+            ("append empty", "[].append([1]) == [1] && [1].append([]) == [1]"),
+            ("append as a function", "append([1], [2]) == [1, 2]"),
+            ("push", "[1, 2, 3].push(4) == [1, 2, 3, 4]"),
+            ("push a list", "[1].push([2]) == [1, [2]]"),
+            ("put", r#"{"a": 1}.put("b", 2) == {"a": 1, "b": 2}"#),
+            ("put replaces", r#"put({"a": 1}, "a", 2) == {"a": 2}"#),
+            ("put int key", "{}.put(1, true)[1]"),
         ]
         .iter()
         .for_each(assert_script);
+
+        let mut ctx = Context::default();
+        ctx.add_variable_from_value("list", vec![1, 2]);
+        assert_eq!(
+            test_script("list.push(3) == [1, 2, 3] && list == [1, 2]", Some(ctx)),
+            Ok(true.into()),
+            "shared input is not modified"
+        );
     }
 
     #[test]
-    fn test_starts_with() {
+    fn test_set_operations() {
         [
-            ("starts with true", r#""foobar".starts_with("foo") == true"#),
-            ("starts with false", r#""foobar".starts_with("bar") == false"#),
+            ("union", "[1, 2, 3].union([3, 4]) == [1, 2, 3, 4]"),
+            ("union disjoint", "[1, 2].union([3, 4]) == [1, 2, 3, 4]"),
+            ("union duplicates", "[1, 1, 2].union([2, 3, 3]) == [1, 2, 3]"),
+            ("union empty", "[].union([]) == [] && union([1], []) == [1]"),
+            ("intersection", "[1, 2, 3].intersection([3, 2, 4]) == [2, 3]"),
+            ("intersection disjoint", "[1, 2].intersection([3, 4]) == []"),
+            ("intersection duplicates", "[2, 1, 2, 1].intersection([1, 1, 2]) == [2, 1]"),
+            ("difference", "[1, 2, 3].difference([2, 4]) == [1, 3]"),
+            ("difference disjoint", "[1, 2].difference([3, 4]) == [1, 2]"),
+            ("difference duplicates", "[1, 3, 1, 2, 3].difference([2, 2]) == [1, 3]"),
+            (
+                "roles",
+                r#"["admin", "user"].union(["user", "guest"]).difference(["admin"]) == ["user", "guest"]"#,
+            ),
+            ("nested values", "[[1], {1: 2}].intersection([{1: 2}]) == [{1: 2}]"),
         ]
         .iter()
         .for_each(assert_script);
+
+        for script in [
+            "[1].union(1)",
+            r#""a".intersection(["a"])"#,
+            r#"difference({"a": 1}, ["a"])"#,
+        ] {
+            assert!(test_script(script, None).is_err(), "{script}");
+        }
     }
 
     #[test]
-    fn test_ends_with() {
+    fn test_get_path() {
+        let mut ctx = Context::default();
+        let config = r#"{"a": {"b": {"c": "deep"}}, "servers": [{"port": 80}, {"port": 443}], "ids": {1: "one"}}"#;
+        ctx.add_variable_from_value("config", test_script(config, None).unwrap());
+
         [
-            ("ends with true", r#""foobar".ends_with("bar") == true"#),
-            ("ends with false", r#""foobar".ends_with("foo") == false"#),
+            ("deep path", r#"config.getPath("a.b.c", "default") == "deep""#),
+            ("function call", r#"getPath(config, "a.b", {}) == {"c": "deep"}"#),
+            ("missing key", r#"config.getPath("a.x.c", "default") == "default""#),
+            ("past a leaf", r#"config.getPath("a.b.c.d", "default") == "default""#),
+            ("list index", r#"config.getPath("servers.1.port", 0) == 443"#),
+            ("list index out of bounds", r#"config.getPath("servers.2.port", 0) == 0"#),
+            ("list non-numeric index", r#"config.getPath("servers.first.port", 0) == 0"#),
+            ("int key", r#"config.getPath("ids.1", "") == "one""#),
         ]
         .iter()
-        .for_each(assert_script);
+        .for_each(|(name, script)| {
+            assert_eq!(test_script(script, Some(ctx.new_inner_scope())), Ok(true.into()), "{name}");
+        });
+    }
+
+    #[test]
+    fn test_append_push_put_in_place() {
+        use std::{collections::HashMap, sync::Arc};
+
+        use crate::{
+            FunctionContext,
+            magic::This,
+            objects::{Key, Map, Value},
+        };
+
+        // uniquely-owned inputs are reused
+        let list = Arc::new(vec![Value::Int(1)]);
+        let ptr = Arc::as_ptr(&list);
+        let list = super::push(This(list), Value::Int(2));
+        assert_eq!(Arc::as_ptr(&list), ptr);
+        let list = super::append(This(list), Arc::new(vec![Value::Int(3)]));
+        assert_eq!(Arc::as_ptr(&list), ptr);
+        assert_eq!(*list, vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+
+        // shared inputs are copied and left untouched
+        let shared = Arc::new(vec![Value::Int(1)]);
+        let list = super::push(This(shared.clone()), Value::Int(2));
+        assert_eq!(Arc::strong_count(&shared), 1);
+        assert_eq!(*shared, vec![Value::Int(1)]);
+        assert_eq!(*list, vec![Value::Int(1), Value::Int(2)]);
+
+        let ctx = Context::default();
+        let ftx = FunctionContext::new(Arc::new("put".to_string()), None, &ctx, Vec::new());
+
+        let map = Arc::new(HashMap::from([(Key::Int(1), Value::Int(1))]));
+        let ptr = Arc::as_ptr(&map);
+        let map = Value::Map(Map {
+            map,
+        });
+        let Ok(Value::Map(result)) = super::put(&ftx, This(map), 2.into(), 2.into()) else {
+            panic!("put should return a map");
+        };
+        assert_eq!(Arc::as_ptr(&result.map), ptr);
+        assert_eq!(result.map.len(), 2);
+
+        let shared = Arc::new(HashMap::from([(Key::Int(1), Value::Int(1))]));
+        let map = Value::Map(Map {
+            map: shared.clone(),
+        });
+        let Ok(Value::Map(result)) = super::put(&ftx, This(map), 2.into(), 2.into()) else {
+            panic!("put should return a map");
+        };
+        assert_eq!(Arc::strong_count(&shared), 1);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(result.map.len(), 2);
     }
 
     #[cfg(feature = "time")]
@@ -660,6 +2049,26 @@ mod tests {
                 "timestamp milliseconds",
                 r#"Timestamp("2023-05-28T00:00:42.123Z").milliseconds() == 123"#,
             ),
+            (
+                "timestamp truncateToDay",
+                r#"Timestamp("2023-05-28T15:42:17.5Z").truncateToDay() == Timestamp("2023-05-28T00:00:00Z")"#,
+            ),
+            (
+                "timestamp truncateToDay keeps the offset",
+                r#"String(Timestamp("2023-05-28T15:42:17+02:00").truncateToDay()) == "2023-05-28T00:00:00+02:00""#,
+            ),
+            (
+                "timestamp truncateToDay uses the local date",
+                r#"Timestamp("2023-05-28T23:30:00-05:00").truncateToDay() == Timestamp("2023-05-28T05:00:00Z")"#,
+            ),
+            (
+                "timestamp truncateToHour",
+                r#"Timestamp("2023-05-28T15:42:17.5Z").truncateToHour() == Timestamp("2023-05-28T15:00:00Z")"#,
+            ),
+            (
+                "timestamp truncateToMinute",
+                r#"Timestamp("2023-05-28T15:42:17.5+05:30").truncateToMinute() == Timestamp("2023-05-28T15:42:00+05:30")"#,
+            ),
         ]
         .iter()
         .for_each(assert_script);
@@ -695,6 +2104,93 @@ mod tests {
         .for_each(assert_error)
     }
 
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_timestamp_timezone() {
+        [
+            ("getHours in UTC", r#"Timestamp("2023-05-28T02:00:00Z").getHours("UTC") == 2"#),
+            (
+                "getHours in a named timezone",
+                r#"Timestamp("2023-05-28T02:00:00Z").getHours("America/New_York") == 22"#,
+            ),
+            (
+                "getHours in winter time",
+                r#"Timestamp("2023-01-28T02:00:00Z").getHours("America/New_York") == 21"#,
+            ),
+            (
+                "getHours in the southern hemisphere",
+                r#"Timestamp("2023-01-28T02:00:00Z").getHours("Australia/Sydney") == 13"#,
+            ),
+            (
+                "getHours as a function",
+                r#"getHours(Timestamp("2023-05-28T02:00:00Z"), "Europe/Paris") == 4"#,
+            ),
+            (
+                "getHours with an offset",
+                r#"Timestamp("2023-05-28T02:00:00Z").getHours("+05:30") == 7"#,
+            ),
+            (
+                "getMinutes with an offset",
+                r#"Timestamp("2023-05-28T02:00:00Z").getMinutes("+05:30") == 30"#,
+            ),
+            (
+                "getDate changes with the timezone",
+                r#"Timestamp("2023-05-28T02:00:00Z").getDate("America/New_York") == 27"#,
+            ),
+            (
+                "getDayOfWeek changes with the timezone",
+                r#"Timestamp("2023-05-28T02:00:00Z").getDayOfWeek("America/New_York") == 6"#,
+            ),
+            (
+                "year changes with the timezone",
+                r#"Timestamp("2023-01-01T02:00:00Z").year("America/New_York") == 2022"#,
+            ),
+            (
+                "getDayOfYear changes with the timezone",
+                r#"Timestamp("2023-01-01T02:00:00Z").getDayOfYear("America/New_York") == 364"#,
+            ),
+            (
+                "the default is the offset of the timestamp",
+                r#"Timestamp("2023-05-28T02:00:00+02:00").getHours() == 2"#,
+            ),
+            (
+                "getSeconds",
+                r#"Timestamp("2023-05-28T00:00:06Z").getSeconds("Asia/Tokyo") == 6"#,
+            ),
+            (
+                "getMilliseconds",
+                r#"Timestamp("2023-05-28T00:00:42.123Z").getMilliseconds("Asia/Tokyo") == 123"#,
+            ),
+        ]
+        .iter()
+        .for_each(assert_script);
+
+        [
+            (
+                "unknown timezone",
+                r#"Timestamp("2023-05-28T02:00:00Z").getHours("Mars/Olympus_Mons")"#,
+                r#"Error executing function 'getHours': unknown timezone "Mars/Olympus_Mons""#,
+            ),
+            (
+                "offset out of range",
+                r#"Timestamp("2023-05-28T02:00:00Z").getHours("+25:00")"#,
+                r#"Error executing function 'getHours': unknown timezone "+25:00""#,
+            ),
+            (
+                "timezone is not a string",
+                r#"Timestamp("2023-05-28T02:00:00Z").getHours(2)"#,
+                "Unexpected type: got 'int', want 'string'",
+            ),
+            (
+                "too many arguments",
+                r#"Timestamp("2023-05-28T02:00:00Z").getHours("UTC", "UTC")"#,
+                "Invalid argument count: expected 2, got 3",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error)
+    }
+
     #[cfg(feature = "time")]
     #[test]
     fn test_duration() {
@@ -711,6 +2207,46 @@ mod tests {
         .for_each(assert_script);
     }
 
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_duration_getters() {
+        [
+            ("hours", r#"Duration("3600s").getHours() == 1"#),
+            ("minutes", r#"Duration("3600s").getMinutes() == 60"#),
+            ("seconds", r#"Duration("3600s").getSeconds() == 3600"#),
+            ("milliseconds", r#"Duration("3600s").getMilliseconds() == 3600000"#),
+            ("multi-hour hours", r#"Duration("49h30m").getHours() == 49"#),
+            ("multi-hour minutes", r#"Duration("49h30m").getMinutes() == 2970"#),
+            ("partial hours are truncated", r#"Duration("90m").getHours() == 1"#),
+            ("sub-second seconds", r#"Duration("1.5s").getSeconds() == 1"#),
+            ("sub-second milliseconds", r#"Duration("1.5s").getMilliseconds() == 1500"#),
+            ("sub-millisecond", r#"Duration("999us").getMilliseconds() == 0"#),
+            ("negative", r#"(Duration("1s") - Duration("2h")).getMinutes() == -119"#),
+            ("as a function", r#"getMinutes(Duration("2m30s")) == 2"#),
+            (
+                "timestamps keep their getters",
+                r#"Timestamp("2023-05-28T02:03:04.5Z").getSeconds() == 4"#,
+            ),
+        ]
+        .iter()
+        .for_each(assert_script);
+
+        [
+            (
+                "timezone on a duration",
+                r#"Duration("1h").getHours("UTC")"#,
+                "Invalid argument count: expected 1, got 2",
+            ),
+            (
+                "not a timestamp or duration",
+                r#""1h".getHours()"#,
+                "Unexpected type: got 'string', want 'timestamp or duration'",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
+    }
+
     #[cfg(feature = "time")]
     #[test]
     fn test_timestamp_variable() {
@@ -756,6 +2292,75 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "ip")]
+    #[test]
+    fn test_ip_helpers() {
+        [
+            ("family v4", r#"Ip("127.0.0.1").family() == 4"#),
+            ("family v4 network", r#"Ip("10.0.0.0/8").family() == 4"#),
+            ("family v6", r#"Ip("::1").family() == 6"#),
+            ("family v6 network", r#"Ip("2001:db8::/32").family() == 6"#),
+            ("prefix length v4", r#"Ip("10.0.0.0/8").prefixLength() == 8"#),
+            ("prefix length v4 address", r#"Ip("10.1.2.3").prefixLength() == 32"#),
+            ("prefix length v6", r#"Ip("2001:db8::/32").prefixLength() == 32"#),
+            ("prefix length v6 address", r#"Ip("::1").prefixLength() == 128"#),
+            ("loopback v4", r#"Ip("127.0.0.1").isLoopback()"#),
+            ("loopback v4 network", r#"Ip("127.0.0.0/8").isLoopback()"#),
+            ("not loopback v4", r#"!Ip("10.0.0.1").isLoopback()"#),
+            ("not loopback v4 supernet", r#"!Ip("126.0.0.0/7").isLoopback()"#),
+            ("loopback v6", r#"Ip("::1").isLoopback()"#),
+            ("not loopback v6", r#"!Ip("::2").isLoopback()"#),
+            ("not loopback v6 network", r#"!Ip("::/127").isLoopback()"#),
+            ("private v4 10/8", r#"Ip("10.1.2.3").isPrivate()"#),
+            ("private v4 172.16/12", r#"Ip("172.31.255.255").isPrivate()"#),
+            ("private v4 192.168/16", r#"Ip("192.168.1.0/24").isPrivate()"#),
+            ("not private v4", r#"!Ip("8.8.8.8").isPrivate()"#),
+            ("not private v4 outside 172.16/12", r#"!Ip("172.32.0.1").isPrivate()"#),
+            ("not private v4 supernet", r#"!Ip("10.0.0.0/7").isPrivate()"#),
+            ("private v6", r#"Ip("fc00::/7").isPrivate()"#),
+            ("private v6 address", r#"Ip("fd12:3456::1").isPrivate()"#),
+            ("not private v6", r#"!Ip("2001:db8::1").isPrivate()"#),
+            ("not private v6 loopback", r#"!Ip("::1").isPrivate()"#),
+            ("as a function", r#"isPrivate(Ip("10.0.0.1"))"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
+
+    #[test]
+    fn test_type() {
+        let tests = vec![
+            ("list", r#"type([1, 2]) == "list""#),
+            ("map", r#"type({"a": 1}) == "map""#),
+            ("int", r#"type(1) == "int""#),
+            ("uint", r#"type(1u) == "uint""#),
+            ("float", r#"type(1.5) == "float""#),
+            ("string", r#"type("a") == "string""#),
+            ("bytes", r#"type(b"a") == "bytes""#),
+            ("bool", r#"type(true) == "bool""#),
+            ("null", r#"type(null) == "null""#),
+            ("type of type", r#"type(type(1)) == "string""#),
+            ("expression", r#"type(1 + 2) == type(3)"#),
+            #[cfg(feature = "time")]
+            ("duration", r#"type(Duration("1s")) == "duration""#),
+            #[cfg(feature = "time")]
+            ("timestamp", r#"type(Timestamp("2023-05-29T00:00:00Z")) == "timestamp""#),
+            #[cfg(feature = "regex")]
+            ("regex", r#"type(Regex("^a$")) == "regex""#),
+            #[cfg(feature = "ip")]
+            ("ip", r#"type(Ip("127.0.0.1")) == "ip""#),
+        ];
+
+        for (name, script) in tests {
+            assert_eq!(test_script(script, None), Ok(true.into()), "{name}");
+        }
+
+        let mut ctx = Context::default();
+        let function = crate::Value::Function(std::sync::Arc::new("f".to_string()), None);
+        ctx.add_variable_from_value("f", function);
+        assert_eq!(test_script(r#"type(f) == "function""#, Some(ctx)), Ok(true.into()));
+    }
+
     #[cfg(feature = "regex")]
     #[test]
     fn test_matches() {
@@ -785,6 +2390,43 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex_limits() {
+        use crate::context::RegexLimits;
+
+        // `\w` matches any Unicode word character, so repeating it compiles to a huge automaton
+        let script = r#""foobar".matches(Regex("\\w{100}"))"#;
+        match test_script(script, None) {
+            Err(crate::ExecutionError::FunctionError {
+                function,
+                message,
+            }) => {
+                assert_eq!(function, "Regex");
+                assert!(message.contains("exceeds size limit"), "{message}");
+            }
+            res => panic!("expected the regex to be rejected, got {res:?}"),
+        }
+
+        let normal = r#""foobar".matches(Regex("^[a-z]+(bar|baz)$")) == true"#;
+        assert_eq!(test_script(normal, None), Ok(true.into()));
+
+        let mut ctx = Context::default();
+        ctx.set_regex_limits(RegexLimits {
+            size_limit: 100 << 20,
+            ..Default::default()
+        });
+        assert_eq!(ctx.new_inner_scope().regex_limits().size_limit, 100 << 20);
+        assert_eq!(test_script(script, Some(ctx)), Ok(false.into()));
+
+        let mut ctx = Context::default();
+        ctx.set_regex_limits(RegexLimits {
+            size_limit: 16,
+            ..Default::default()
+        });
+        assert!(test_script(normal, Some(ctx)).is_err());
+    }
+
     #[test]
     fn test_string() {
         [
@@ -802,6 +2444,9 @@ mod tests {
         [
             ("String", r#"Bytes("abc") == b"abc""#),
             ("Bytes", r#"Bytes("abc") == b"\x61b\x63""#),
+            ("Unicode", r#"Bytes("é") == b"\u00e9""#),
+            ("Raw", r#"Bytes(r"\x61") == br"\x61""#),
+            ("Triple quoted", r#"Bytes("a\"b") == b"""a"b""""#),
         ]
         .iter()
         .for_each(assert_script);
@@ -818,23 +2463,64 @@ mod tests {
         .for_each(assert_script);
     }
 
-    // #[test]
-    // fn test_uint() {
-    //     [
-    //         ("String", r#"Uint("10") == Uint(10)"#),
-    //         ("Float", "Uint(10.5) == Uint(10)"),
-    //     ]
-    //     .iter()
-    //     .for_each(assert_script);
-    // }
+    #[test]
+    fn test_uint() {
+        [
+            ("String", r#"Uint("10") == Uint(10)"#),
+            ("Float", "Uint(10.5) == Uint(10)"),
+        ]
+        .iter()
+        .for_each(assert_script);
+    }
 
     #[test]
     fn test_int() {
         [
             ("String", r#"Int("10") == 10"#),
             ("Int", "Int(10) == 10"),
-            // ("Uint", "10.uint().int() == 10"),
+            ("Uint", "Int(10u) == 10 && Int(Uint(10)) == 10"),
             ("Float", "Int(10.5) == 10"),
+            ("hex", r#"Int("0x1F") == 31 && Int("0X1f") == 31"#),
+            ("signed hex", r#"Int("-0x1F") == -31 && Int("+0x1F") == 31"#),
+            ("min hex", r#"Int("-0x8000000000000000") == -9223372036854775807 - 1"#),
+            ("whitespace", r#"Int(" 42 ") == 42 && Int("\t-42\n") == -42"#),
+            ("whitespace hex", r#"Int(" 0x10 ") == 16"#),
+        ]
+        .iter()
+        .for_each(assert_script);
+
+        [
+            (
+                "invalid",
+                r#"Int("4 2")"#,
+                "Error executing function 'Int': string parse error: invalid digit found in string",
+            ),
+            (
+                "hex without digits",
+                r#"Int("0x")"#,
+                "Error executing function 'Int': string parse error: cannot parse integer from empty string",
+            ),
+            (
+                "hex with a misplaced sign",
+                r#"Int("0x-1")"#,
+                "Error executing function 'Int': string parse error: invalid digit found in string",
+            ),
+            (
+                "hex overflow",
+                r#"Int("0x8000000000000000")"#,
+                "Error executing function 'Int': string parse error: number too large to fit in target type",
+            ),
+        ]
+        .iter()
+        .for_each(assert_error);
+    }
+
+    #[test]
+    fn test_dyn() {
+        [
+            ("list", "dyn([1, 2]) == [1, 2]"),
+            ("int", "dyn(1) + 1 == 2"),
+            ("map", r#"dyn({"a": 1}).a == 1"#),
         ]
         .iter()
         .for_each(assert_script);
@@ -845,7 +2531,7 @@ mod tests {
         [
             ("String || bool", r#""" || false"#, "No such overload"),
             ("Int || bool", "1 || false", "No such overload"),
-            // ("UInt || bool", "1u || false", "No such overload"),
+            ("UInt || bool", "1u || false", "No such overload"),
             ("Float || bool", "0.1|| false", "No such overload"),
             ("List || bool", "[] || false", "No such overload"),
             ("Map || bool", "{} || false", "No such overload"),
@@ -0,0 +1,143 @@
+//! Interning of short strings, enabled by the `intern` feature.
+//!
+//! Programs often reuse the same map keys and short strings across evaluations. When interning
+//! is enabled, [`Value::String`](crate::Value::String) and [`Key::String`](crate::objects::Key)
+//! created from strings of at most [`MAX_INTERNED_LEN`] bytes share a single allocation per
+//! distinct string, for the whole process.
+//!
+//! When the `intern` feature is disabled, [`intern`] simply allocates a new `Arc`.
+
+use std::sync::Arc;
+
+/// Maximum length in bytes of the strings that are interned. Longer strings are rarely repeated.
+#[cfg(feature = "intern")]
+pub(crate) const MAX_INTERNED_LEN: usize = 32;
+
+/// Maximum number of distinct interned strings. When the interner is full, new strings are not
+/// interned, and the strings that are not used anymore are evicted once every
+/// [`EVICTION_INTERVAL`] new strings.
+#[cfg(feature = "intern")]
+pub(crate) const MAX_INTERNED_STRINGS: usize = 10_000;
+
+/// Number of new strings that are not interned because the interner is full between two
+/// evictions. An eviction scans all the interned strings, so it's not done for every new string.
+#[cfg(feature = "intern")]
+const EVICTION_INTERVAL: usize = 1_000;
+
+#[cfg(feature = "intern")]
+static INTERNER: std::sync::LazyLock<std::sync::Mutex<Interner>> = std::sync::LazyLock::new(Default::default);
+
+#[cfg(feature = "intern")]
+#[derive(Default)]
+struct Interner {
+    strings: std::collections::HashSet<Arc<String>>,
+    // new strings that were not interned since the last eviction
+    misses: usize,
+}
+
+#[cfg(feature = "intern")]
+impl Interner {
+    fn intern(&mut self, s: String) -> Arc<String> {
+        if let Some(interned) = self.strings.get(&s) {
+            return interned.clone();
+        }
+
+        if self.strings.len() >= MAX_INTERNED_STRINGS {
+            self.misses += 1;
+            if self.misses < EVICTION_INTERVAL {
+                return Arc::new(s);
+            }
+            self.misses = 0;
+            // only the interner references these strings
+            self.strings.retain(|interned| Arc::strong_count(interned) > 1);
+            if self.strings.len() >= MAX_INTERNED_STRINGS {
+                return Arc::new(s);
+            }
+        }
+
+        let interned = Arc::new(s);
+        self.strings.insert(interned.clone());
+        interned
+    }
+}
+
+/// Returns an `Arc` containing `s`, shared with previous calls for the same string if `s` is
+/// short enough.
+#[cfg(feature = "intern")]
+pub(crate) fn intern(s: String) -> Arc<String> {
+    if s.len() > MAX_INTERNED_LEN {
+        return Arc::new(s);
+    }
+
+    // if the lock is poisoned, the interner is still valid: a panic can't leave it half-updated
+    INTERNER.lock().unwrap_or_else(|err| err.into_inner()).intern(s)
+}
+
+#[cfg(not(feature = "intern"))]
+#[inline]
+pub(crate) fn intern(s: String) -> Arc<String> {
+    Arc::new(s)
+}
+
+#[cfg(all(test, feature = "intern"))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{EVICTION_INTERVAL, Interner, MAX_INTERNED_LEN, MAX_INTERNED_STRINGS};
+    use crate::{Context, Program, Value, objects::Key};
+
+    fn string(value: Value) -> Arc<String> {
+        match value {
+            Value::String(s) => s,
+            value => panic!("expected a string, got {value:?}"),
+        }
+    }
+
+    #[test]
+    fn short_strings_are_shared() {
+        let a = string(Value::from("interned"));
+        let b = string(Value::from("interned".to_string()));
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let Key::String(key) = Key::from("interned") else {
+            unreachable!()
+        };
+        assert!(Arc::ptr_eq(&a, &key));
+
+        let program = Program::compile(r#""interned""#).unwrap();
+        let literal = string(program.execute(&Context::default()).unwrap());
+        assert!(Arc::ptr_eq(&a, &literal));
+    }
+
+    #[test]
+    fn long_strings_are_not_shared() {
+        let long = "a".repeat(MAX_INTERNED_LEN + 1);
+        let a = string(Value::from(long.as_str()));
+        let b = string(Value::from(long.as_str()));
+        assert_eq!(a, b);
+        assert!(!Arc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn unused_strings_are_evicted_periodically() {
+        let mut interner = Interner::default();
+        let used: Vec<_> = (0..MAX_INTERNED_STRINGS / 2)
+            .map(|i| interner.intern(i.to_string()))
+            .collect();
+        for i in used.len()..MAX_INTERNED_STRINGS {
+            interner.intern(i.to_string());
+        }
+
+        // the interner is full, the unused strings are evicted only after a while
+        for _ in 1..EVICTION_INTERVAL {
+            let new = interner.intern("new".to_string());
+            assert_eq!(Arc::strong_count(&new), 1);
+        }
+        assert_eq!(interner.strings.len(), MAX_INTERNED_STRINGS);
+
+        let a = interner.intern("new".to_string());
+        assert_eq!(interner.strings.len(), used.len() + 1);
+        assert!(Arc::ptr_eq(&a, &interner.intern("new".to_string())));
+        assert!(Arc::ptr_eq(&used[0], &interner.intern("0".to_string())));
+    }
+}
@@ -1,8 +1,13 @@
 #[cfg(feature = "time")]
 use chrono::Duration;
+use std::{collections::HashMap, sync::Arc};
+
 use thiserror::Error;
 
-use crate::Value;
+use crate::{
+    Value,
+    objects::{Key, Map},
+};
 
 #[derive(Debug, Clone, Error)]
 #[error("unable to convert value to json: {0:?}")]
@@ -20,6 +25,71 @@ pub enum ConvertToJsonError<'a> {
     DurationOverflow(&'a Duration),
 }
 
+/// The owned counterpart of [`ConvertToJsonError`], returned when converting a [`Value`] to JSON
+/// with [`TryFrom`].
+#[derive(Debug, Clone, Error)]
+pub enum IntoJsonError {
+    /// The value is not representable in JSON, e.g. a function or a regex.
+    #[error("unable to convert value to json: {0:?}")]
+    Value(Value),
+
+    #[cfg(feature = "time")]
+    /// The duration is too large to convert to nanoseconds.
+    #[error("duration too large to convert to nanoseconds: {0:?}")]
+    DurationOverflow(Duration),
+}
+
+impl From<ConvertToJsonError<'_>> for IntoJsonError {
+    fn from(err: ConvertToJsonError<'_>) -> Self {
+        match err {
+            ConvertToJsonError::Value(value) => IntoJsonError::Value(value.clone()),
+            #[cfg(feature = "time")]
+            ConvertToJsonError::DurationOverflow(duration) => IntoJsonError::DurationOverflow(*duration),
+        }
+    }
+}
+
+/// Converts a JSON value to a CEL value. Integers that fit in an `i64` are converted to
+/// [`Value::Int`], larger integers that fit in a `u64` to [`Value::UInt`], and other numbers to
+/// [`Value::Float`]. Objects are converted to maps with
+/// string keys.
+///
+/// This is the inverse of [`Value::json`] for JSON values. To convert arbitrary Serde types,
+/// use [`to_value`](crate::to_value).
+impl From<serde_json::Value> for Value {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(b) => Value::Bool(b),
+            serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Value::Int(i),
+                (None, Some(u)) => Value::UInt(u),
+                // it's okay to unwrap because without the `arbitrary_precision` feature, every
+                // number can be represented as an f64
+                (None, None) => Value::Float(n.as_f64().unwrap()),
+            },
+            serde_json::Value::String(s) => Value::String(Arc::new(s)),
+            serde_json::Value::Array(array) => Value::List(Arc::new(array.into_iter().map(Value::from).collect())),
+            serde_json::Value::Object(object) => {
+                let map: HashMap<Key, Value> = object.into_iter().map(|(k, v)| (k.into(), v.into())).collect();
+                Value::Map(Map {
+                    map: Arc::new(map),
+                })
+            }
+        }
+    }
+}
+
+/// Converts a CEL value to a JSON value, like [`Value::json`]. Returns an error for values that
+/// are not representable in JSON, such as functions or regexes.
+impl TryFrom<Value> for serde_json::Value {
+    type Error = IntoJsonError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value.json().map_err(IntoJsonError::from)
+    }
+}
+
 impl Value {
     /// Converts a CEL value to a JSON value.
     ///
@@ -46,7 +116,7 @@ impl Value {
                 serde_json::Value::Object(obj)
             }
             Value::Int(i) => i.into(),
-            // Value::UInt(u) => u.into(),
+            Value::UInt(u) => u.into(),
             Value::Float(f) => f.into(),
             Value::String(ref s) => s.to_string().into(),
             Value::Bool(b) => b.into(),
@@ -71,6 +141,7 @@ mod tests {
     use chrono::Duration;
     use serde_json::json;
 
+    use super::IntoJsonError;
     use crate::{Value as CelValue, objects::Map};
 
     #[test]
@@ -103,4 +174,82 @@ mod tests {
             assert_eq!(value.json().unwrap(), *expected, "{value:?}={expected:?}");
         }
     }
+
+    #[test]
+    fn test_json_to_cel_value() {
+        let tests = vec![
+            (json!(null), CelValue::Null),
+            (json!(true), CelValue::Bool(true)),
+            (json!(-42), CelValue::Int(-42)),
+            (json!(42.5), CelValue::Float(42.5)),
+            // too large for an i64
+            (json!(u64::MAX), CelValue::UInt(u64::MAX)),
+            (json!(-1e20), CelValue::Float(-1e20)),
+            (json!("hello"), CelValue::String("hello".to_string().into())),
+            (
+                json!([1, [2.5, null]]),
+                CelValue::List(
+                    vec![
+                        CelValue::Int(1),
+                        CelValue::List(vec![CelValue::Float(2.5), CelValue::Null].into()),
+                    ]
+                    .into(),
+                ),
+            ),
+            (
+                json!({"a": {"b": [true]}}),
+                CelValue::Map(Map::from(HashMap::from([(
+                    "a",
+                    CelValue::Map(Map::from(HashMap::from([(
+                        "b",
+                        CelValue::List(vec![CelValue::Bool(true)].into()),
+                    )]))),
+                )]))),
+            ),
+        ];
+
+        for (json, expected) in tests {
+            assert_eq!(CelValue::from(json.clone()), expected, "{json}");
+        }
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let tests = [
+            json!(null),
+            json!(false),
+            json!(0),
+            json!(i64::MIN),
+            json!(-1.5),
+            json!("hello"),
+            json!([]),
+            json!({}),
+            json!({"list": [1, 2.5, "three", null, {"nested": [true]}], "int": 1, "float": 1.5}),
+        ];
+
+        for json in tests {
+            let value = CelValue::from(json.clone());
+            assert_eq!(serde_json::Value::try_from(value.clone()).unwrap(), json);
+            assert_eq!(CelValue::from(serde_json::Value::try_from(value.clone()).unwrap()), value);
+        }
+    }
+
+    #[test]
+    fn test_cel_value_to_json_error() {
+        let function = CelValue::Function("size".to_string().into(), None);
+        let err = serde_json::Value::try_from(function.clone()).unwrap_err();
+        assert!(matches!(err, IntoJsonError::Value(value) if value == function));
+
+        let nested = CelValue::List(vec![CelValue::Int(1), function.clone()].into());
+        assert!(matches!(
+            serde_json::Value::try_from(nested),
+            Err(IntoJsonError::Value(value)) if value == function
+        ));
+
+        #[cfg(feature = "regex")]
+        {
+            let regex = CelValue::Regex(regex::Regex::new("^a+$").unwrap());
+            assert!(matches!(serde_json::Value::try_from(regex), Err(IntoJsonError::Value(_))));
+        }
+    }
 }
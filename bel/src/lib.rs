@@ -1,19 +1,31 @@
 extern crate core;
 
-use std::{convert::TryFrom, sync::Arc};
+use std::{
+    convert::TryFrom,
+    sync::{Arc, atomic::AtomicBool},
+};
 
 use thiserror::Error;
 
 mod macros;
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+
+mod checker;
 pub mod common;
 pub mod context;
+mod cost;
+mod intern;
+mod lint;
 pub mod parser;
 
+pub use checker::TypeError;
 pub use common::ast::IdedExpr;
-use common::ast::SelectExpr;
+use common::ast::{Expr, SelectExpr, operators};
 pub use context::Context;
 pub use functions::FunctionContext;
+pub use lint::LintWarning;
 pub use objects::{ResolveResult, Value};
 use parser::{Expression, ExpressionReferences, Parser};
 pub use parser::{ParseError, ParseErrors};
@@ -24,6 +36,8 @@ mod resolvers;
 
 #[cfg(feature = "time")]
 mod duration;
+#[cfg(feature = "time")]
+mod timezones;
 
 #[cfg(feature = "ip")]
 pub use ser::Ip;
@@ -33,10 +47,13 @@ pub use ser::{Duration, Timestamp};
 mod ser;
 pub use ser::{SerializationError, to_value};
 
+mod de;
+pub use de::{DeserializationError, from_value};
+
 #[cfg(feature = "json")]
 mod json;
 #[cfg(feature = "json")]
-pub use json::ConvertToJsonError;
+pub use json::{ConvertToJsonError, IntoJsonError};
 use magic::FromContext;
 
 pub mod extractors {
@@ -110,6 +127,13 @@ pub enum ExecutionError {
     RemainderByZero(Value),
     #[error("Overflow from binary operator '{0}': {1:?}, {2:?}")]
     Overflow(&'static str, Value, Value),
+    /// Indicates that the execution was cancelled, see [`Program::execute_cancellable`].
+    #[error("Execution cancelled")]
+    Cancelled,
+    /// Indicates that the execution exceeded one of the limits of the context, `max_steps` or
+    /// `max_depth`, see [`Context::set_evaluation_limits`].
+    #[error("Resource exhausted: {0} exceeded")]
+    ResourceExhausted(&'static str),
 }
 
 impl ExecutionError {
@@ -157,7 +181,15 @@ impl ExecutionError {
     }
 }
 
+/// A compiled program, ready to be executed.
+///
+/// With the `serialize` feature, programs implement [`serde::Serialize`] and
+/// [`serde::Deserialize`], so they can be compiled once (e.g. at build time), persisted, and
+/// loaded later without parsing the source again. A deserialized program executes exactly like
+/// the compiled one. The serialized form is the syntax tree of the program, which is not stable
+/// across versions of this crate.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     expression: Expression,
 }
@@ -170,8 +202,236 @@ impl Program {
         })
     }
 
+    /// Compiles the program like [`Program::compile`], and also checks that the functions it calls
+    /// are registered in `context`, so that a typo in a function name is reported at compile time
+    /// instead of failing at execution.
+    ///
+    /// If `variables` is `Some`, the variables referenced by the program, apart from the
+    /// variables of its macros, must also be in the list or in `context`.
+    ///
+    /// # Errors
+    /// Returns the syntax errors of the program or, if it's valid, an error for each undeclared
+    /// function or variable, at the position of its first reference.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bel::{Context, Program};
+    /// let context = Context::default();
+    ///
+    /// assert!(Program::compile_checked("length(foo) > 0", &context, Some(&["foo"])).is_ok());
+    /// assert!(Program::compile_checked("lenght(foo) > 0", &context, None).is_err());
+    /// assert!(Program::compile_checked("length(bar) > 0", &context, Some(&["foo"])).is_err());
+    /// ```
+    pub fn compile_checked(
+        source: &str,
+        context: &Context,
+        variables: Option<&[&str]>,
+    ) -> Result<Program, ParseErrors> {
+        let (expression, source_info) = Parser::default().parse_with_source_info(source)?;
+        let references = expression.references();
+
+        let mut undeclared = Vec::new();
+        for function in references.functions() {
+            if !operators::BUILTINS.contains(&function) && !context.has_function(function) {
+                let id = references.function_id(function);
+                undeclared.push((id, format!("undeclared reference to function '{function}'")));
+            }
+        }
+        if let Some(variables) = variables {
+            let declared = context.variable_names();
+            for variable in references.free_variables() {
+                if !variables.contains(&variable) && !declared.contains(&variable) {
+                    let id = references.free_variable_id(variable);
+                    undeclared.push((id, format!("undeclared reference to variable '{variable}'")));
+                }
+            }
+        }
+
+        if undeclared.is_empty() {
+            return Ok(Program {
+                expression,
+            });
+        }
+
+        let mut errors: Vec<ParseError> = undeclared
+            .into_iter()
+            .map(|(id, msg)| ParseError {
+                source: None,
+                pos: id.and_then(|id| source_info.pos_for(id)).unwrap_or_default(),
+                msg,
+                expr_id: id.unwrap_or_default(),
+                source_info: Some(source_info.clone()),
+            })
+            .collect();
+        errors.sort_by(|a, b| a.pos.cmp(&b.pos));
+        Err(ParseErrors {
+            errors,
+        })
+    }
+
     pub fn execute(&self, context: &Context) -> ResolveResult {
-        Value::resolve(&self.expression, context)
+        let resolved_variables = Default::default();
+        Value::resolve(&self.expression, &context.new_evaluation_scope(&resolved_variables))
+    }
+
+    /// Executes the program like [`Program::execute`], but stops as soon as possible once
+    /// `cancelled` is set, for example from another thread, and returns
+    /// [`ExecutionError::Cancelled`].
+    ///
+    /// The flag is checked before each iteration of the comprehensions (generated by macros such as
+    /// `all` or `map`), which is where long evaluations spend their time. A function call that is
+    /// running when the flag is set is not interrupted.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use std::sync::atomic::AtomicBool;
+    /// # use bel::{Context, ExecutionError, Program};
+    /// let program = Program::compile("[1, 2, 3].all(x, x > 0)").unwrap();
+    /// let cancelled = AtomicBool::new(true);
+    ///
+    /// let result = program.execute_cancellable(&Context::default(), &cancelled);
+    /// assert_eq!(result, Err(ExecutionError::Cancelled));
+    /// ```
+    pub fn execute_cancellable(&self, context: &Context, cancelled: &AtomicBool) -> ResolveResult {
+        let resolved_variables = Default::default();
+        let context = context.new_evaluation_scope(&resolved_variables);
+        Value::resolve(&self.expression, &context.new_cancellable_scope(cancelled))
+    }
+
+    /// Executes a program made of conditions joined by `&&`, or of a list literal of conditions,
+    /// evaluating every condition and collecting all their errors instead of stopping at the
+    /// first one, e.g. to report all the failing checks of a form at once.
+    ///
+    /// Returns `true` if every condition evaluated to `true`, and the errors of the conditions
+    /// that failed, in order. A condition that doesn't evaluate to a bool is an
+    /// [`ExecutionError::NoSuchOverload`] error. Any other program is executed as a single
+    /// condition.
+    ///
+    /// Unlike `&&`, the conditions don't short-circuit: the conditions following a `false` or
+    /// failing one are still evaluated, so a condition can't rely on the previous ones being true.
+    /// For example, `has(a.b) && a.b > 0` reports an error for `a.b > 0` when `a.b` is missing.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bel::{Context, ExecutionError, Program};
+    /// let program = Program::compile("a > 0 && b > 0").unwrap();
+    /// let (result, errors) = program.execute_collect_errors(&Context::default());
+    ///
+    /// assert!(!result);
+    /// assert_eq!(
+    ///     errors,
+    ///     vec![ExecutionError::undeclared_reference("a"), ExecutionError::undeclared_reference("b")]
+    /// );
+    /// ```
+    pub fn execute_collect_errors(&self, context: &Context) -> (bool, Vec<ExecutionError>) {
+        let mut conditions = Vec::new();
+        match &self.expression.expr {
+            Expr::List(list) => {
+                for element in &list.elements {
+                    collect_conditions(element, &mut conditions);
+                }
+            }
+            _ => collect_conditions(&self.expression, &mut conditions),
+        }
+
+        // the conditions are a single evaluation, which resolves each variable once
+        let resolved_variables = Default::default();
+        let context = context.new_evaluation_scope(&resolved_variables);
+        let mut result = true;
+        let mut errors = Vec::new();
+        for condition in conditions {
+            match Value::resolve(condition, &context).and_then(|value| value.to_bool()) {
+                Ok(value) => result &= value,
+                Err(err) => {
+                    result = false;
+                    errors.push(err);
+                }
+            }
+        }
+        (result, errors)
+    }
+
+    /// Type-checks the program against the variable types of `context` without executing it.
+    ///
+    /// The types of the variables are the ones declared with [`Context::declare_variable`] or,
+    /// for the variables that were not declared, the types of their values. The check is
+    /// best-effort: it reports the obvious mismatches, such as adding an `int` to a `string`,
+    /// but a program that passes validation can still fail at execution, for example because
+    /// of a function call or an undeclared variable.
+    ///
+    /// # Errors
+    /// Returns all the type mismatches found in the program.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bel::{Context, Program, objects::ValueType};
+    /// let mut context = Context::default();
+    /// context.declare_variable("foo", ValueType::Int);
+    ///
+    /// assert!(Program::compile("foo + 1").unwrap().validate(&context).is_ok());
+    /// assert!(Program::compile("foo + \"x\"").unwrap().validate(&context).is_err());
+    /// ```
+    pub fn validate(&self, context: &Context) -> Result<(), Vec<TypeError>> {
+        checker::check(&self.expression, context)
+    }
+
+    /// Returns a static estimate of the cost of executing the program, in number of operations,
+    /// without executing it. It can be used to reject expensive programs before running them.
+    ///
+    /// Every literal, variable, field selection, operator and function call has a cost of 1. The
+    /// body of a comprehension (generated by macros such as `all` or `map`) is counted once per
+    /// element of its range if the range is a list or map literal, or 100 times otherwise, so
+    /// nested comprehensions are much more expensive than flat expressions. The cost of the
+    /// functions themselves is not known, so the estimate is only meaningful to compare programs.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bel::Program;
+    /// let flat = Program::compile("foo + 1 > 2").unwrap();
+    /// let nested = Program::compile("foo.all(x, x.all(y, y > 0))").unwrap();
+    ///
+    /// assert!(nested.cost() > flat.cost());
+    /// ```
+    pub fn cost(&self) -> u64 {
+        cost::estimate(&self.expression)
+    }
+
+    /// Returns the suspicious constructs found in the program, such as duplicate keys in a map
+    /// literal or comprehension variables shadowing the variable of an enclosing comprehension.
+    /// Unlike parse errors, warnings don't prevent the program from being executed.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bel::{LintWarning, Program};
+    /// let program = Program::compile(r#"{"a": 1, "a": 2}"#).unwrap();
+    /// let warnings = program.lint();
+    ///
+    /// assert_eq!(warnings.len(), 1);
+    /// assert!(matches!(warnings[0], LintWarning::DuplicateMapKey { .. }));
+    /// ```
+    pub fn lint(&self) -> Vec<LintWarning> {
+        lint::lint(&self.expression, &[])
+    }
+
+    /// Lints the program like [`Program::lint`], and also reports the comprehension variables
+    /// that shadow a variable of `context`, which are otherwise only reported when they shadow
+    /// the variable of an enclosing comprehension.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bel::{Context, LintWarning, Program};
+    /// let mut context = Context::default();
+    /// context.add_variable_from_value("x", 2);
+    /// let program = Program::compile("[1, 2, 3].map(x, x * x)").unwrap();
+    ///
+    /// assert!(program.lint().is_empty());
+    /// assert!(matches!(
+    ///     &program.lint_with_context(&context)[..],
+    ///     [LintWarning::ShadowedVariable { name, .. }] if name == "x"
+    /// ));
+    /// ```
+    pub fn lint_with_context(&self, context: &Context) -> Vec<LintWarning> {
+        lint::lint(&self.expression, &context.variable_names())
     }
 
     /// Returns the variables and functions referenced by the CEL program
@@ -195,6 +455,18 @@ impl Program {
     }
 }
 
+/// Splits the operands of the `&&` operators at the root of `expr` into `conditions`.
+fn collect_conditions<'a>(expr: &'a Expression, conditions: &mut Vec<&'a Expression>) {
+    match &expr.expr {
+        Expr::Call(call) if call.func_name == operators::LOGICAL_AND && call.target.is_none() => {
+            for arg in &call.args {
+                collect_conditions(arg, conditions);
+            }
+        }
+        _ => conditions.push(expr),
+    }
+}
+
 impl TryFrom<&str> for Program {
     type Error = ParseErrors;
 
@@ -233,6 +505,34 @@ mod tests {
         let _p: Program = input.try_into().unwrap();
     }
 
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn serialize_program() {
+        let scripts = [
+            "1 + 2 * 3 - 4 / 2 % 3",
+            "-1.5 < 2.5 && !false || null == null",
+            r#""foo" + "bar" + """baz""" == "foobarbaz" && b"abc".contains(b"b")"#,
+            "[1, 2, 3].map(x, x * n).filter(x, x > 2) == [4, 6]",
+            r#"{"a": 1, 2: [true], false: {"nested": n}}["a"] + foo.bar"#,
+            "has(foo.bar) && !has(foo.baz) ? foo.bar : n",
+            "[1, 2].all(x, x > 0) && [1, 2].any(x, x == 2) && length([1]) == 1",
+            "length(missing)",
+        ];
+
+        let mut ctx = Context::default();
+        ctx.add_variable_from_value("n", 2i64);
+        ctx.add_variable_from_value("foo", HashMap::from([("bar", 1i64)]));
+
+        for script in scripts {
+            let program = Program::compile(script).unwrap();
+            let serialized = serde_json::to_string(&program).unwrap();
+            let deserialized: Program = serde_json::from_str(&serialized).unwrap();
+
+            assert_eq!(deserialized.expression, program.expression, "{script}");
+            assert_eq!(deserialized.execute(&ctx), program.execute(&ctx), "{script}");
+        }
+    }
+
     #[test]
     fn variables() {
         fn assert_output(script: &str, expected: ResolveResult) {
@@ -263,6 +563,174 @@ mod tests {
         let p = Program::compile("[1, 1].map(x, x * 2)").unwrap();
         assert!(p.references().has_variable("x"));
         assert_eq!(p.references().variables().len(), 1);
+        assert!(p.references().free_variables().is_empty());
+    }
+
+    #[test]
+    fn free_variables() {
+        let free_variables = |script: &str| {
+            let p = Program::compile(script).unwrap();
+            let mut variables: Vec<String> = p.references().free_variables().iter().map(|v| v.to_string()).collect();
+            variables.sort();
+            variables
+        };
+
+        assert_eq!(free_variables("foo + x"), ["foo", "x"]);
+        assert_eq!(free_variables("items.map(x, foo + x)"), ["foo", "items"]);
+        // `x` is only bound inside of the macro
+        assert_eq!(free_variables("items.map(x, x * 2) + [x]"), ["items", "x"]);
+        assert_eq!(free_variables("items.all(x, x.exists(y, x + y > z))"), ["items", "z"]);
+        // the iteration variable of a macro may shadow a variable used in its range
+        assert_eq!(free_variables("x.filter(x, x > 0)"), ["x"]);
+        assert!(free_variables("[1, 2].sortBy(x, -x)").is_empty());
+    }
+
+    #[test]
+    fn compile_checked() {
+        let context = Context::default();
+        let errors = |script: &str, variables: Option<&[&str]>| -> Vec<(String, (isize, isize))> {
+            match Program::compile_checked(script, &context, variables) {
+                Ok(_) => Vec::new(),
+                Err(e) => e.errors.into_iter().map(|e| (e.msg, e.pos)).collect(),
+            }
+        };
+
+        assert!(errors("length(foo) > 0 && [1, 2].map(x, x * 2).sortBy(x, -x)[0] in [4]", None).is_empty());
+        assert!(errors("length(foo) > 0", Some(&["foo"])).is_empty());
+        assert!(errors("foo.all(x, x > y)", Some(&["foo", "y"])).is_empty());
+
+        assert_eq!(
+            errors("1 + missing(1)", None),
+            [("undeclared reference to function 'missing'".to_string(), (1, 12))]
+        );
+        assert_eq!(
+            errors("foo > bar", Some(&["foo"])),
+            [("undeclared reference to variable 'bar'".to_string(), (1, 7))]
+        );
+        // the variables are only checked with an allow-list
+        assert!(errors("foo > bar", None).is_empty());
+        // the variables of macros don't need to be declared
+        assert!(errors("[1].map(x, x)", Some(&[])).is_empty());
+        assert_eq!(
+            errors("a.foo() + b", Some(&["b"])).len(),
+            2,
+            "both the variable and the function are undeclared"
+        );
+
+        // syntax errors are still reported
+        assert!(Program::compile_checked("1 +", &context, None).is_err());
+
+        let mut context = Context::default();
+        context.add_variable_from_value("bar", 1);
+        context.add_function("missing", |a: i64| a);
+        assert!(Program::compile_checked("foo > bar && missing(1) == 1", &context, Some(&["foo"])).is_ok());
+    }
+
+    #[test]
+    fn execute_cancellable() {
+        use std::{
+            sync::atomic::{AtomicBool, Ordering},
+            thread,
+            time::Duration,
+        };
+
+        let mut ctx = Context::default();
+        ctx.add_variable_from_value("items", (0..10_000i64).collect::<Vec<_>>());
+        let program = Program::compile("items.all(x, items.all(y, x + y >= 0))").unwrap();
+
+        let cancelled = AtomicBool::new(false);
+        let result = thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(50));
+                cancelled.store(true, Ordering::Relaxed);
+            });
+            program.execute_cancellable(&ctx, &cancelled)
+        });
+        assert_eq!(result, Err(ExecutionError::Cancelled));
+
+        // without cancellation, the program runs to completion
+        let program = Program::compile("[1, 2, 3].all(x, [4, 5].all(y, x < y))").unwrap();
+        let cancelled = AtomicBool::new(false);
+        assert_eq!(program.execute_cancellable(&ctx, &cancelled), Ok(true.into()));
+    }
+
+    #[test]
+    fn evaluation_limits() {
+        use crate::context::EvaluationLimits;
+
+        let script = format!("1{}", " + 1".repeat(200));
+        let program = Program::compile(&script).unwrap();
+        assert_eq!(program.execute(&Context::default()), Ok(Value::Int(201)));
+
+        let ctx = Context::default().with_limits(EvaluationLimits {
+            max_depth: Some(50),
+            ..Default::default()
+        });
+        assert_eq!(program.execute(&ctx), Err(ExecutionError::ResourceExhausted("max_depth")));
+        // the depth is not the length of the expression
+        let program = Program::compile("[1, 2, 3].map(x, x + 1) == [2, 3, 4]").unwrap();
+        assert_eq!(program.execute(&ctx), Ok(true.into()));
+
+        let mut ctx = Context::default().with_limits(EvaluationLimits {
+            max_steps: Some(10_000),
+            ..Default::default()
+        });
+        ctx.add_variable_from_value("items", (0..10_000i64).collect::<Vec<_>>());
+        let program = Program::compile("items.map(x, x * 2)").unwrap();
+        assert_eq!(program.execute(&ctx), Err(ExecutionError::ResourceExhausted("max_steps")));
+        // the steps are counted per evaluation, and shared with the child scopes
+        let program = Program::compile("items.length() > 0 && [1, 2].all(x, x > 0)").unwrap();
+        assert_eq!(program.execute(&ctx), Ok(true.into()));
+        assert_eq!(program.execute(&ctx), Ok(true.into()));
+        assert_eq!(ctx.new_inner_scope().evaluation_limits().max_steps, Some(10_000));
+
+        let cancelled = std::sync::atomic::AtomicBool::new(false);
+        let program = Program::compile("items.all(x, x >= 0)").unwrap();
+        assert_eq!(
+            program.execute_cancellable(&ctx, &cancelled),
+            Err(ExecutionError::ResourceExhausted("max_steps"))
+        );
+    }
+
+    #[test]
+    fn execute_collect_errors() {
+        let program = Program::compile("a > 0 && b > 0").unwrap();
+        assert_eq!(
+            program.execute_collect_errors(&Context::default()),
+            (
+                false,
+                vec![
+                    ExecutionError::undeclared_reference("a"),
+                    ExecutionError::undeclared_reference("b"),
+                ]
+            )
+        );
+
+        let mut ctx = Context::default();
+        ctx.add_variable_from_value("a", 1);
+        ctx.add_variable_from_value("b", -1);
+        assert_eq!(program.execute_collect_errors(&ctx), (false, vec![]));
+        ctx.add_variable_from_value("b", 1);
+        assert_eq!(program.execute_collect_errors(&ctx), (true, vec![]));
+
+        // nested `&&` and list literals, with errors after a false condition
+        let program = Program::compile("[a > 5 && missing, 1, b > 0 && c.d == 1]").unwrap();
+        ctx.add_variable_from_value("c", HashMap::from([("e", 1)]));
+        assert_eq!(
+            program.execute_collect_errors(&ctx),
+            (
+                false,
+                vec![
+                    ExecutionError::undeclared_reference("missing"),
+                    ExecutionError::NoSuchOverload,
+                    ExecutionError::no_such_key("d"),
+                ]
+            )
+        );
+
+        // other programs are a single condition
+        let program = Program::compile("a > 0 || missing").unwrap();
+        assert_eq!(program.execute_collect_errors(&ctx), (true, vec![]));
     }
 
     #[test]
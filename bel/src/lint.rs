@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::{
+    common::{
+        ast::{EntryExpr, Expr},
+        value::CelVal,
+    },
+    objects::Key,
+    parser::Expression,
+};
+
+/// A suspicious construct found by [`Program::lint`](crate::Program::lint). Warnings don't
+/// prevent the program from being executed.
+#[derive(Error, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum LintWarning {
+    /// A key appears several times in a map literal, for example `{"a": 1, "a": 2}`. Only the
+    /// last value is kept when the map is evaluated.
+    ///
+    /// `id` is the ID of the duplicate entry expression.
+    #[error("Duplicate key '{key}' in map literal")]
+    DuplicateMapKey { key: Key, id: u64 },
+    /// The variable of a comprehension, for example the `x` of `.map(x, ...)`, has the same name
+    /// as a variable of the context or of an enclosing comprehension, which can't be referenced
+    /// inside the comprehension.
+    ///
+    /// `id` is the ID of the comprehension expression.
+    #[error("Variable '{name}' shadows an outer variable")]
+    ShadowedVariable { name: String, id: u64 },
+}
+
+/// Lints `expr`, where `variables` are the names of the variables of the context the program is
+/// executed with, if known.
+pub(crate) fn lint(expr: &Expression, variables: &[&str]) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let mut scope = variables.to_vec();
+    visit(expr, &mut scope, &mut warnings);
+    warnings
+}
+
+fn visit<'a>(expr: &'a Expression, scope: &mut Vec<&'a str>, warnings: &mut Vec<LintWarning>) {
+    match &expr.expr {
+        Expr::Map(map) => {
+            let mut keys = HashSet::new();
+            for entry in &map.entries {
+                if let EntryExpr::MapEntry(map_entry) = &entry.expr {
+                    if let Some(key) = literal_key(&map_entry.key)
+                        && !keys.insert(key.clone())
+                    {
+                        warnings.push(LintWarning::DuplicateMapKey {
+                            key,
+                            id: entry.id,
+                        });
+                    }
+                    visit(&map_entry.key, scope, warnings);
+                    visit(&map_entry.value, scope, warnings);
+                }
+            }
+        }
+        Expr::Struct(structure) => {
+            for entry in &structure.entries {
+                match &entry.expr {
+                    EntryExpr::MapEntry(entry) => {
+                        visit(&entry.key, scope, warnings);
+                        visit(&entry.value, scope, warnings);
+                    }
+                    EntryExpr::StructField(field) => visit(&field.value, scope, warnings),
+                }
+            }
+        }
+        Expr::List(list) => {
+            for element in &list.elements {
+                visit(element, scope, warnings);
+            }
+        }
+        Expr::Select(select) => visit(&select.operand, scope, warnings),
+        Expr::Call(call) => {
+            if let Some(target) = &call.target {
+                visit(target, scope, warnings);
+            }
+            for arg in &call.args {
+                visit(arg, scope, warnings);
+            }
+        }
+        Expr::Comprehension(comprehension) => {
+            visit(&comprehension.iter_range, scope, warnings);
+            visit(&comprehension.accu_init, scope, warnings);
+
+            // the accumulators of the macros are named `@result` and can't clash with variables
+            let names = [
+                Some(comprehension.iter_var.as_str()),
+                comprehension.iter_var2.as_deref(),
+                Some(comprehension.accu_var.as_str()).filter(|name| !name.starts_with('@')),
+            ];
+            let len = scope.len();
+            for name in names.into_iter().flatten() {
+                if scope.contains(&name) {
+                    warnings.push(LintWarning::ShadowedVariable {
+                        name: name.to_string(),
+                        id: expr.id,
+                    });
+                }
+                scope.push(name);
+            }
+
+            visit(&comprehension.loop_cond, scope, warnings);
+            visit(&comprehension.loop_step, scope, warnings);
+            visit(&comprehension.result, scope, warnings);
+            scope.truncate(len);
+        }
+        Expr::Literal(_) | Expr::Ident(_) | Expr::Unspecified => (),
+    }
+}
+
+/// Returns the map key of a literal expression, or `None` if the expression is not a literal or
+/// if its type can't be used as a key.
+fn literal_key(expr: &Expression) -> Option<Key> {
+    match &expr.expr {
+        Expr::Literal(CelVal::Int(i)) => Some(Key::Int(*i)),
+        Expr::Literal(CelVal::UInt(u)) => Some(Key::Uint(*u)),
+        Expr::Literal(CelVal::Boolean(b)) => Some(Key::Bool(*b)),
+        Expr::Literal(CelVal::String(s)) => Some(Key::String(s.clone().into())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{Context, Program, lint::LintWarning, objects::Key};
+
+    fn duplicate_keys(script: &str) -> Vec<Key> {
+        Program::compile(script)
+            .unwrap()
+            .lint()
+            .into_iter()
+            .filter_map(|warning| match warning {
+                LintWarning::DuplicateMapKey {
+                    key, ..
+                } => Some(key),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn shadowed_variables(script: &str, context: &Context) -> Vec<String> {
+        Program::compile(script)
+            .unwrap()
+            .lint_with_context(context)
+            .into_iter()
+            .filter_map(|warning| match warning {
+                LintWarning::ShadowedVariable {
+                    name, ..
+                } => Some(name),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn duplicate_string_key() {
+        assert_eq!(
+            duplicate_keys(r#"{"a": 1, "b": 2, "a": 3}"#),
+            vec![Key::String(Arc::new("a".to_string()))]
+        );
+    }
+
+    #[test]
+    fn duplicate_int_key() {
+        assert_eq!(duplicate_keys("{1: true, 2: false, 1: false}"), vec![Key::Int(1)]);
+        assert_eq!(duplicate_keys("{1: 1, 1: 2, 1: 3}"), vec![Key::Int(1), Key::Int(1)]);
+    }
+
+    #[test]
+    fn nested_duplicate_key() {
+        assert_eq!(
+            duplicate_keys(r#"[{"a": {true: 1, true: 2}}].map(x, x)"#),
+            vec![Key::Bool(true)]
+        );
+    }
+
+    #[test]
+    fn distinct_keys() {
+        assert!(duplicate_keys(r#"{"a": 1, "b": 2, "c": 3}"#).is_empty());
+        assert!(duplicate_keys(r#"{1: "a", "1": "b", true: "c"}"#).is_empty());
+        // keys that are not literals can't be checked statically
+        assert!(duplicate_keys("{a: 1, a: 2}").is_empty());
+        assert!(duplicate_keys("{}").is_empty());
+    }
+
+    #[test]
+    fn shadowed_comprehension_variable() {
+        let context = Context::default();
+        assert_eq!(shadowed_variables("[[1]].all(x, x.all(x, x > 0))", &context), vec!["x"]);
+        assert_eq!(
+            shadowed_variables("[1].map(x, [2].map(y, [3].filter(x, x > y)))", &context),
+            vec!["x"]
+        );
+        assert_eq!(shadowed_variables("[1].reduce(acc, acc, 0, acc)", &context), vec!["acc"]);
+    }
+
+    #[test]
+    fn shadowed_context_variable() {
+        let mut context = Context::default();
+        context.add_variable_from_value("x", 1);
+        assert_eq!(shadowed_variables("[1, 2].map(x, x * 2)", &context), vec!["x"]);
+        // without the context, only the comprehension variables are known
+        assert!(Program::compile("[1, 2].map(x, x * 2)").unwrap().lint().is_empty());
+    }
+
+    #[test]
+    fn uniquely_named_variables() {
+        let mut context = Context::default();
+        context.add_variable_from_value("x", 1);
+        assert!(shadowed_variables("[1, 2].map(y, y * x)", &context).is_empty());
+        assert!(shadowed_variables("[[1]].all(y, y.all(z, z > x))", &context).is_empty());
+        // sibling comprehensions don't shadow each other
+        assert!(shadowed_variables("[1].map(y, y) + [2].map(y, y)", &context).is_empty());
+        assert!(shadowed_variables("[1].reduce(acc, y, 0, acc + y)", &context).is_empty());
+    }
+
+    #[test]
+    fn warnings_dont_fail_execution() {
+        let program = Program::compile(r#"{"a": 1, "a": 2}"#).unwrap();
+        assert_eq!(program.lint().len(), 1);
+        assert!(program.execute(&Default::default()).is_ok());
+
+        let mut context = Context::default();
+        context.add_variable_from_value("x", 10);
+        let program = Program::compile("[1, 2].map(x, x * 2) == [2, 4] && x == 10").unwrap();
+        assert_eq!(program.lint_with_context(&context).len(), 1);
+        assert_eq!(program.execute(&context), Ok(true.into()));
+    }
+}
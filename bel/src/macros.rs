@@ -98,5 +98,50 @@ macro_rules! impl_handler {
     };
 }
 
+/// Builds a [`Value::List`](crate::Value::List) from expressions that can be converted into a
+/// [`Value`](crate::Value).
+///
+/// # Example
+/// ```
+/// use bel::{Value, vlist};
+///
+/// let list = vlist![1, "two", vlist![3.0]];
+/// assert_eq!(list, Value::list_from([Value::from(1), Value::from("two"), Value::from(vec![3.0])]));
+/// ```
+#[macro_export]
+macro_rules! vlist {
+    () => {
+        $crate::Value::list_from(::std::iter::empty())
+    };
+    ($($value:expr),+ $(,)?) => {
+        $crate::Value::list_from([$($crate::Value::from($value)),+])
+    };
+}
+
+/// Builds a [`Value::Map`](crate::Value::Map) from `key => value` pairs, where the keys can be
+/// converted into a [`Key`](crate::objects::Key) and the values into a [`Value`](crate::Value).
+/// If a key appears several times, the last value is kept.
+///
+/// # Example
+/// ```
+/// use bel::{Value, vlist, vmap};
+///
+/// let map = vmap! {
+///     "name" => "bel",
+///     "tags" => vlist!["cel", "policy"],
+///     "limits" => vmap! { 1 => true },
+/// };
+/// assert!(matches!(map, Value::Map(_)));
+/// ```
+#[macro_export]
+macro_rules! vmap {
+    () => {
+        $crate::Value::map_from(::std::iter::empty())
+    };
+    ($($key:expr => $value:expr),+ $(,)?) => {
+        $crate::Value::map_from([$(($crate::objects::Key::from($key), $crate::Value::from($value))),+])
+    };
+}
+
 pub(crate) use impl_conversions;
 pub(crate) use impl_handler;
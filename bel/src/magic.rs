@@ -9,7 +9,7 @@ use crate::{
 
 impl_conversions!(
     i64 => Value::Int,
-    // u64 => Value::UInt,
+    u64 => Value::UInt,
     f64 => Value::Float,
     Arc<String> => Value::String,
     Arc<Vec<u8>> => Value::Bytes,
@@ -238,6 +238,7 @@ impl From<Identifier> for String {
 /// pub fn sum(Arguments(args): Arguments) -> Value {
 ///     args.iter().fold(0.0, |acc, val| match val {
 ///         Value::Int(x) => *x as f64 + acc,
+///         Value::UInt(x) => *x as f64 + acc,
 ///         Value::Float(x) => *x + acc,
 ///         _ => acc,
 ///     }).into()
@@ -19,8 +19,9 @@ use crate::{
         ast::{EntryExpr, Expr, operators},
         value::CelVal,
     },
-    context::Context,
+    context::{Context, EvaluationBudget, EvaluationLimits},
     functions::FunctionContext,
+    intern::intern,
 };
 
 /// Timestamp values are limited to the range of values which can be serialized as a string:
@@ -30,7 +31,7 @@ use crate::{
 ///
 /// https://github.com/google/cel-spec/blob/master/doc/langdef.md#overflow
 #[cfg(feature = "time")]
-static MAX_TIMESTAMP: LazyLock<chrono::DateTime<chrono::FixedOffset>> = LazyLock::new(|| {
+pub(crate) static MAX_TIMESTAMP: LazyLock<chrono::DateTime<chrono::FixedOffset>> = LazyLock::new(|| {
     let naive = chrono::NaiveDate::from_ymd_opt(9999, 12, 31)
         .unwrap()
         .and_hms_nano_opt(23, 59, 59, 999_999_999)
@@ -39,7 +40,7 @@ static MAX_TIMESTAMP: LazyLock<chrono::DateTime<chrono::FixedOffset>> = LazyLock
 });
 
 #[cfg(feature = "time")]
-static MIN_TIMESTAMP: LazyLock<chrono::DateTime<chrono::FixedOffset>> = LazyLock::new(|| {
+pub(crate) static MIN_TIMESTAMP: LazyLock<chrono::DateTime<chrono::FixedOffset>> = LazyLock::new(|| {
     let naive = chrono::NaiveDate::from_ymd_opt(1, 1, 1)
         .unwrap()
         .and_hms_opt(0, 0, 0)
@@ -48,7 +49,6 @@ static MIN_TIMESTAMP: LazyLock<chrono::DateTime<chrono::FixedOffset>> = LazyLock
 });
 
 #[derive(Debug, PartialEq, Clone)]
-// #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Map {
     pub map: Arc<HashMap<Key, Value>>,
 }
@@ -60,20 +60,40 @@ impl PartialOrd for Map {
 }
 
 impl Map {
-    /// Returns a reference to the value corresponding to the key. Implicitly converts between int
-    /// and uint keys.
+    /// Returns a reference to the value corresponding to the key.
+    ///
+    /// Like numbers are compared by value with `==`, int and uint keys representing the same
+    /// number are interchangeable: `Key::Int(1)` finds the value of `Key::Uint(1)` if the map has
+    /// no `Key::Int(1)`, and vice versa. Negative ints and uints greater than `i64::MAX` only match
+    /// keys of their own type. Floats can't be keys, but indexing a map with an integral float,
+    /// e.g. `m[1.0]`, looks up the corresponding int or uint key.
     pub fn get(&self, key: &Key) -> Option<&Value> {
-        self.map.get(key)
-
-        // .or_else(|| {
-        //     // Also check keys that are cross type comparable.
-        //     let converted = match key {
-        //         Key::Int(k) => Key::Uint(u64::try_from(*k).ok()?),
-        //         // Key::Uint(k) => Key::Int(i64::try_from(*k).ok()?),
-        //         _ => return None,
-        //     };
-        //     self.map.get(&converted)
-        // })
+        self.map.get(key).or_else(|| {
+            // Also check keys that are cross type comparable.
+            let converted = match key {
+                Key::Int(k) => Key::Uint(u64::try_from(*k).ok()?),
+                Key::Uint(k) => Key::Int(i64::try_from(*k).ok()?),
+                _ => return None,
+            };
+            self.map.get(&converted)
+        })
+    }
+}
+
+/// Returns the key of a map indexed by a float: the int, or uint if it's too large for an int,
+/// equal to `f`. Returns `None` if `f` is not an integer.
+fn float_key(f: f64) -> Option<Key> {
+    if f.fract() != 0.0 {
+        // including NaN and infinities
+        return None;
+    }
+    // `i64::MAX as f64` and `u64::MAX as f64` are rounded up to the next power of two
+    if f >= i64::MIN as f64 && f < i64::MAX as f64 {
+        Some(Key::Int(f as i64))
+    } else if f >= 0.0 && f < u64::MAX as f64 {
+        Some(Key::Uint(f as u64))
+    } else {
+        None
     }
 }
 
@@ -81,7 +101,7 @@ impl Map {
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Key {
     Int(i64),
-    // Uint(u64),
+    Uint(u64),
     Bool(bool),
     String(Arc<String>),
 }
@@ -89,7 +109,7 @@ pub enum Key {
 /// Implement conversions from primitive types to [`Key`]
 impl From<String> for Key {
     fn from(v: String) -> Self {
-        Key::String(v.into())
+        Key::String(intern(v))
     }
 }
 
@@ -101,7 +121,7 @@ impl From<Arc<String>> for Key {
 
 impl<'a> From<&'a str> for Key {
     fn from(v: &'a str) -> Self {
-        Key::String(Arc::new(v.into()))
+        Key::String(intern(v.into()))
     }
 }
 
@@ -117,11 +137,17 @@ impl From<i64> for Key {
     }
 }
 
-// impl From<u64> for Key {
-//     fn from(v: u64) -> Self {
-//         Key::Uint(v)
-//     }
-// }
+impl From<i32> for Key {
+    fn from(v: i32) -> Self {
+        Key::Int(v as i64)
+    }
+}
+
+impl From<u64> for Key {
+    fn from(v: u64) -> Self {
+        Key::Uint(v)
+    }
+}
 
 impl serde::Serialize for Key {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -130,7 +156,7 @@ impl serde::Serialize for Key {
     {
         match self {
             Key::Int(v) => v.serialize(serializer),
-            // Key::Uint(v) => v.serialize(serializer),
+            Key::Uint(v) => v.serialize(serializer),
             Key::Bool(v) => v.serialize(serializer),
             Key::String(v) => v.serialize(serializer),
         }
@@ -141,7 +167,7 @@ impl Display for Key {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Key::Int(v) => write!(f, "{v}"),
-            // Key::Uint(v) => write!(f, "{v}"),
+            Key::Uint(v) => write!(f, "{v}"),
             Key::Bool(v) => write!(f, "{v}"),
             Key::String(v) => write!(f, "{v}"),
         }
@@ -156,7 +182,7 @@ impl TryInto<Key> for Value {
     fn try_into(self) -> Result<Key, Self::Error> {
         match self {
             Value::Int(v) => Ok(Key::Int(v)),
-            // Value::UInt(v) => Ok(Key::Uint(v)),
+            Value::UInt(v) => Ok(Key::Uint(v)),
             Value::String(v) => Ok(Key::String(v)),
             Value::Bool(v) => Ok(Key::Bool(v)),
             _ => Err(self),
@@ -196,7 +222,6 @@ impl TryIntoValue for Value {
 }
 
 #[derive(Debug, Clone)]
-// #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Value {
     List(Arc<Vec<Value>>),
     Map(Map),
@@ -205,7 +230,7 @@ pub enum Value {
 
     // Atoms
     Int(i64),
-    // UInt(u64),
+    UInt(u64),
     Float(f64),
     String(Arc<String>),
     Bytes(Arc<Vec<u8>>),
@@ -224,10 +249,10 @@ pub enum Value {
 impl From<CelVal> for Value {
     fn from(val: CelVal) -> Self {
         match val {
-            CelVal::String(s) => Value::String(Arc::new(s)),
+            CelVal::String(s) => Value::String(intern(s)),
             CelVal::Boolean(b) => Value::Bool(b),
             CelVal::Int(i) => Value::Int(i),
-            // CelVal::UInt(u) => Value::UInt(u),
+            CelVal::UInt(u) => Value::UInt(u),
             CelVal::Float(d) => Value::Float(d),
             CelVal::Bytes(bytes) => Value::Bytes(Arc::new(bytes)),
             CelVal::Null => Value::Null,
@@ -236,13 +261,13 @@ impl From<CelVal> for Value {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ValueType {
     List,
     Map,
     Function,
     Int,
-    // UInt,
+    UInt,
     Float,
     String,
     Bytes,
@@ -261,7 +286,7 @@ impl Display for ValueType {
             ValueType::Map => write!(f, "map"),
             ValueType::Function => write!(f, "function"),
             ValueType::Int => write!(f, "int"),
-            // ValueType::UInt => write!(f, "uint"),
+            ValueType::UInt => write!(f, "uint"),
             ValueType::Float => write!(f, "float"),
             ValueType::String => write!(f, "string"),
             ValueType::Bytes => write!(f, "bytes"),
@@ -276,13 +301,42 @@ impl Display for ValueType {
 }
 
 impl Value {
+    /// Builds a [`Value::List`] from values. See also the [`vlist!`](crate::vlist) macro.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::Value;
+    ///
+    /// let list = Value::list_from((1..=3).map(Value::Int));
+    /// assert_eq!(list, vec![Value::Int(1), Value::Int(2), Value::Int(3)].into());
+    /// ```
+    pub fn list_from<I: IntoIterator<Item = Value>>(values: I) -> Value {
+        Value::List(Arc::new(values.into_iter().collect()))
+    }
+
+    /// Builds a [`Value::Map`] from key-value pairs. If a key appears several times, the last
+    /// value is kept. See also the [`vmap!`](crate::vmap) macro.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::{Value, objects::Key};
+    ///
+    /// let map = Value::map_from([(Key::from("a"), Value::Int(1)), (Key::from("b"), Value::Int(2))]);
+    /// assert_eq!(map, std::collections::HashMap::from([("a", 1i64), ("b", 2)]).into());
+    /// ```
+    pub fn map_from<I: IntoIterator<Item = (Key, Value)>>(entries: I) -> Value {
+        Value::Map(Map {
+            map: Arc::new(entries.into_iter().collect()),
+        })
+    }
+
     pub fn type_of(&self) -> ValueType {
         match self {
             Value::List(_) => ValueType::List,
             Value::Map(_) => ValueType::Map,
             Value::Function(_, _) => ValueType::Function,
             Value::Int(_) => ValueType::Int,
-            // Value::UInt(_) => ValueType::UInt,
+            Value::UInt(_) => ValueType::UInt,
             Value::Float(_) => ValueType::Float,
             Value::String(_) => ValueType::String,
             Value::Bytes(_) => ValueType::Bytes,
@@ -305,6 +359,143 @@ impl Value {
             want: expected.to_string(),
         }
     }
+
+    /// Returns an approximation of the memory used by the value, in bytes.
+    ///
+    /// The estimate includes the size of the [`Value`] itself plus the heap memory owned by
+    /// strings, bytes, lists and maps, recursively. Shared (`Arc`) data is counted for every
+    /// reference, so the result is an upper bound that is suited for limits and cache eviction
+    /// decisions, not for exact accounting.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::Value;
+    ///
+    /// let small: Value = "a".into();
+    /// let big: Value = "a".repeat(1024).into();
+    /// assert!(big.approx_size() > small.approx_size() + 1000);
+    /// ```
+    pub fn approx_size(&self) -> usize {
+        let heap = match self {
+            Value::List(list) => list.iter().map(Value::approx_size).sum(),
+            Value::Map(map) => map
+                .map
+                .iter()
+                .map(|(key, value)| key.approx_size() + value.approx_size())
+                .sum(),
+            Value::Function(name, target) => name.len() + target.as_ref().map_or(0, |target| target.approx_size()),
+            Value::String(s) => s.len(),
+            Value::Bytes(b) => b.len(),
+            #[cfg(feature = "regex")]
+            Value::Regex(regex) => regex.as_str().len(),
+            _ => 0,
+        };
+        std::mem::size_of::<Value>() + heap
+    }
+
+    /// Compares two values structurally, recursing into lists and maps.
+    ///
+    /// Floats follow IEEE 754: `NaN` is never equal to anything, including itself, at any depth.
+    /// So a list or a map that contains a `NaN` is never equal to another value, not even to
+    /// itself.
+    ///
+    /// This differs from the `==` operator (and [`PartialEq`]) for lists and maps that share the
+    /// same allocation: because `Value` implements [`Eq`], the `Arc`s holding their elements are
+    /// considered equal without looking at the elements, so `x == x` is `true` even if `x` is
+    /// `[NaN]`. `deep_eq` always compares the elements.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::Value;
+    ///
+    /// let list: Value = vec![Value::Float(f64::NAN)].into();
+    /// assert!(list == list.clone());
+    /// assert!(!list.deep_eq(&list.clone()));
+    /// ```
+    pub fn deep_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::List(a), Value::List(b)) => a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.deep_eq(b)),
+            (Value::Map(a), Value::Map(b)) => {
+                a.map.len() == b.map.len()
+                    && a.map
+                        .iter()
+                        .all(|(key, a)| b.map.get(key).is_some_and(|b| a.deep_eq(b)))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Sets the value referenced by a JSON pointer ([RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)),
+    /// e.g. `/a/b` or `/list/0`. The empty pointer replaces the whole value.
+    ///
+    /// Missing map keys along the path are created as empty maps, and `-` appends to a list. Map
+    /// keys are always strings. Shared lists and maps are cloned on write, so other values
+    /// referencing them are not modified.
+    ///
+    /// Returns an error if the pointer doesn't start with `/`, if a list index is invalid or out of
+    /// bounds, or if the path goes through a value that is neither a list nor a map.
+    ///
+    /// # Example
+    /// ```
+    /// use bel::Value;
+    ///
+    /// let mut value = bel::vmap! {};
+    /// value.set_pointer("/server/ports", vec![80].into()).unwrap();
+    /// value.set_pointer("/server/ports/-", 443.into()).unwrap();
+    /// assert_eq!(value, bel::vmap! { "server" => bel::vmap! { "ports" => bel::vlist![80, 443] } });
+    /// ```
+    pub fn set_pointer(&mut self, pointer: &str, value: Value) -> Result<(), ExecutionError> {
+        if pointer.is_empty() {
+            *self = value;
+            return Ok(());
+        }
+        let Some(path) = pointer.strip_prefix('/') else {
+            return Err(ExecutionError::function_error(
+                "set_pointer",
+                format!("invalid JSON pointer: {pointer}"),
+            ));
+        };
+
+        let mut target = self;
+        for token in path.split('/') {
+            let token = token.replace("~1", "/").replace("~0", "~");
+            target = match target {
+                Value::Map(map) => Arc::make_mut(&mut map.map)
+                    .entry(Key::from(token))
+                    .or_insert_with(|| Value::map_from(std::iter::empty())),
+                Value::List(list) => {
+                    let list = Arc::make_mut(list);
+                    let idx = if token == "-" {
+                        list.push(Value::map_from(std::iter::empty()));
+                        list.len() - 1
+                    } else {
+                        token
+                            .parse::<usize>()
+                            .map_err(|_| ExecutionError::UnsupportedListIndex(token.into()))?
+                    };
+                    match list.get_mut(idx) {
+                        Some(item) => item,
+                        None => return Err(ExecutionError::UnsupportedListIndex(Value::UInt(idx as u64))),
+                    }
+                }
+                other => return Err(ExecutionError::UnsupportedIndex(other.clone(), token.into())),
+            };
+        }
+        *target = value;
+        Ok(())
+    }
+}
+
+impl Key {
+    /// Returns an approximation of the memory used by the key, in bytes.
+    /// See [`Value::approx_size`].
+    pub fn approx_size(&self) -> usize {
+        let heap = match self {
+            Key::String(s) => s.len(),
+            _ => 0,
+        };
+        std::mem::size_of::<Key>() + heap
+    }
 }
 
 impl From<&Value> for Value {
@@ -320,7 +511,7 @@ impl PartialEq for Value {
             (Value::List(a), Value::List(b)) => a == b,
             (Value::Function(a1, a2), Value::Function(b1, b2)) => a1 == b1 && a2 == b2,
             (Value::Int(a), Value::Int(b)) => a == b,
-            // (Value::UInt(a), Value::UInt(b)) => a == b,
+            (Value::UInt(a), Value::UInt(b)) => a == b,
             (Value::Float(a), Value::Float(b)) => a == b,
             (Value::String(a), Value::String(b)) => a == b,
             (Value::Bytes(a), Value::Bytes(b)) => a == b,
@@ -331,20 +522,12 @@ impl PartialEq for Value {
             #[cfg(feature = "time")]
             (Value::Timestamp(a), Value::Timestamp(b)) => a == b,
             // Allow different numeric types to be compared without explicit casting.
-            // (Value::Int(a), Value::UInt(b)) => a
-            //     .to_owned()
-            //     .try_into()
-            //     .map(|a: u64| a == *b)
-            //     .unwrap_or(false),
+            (Value::Int(a), Value::UInt(b)) => a.to_owned().try_into().map(|a: u64| a == *b).unwrap_or(false),
             (Value::Int(a), Value::Float(b)) => (*a as f64) == *b,
-            // (Value::UInt(a), Value::Int(b)) => a
-            //     .to_owned()
-            //     .try_into()
-            //     .map(|a: i64| a == *b)
-            //     .unwrap_or(false),
-            // (Value::UInt(a), Value::Float(b)) => (*a as f64) == *b,
+            (Value::UInt(a), Value::Int(b)) => a.to_owned().try_into().map(|a: i64| a == *b).unwrap_or(false),
+            (Value::UInt(a), Value::Float(b)) => (*a as f64) == *b,
             (Value::Float(a), Value::Int(b)) => *a == (*b as f64),
-            // (Value::Float(a), Value::UInt(b)) => *a == (*b as f64),
+            (Value::Float(a), Value::UInt(b)) => *a == (*b as f64),
             #[cfg(feature = "ip")]
             (Value::Ip(a), Value::Ip(b)) => a == b,
             (_, _) => false,
@@ -358,9 +541,11 @@ impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
             (Value::Int(a), Value::Int(b)) => Some(a.cmp(b)),
-            // (Value::UInt(a), Value::UInt(b)) => Some(a.cmp(b)),
+            (Value::UInt(a), Value::UInt(b)) => Some(a.cmp(b)),
             (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
             (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            // Bytes are compared lexicographically, so a prefix is less than the longer value.
+            (Value::Bytes(a), Value::Bytes(b)) => Some(a.cmp(b)),
             (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
             (Value::Null, Value::Null) => Some(Ordering::Equal),
             #[cfg(feature = "time")]
@@ -368,24 +553,24 @@ impl PartialOrd for Value {
             #[cfg(feature = "time")]
             (Value::Timestamp(a), Value::Timestamp(b)) => Some(a.cmp(b)),
             // Allow different numeric types to be compared without explicit casting.
-            // (Value::Int(a), Value::UInt(b)) => Some(
-            //     a.to_owned()
-            //         .try_into()
-            //         .map(|a: u64| a.cmp(b))
-            //         // If the i64 doesn't fit into a u64 it must be less than 0.
-            //         .unwrap_or(Ordering::Less),
-            // ),
+            (Value::Int(a), Value::UInt(b)) => Some(
+                a.to_owned()
+                    .try_into()
+                    .map(|a: u64| a.cmp(b))
+                    // If the i64 doesn't fit into a u64 it must be less than 0.
+                    .unwrap_or(Ordering::Less),
+            ),
             (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
-            // (Value::UInt(a), Value::Int(b)) => Some(
-            //     a.to_owned()
-            //         .try_into()
-            //         .map(|a: i64| a.cmp(b))
-            //         // If the u64 doesn't fit into a i64 it must be greater than i64::MAX.
-            //         .unwrap_or(Ordering::Greater),
-            // ),
-            // (Value::UInt(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
+            (Value::UInt(a), Value::Int(b)) => Some(
+                a.to_owned()
+                    .try_into()
+                    .map(|a: i64| a.cmp(b))
+                    // If the u64 doesn't fit into a i64 it must be greater than i64::MAX.
+                    .unwrap_or(Ordering::Greater),
+            ),
+            (Value::UInt(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
             (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
-            // (Value::Float(a), Value::UInt(b)) => a.partial_cmp(&(*b as f64)),
+            (Value::Float(a), Value::UInt(b)) => a.partial_cmp(&(*b as f64)),
             #[cfg(feature = "ip")]
             (Value::Ip(a), Value::Ip(b)) => Some(a.cmp(b)),
             _ => None,
@@ -397,7 +582,7 @@ impl From<&Key> for Value {
     fn from(value: &Key) -> Self {
         match value {
             Key::Int(v) => Value::Int(*v),
-            // Key::Uint(v) => Value::UInt(*v),
+            Key::Uint(v) => Value::UInt(*v),
             Key::Bool(v) => Value::Bool(*v),
             Key::String(v) => Value::String(v.clone()),
         }
@@ -408,7 +593,7 @@ impl From<Key> for Value {
     fn from(value: Key) -> Self {
         match value {
             Key::Int(v) => Value::Int(v),
-            // Key::Uint(v) => Value::UInt(v),
+            Key::Uint(v) => Value::UInt(v),
             Key::Bool(v) => Value::Bool(v),
             Key::String(v) => Value::String(v),
         }
@@ -428,6 +613,13 @@ impl<T: Into<Value>> From<Vec<T>> for Value {
     }
 }
 
+// Collect an iterator into a Value::List
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Value::List(Arc::new(iter.into_iter().map(Into::into).collect()))
+    }
+}
+
 // Convert Vec<u8> to Value
 impl From<Vec<u8>> for Value {
     fn from(v: Vec<u8>) -> Self {
@@ -438,13 +630,13 @@ impl From<Vec<u8>> for Value {
 // Convert String to Value
 impl From<String> for Value {
     fn from(v: String) -> Self {
-        Value::String(v.into())
+        Value::String(intern(v))
     }
 }
 
 impl From<&str> for Value {
     fn from(v: &str) -> Self {
-        Value::String(v.to_string().into())
+        Value::String(intern(v.to_string()))
     }
 }
 
@@ -490,6 +682,22 @@ impl Value {
 
     #[inline(always)]
     pub fn resolve(expr: &Expression, ctx: &Context) -> ResolveResult {
+        let Some(budget) = ctx.budget() else {
+            let limits = ctx.evaluation_limits();
+            if limits == EvaluationLimits::default() {
+                return Value::resolve_node(expr, ctx);
+            }
+            // the evaluation starts here, the nodes below share the budget of this scope
+            let budget = EvaluationBudget::new(limits);
+            return Value::resolve(expr, &ctx.new_budgeted_scope(&budget));
+        };
+        budget.enter()?;
+        let result = Value::resolve_node(expr, ctx);
+        budget.exit();
+        result
+    }
+
+    fn resolve_node(expr: &Expression, ctx: &Context) -> ResolveResult {
         match &expr.expr {
             Expr::Literal(val) => Ok(val.clone().into()),
             Expr::Call(call) => {
@@ -570,25 +778,23 @@ impl Value {
                             )
                             .into();
                         }
-                        // operators::IN => {
-                        //     let left = Value::resolve(&call.args[0], ctx)?;
-                        //     let right = Value::resolve(&call.args[1], ctx)?;
-                        //     match (left, right) {
-                        //         (Value::String(l), Value::String(r)) => {
-                        //             return Value::Bool(r.contains(&*l)).into();
-                        //         }
-                        //         (any, Value::List(v)) => {
-                        //             return Value::Bool(v.contains(&any)).into();
-                        //         }
-                        //         (any, Value::Map(m)) => match any.try_into() {
-                        //             Ok(key) => return Value::Bool(m.map.contains_key(&key)).into(),
-                        //             Err(_) => return Value::Bool(false).into(),
-                        //         },
-                        //         (left, right) => {
-                        //             Err(ExecutionError::ValuesNotComparable(left, right))?
-                        //         }
-                        //     }
-                        // }
+                        operators::IN => {
+                            let left = Value::resolve(&call.args[0], ctx)?;
+                            let right = Value::resolve(&call.args[1], ctx)?;
+                            return match (left, right) {
+                                (Value::String(l), Value::String(r)) => Value::Bool(r.contains(l.as_str())).into(),
+                                (any, Value::List(v)) => Value::Bool(v.contains(&any)).into(),
+                                (Value::Float(f), Value::Map(m)) => {
+                                    Value::Bool(float_key(f).is_some_and(|key| m.get(&key).is_some())).into()
+                                }
+                                // values that can't be map keys are never in the map, like in cel-go
+                                (any, Value::Map(m)) => match any.try_into() {
+                                    Ok(key) => Value::Bool(m.get(&key).is_some()).into(),
+                                    Err(_) => Value::Bool(false).into(),
+                                },
+                                (left, right) => Err(ExecutionError::ValuesNotComparable(left, right)),
+                            };
+                        }
                         operators::LOGICAL_OR => {
                             let left = Value::resolve(&call.args[0], ctx)?;
                             return if left.to_bool()? {
@@ -614,10 +820,15 @@ impl Value {
                                 (Value::List(items), Value::Int(idx)) => {
                                     items.get(idx as usize).cloned().unwrap_or(Value::Null).into()
                                 }
+                                (Value::List(items), Value::UInt(idx)) => {
+                                    items.get(idx as usize).cloned().unwrap_or(Value::Null).into()
+                                }
+                                // strings are indexed by character, not by byte, so that
+                                // multibyte characters are never split
                                 (Value::String(str), Value::Int(idx)) => {
-                                    match str.get(idx as usize..(idx + 1) as usize) {
+                                    match usize::try_from(idx).ok().and_then(|idx| str.chars().nth(idx)) {
                                         None => Ok(Value::Null),
-                                        Some(str) => Ok(Value::String(str.to_string().into())),
+                                        Some(char) => Ok(Value::String(char.to_string().into())),
                                     }
                                 }
                                 (Value::Map(map), Value::String(property)) => {
@@ -629,11 +840,14 @@ impl Value {
                                 (Value::Map(map), Value::Int(property)) => {
                                     map.get(&property.into()).cloned().unwrap_or(Value::Null).into()
                                 }
-                                // (Value::Map(map), Value::UInt(property)) => map
-                                //     .get(&property.into())
-                                //     .cloned()
-                                //     .unwrap_or(Value::Null)
-                                //     .into(),
+                                (Value::Map(map), Value::UInt(property)) => {
+                                    map.get(&property.into()).cloned().unwrap_or(Value::Null).into()
+                                }
+                                (Value::Map(map), Value::Float(property)) => float_key(property)
+                                    .and_then(|key| map.get(&key))
+                                    .cloned()
+                                    .unwrap_or(Value::Null)
+                                    .into(),
                                 (Value::Map(_), index) => Err(ExecutionError::UnsupportedMapIndex(index)),
                                 (Value::List(_), index) => Err(ExecutionError::UnsupportedListIndex(index)),
                                 (value, index) => Err(ExecutionError::UnsupportedIndex(value, index)),
@@ -685,15 +899,10 @@ impl Value {
             Expr::Select(select) => {
                 let left = Value::resolve(select.operand.deref(), ctx)?;
                 if select.test {
+                    // only maps have fields: testing a field of any other value, such as a list
+                    // or null, is false instead of an error
                     match &left {
-                        Value::Map(map) => {
-                            for key in map.map.deref().keys() {
-                                if key.to_string().eq(&select.field) {
-                                    return Ok(Value::Bool(true));
-                                }
-                            }
-                            Ok(Value::Bool(false))
-                        }
+                        Value::Map(map) => Ok(Value::Bool(map.map.contains_key(&Key::from(select.field.as_str())))),
                         _ => Ok(Value::Bool(false)),
                     }
                 } else {
@@ -712,7 +921,12 @@ impl Value {
                 let mut map = HashMap::with_capacity(map_expr.entries.len());
                 for entry in map_expr.entries.iter() {
                     let (k, v) = match &entry.expr {
-                        EntryExpr::StructField(_) => panic!("WAT?"),
+                        EntryExpr::StructField(_) => {
+                            return Err(ExecutionError::UnexpectedType {
+                                got: "struct field".to_string(),
+                                want: "map entry".to_string(),
+                            });
+                        }
                         EntryExpr::MapEntry(e) => (&e.key, &e.value),
                     };
                     let key = Value::resolve(k, ctx)?
@@ -732,9 +946,12 @@ impl Value {
                 ctx.add_variable(&comprehension.accu_var, accu_init)
                     .expect("Failed to add accu variable");
 
+                // The loop condition is checked before each iteration, so macros such as `all`
+                // and `any` stop evaluating their predicate as soon as the result is known.
                 match iter {
                     Value::List(items) => {
                         for item in items.deref() {
+                            ctx.check_iteration()?;
                             if !Value::resolve(&comprehension.loop_cond, &ctx)?.to_bool()? {
                                 break;
                             }
@@ -745,6 +962,7 @@ impl Value {
                     }
                     Value::Map(map) => {
                         for key in map.map.deref().keys() {
+                            ctx.check_iteration()?;
                             if !Value::resolve(&comprehension.loop_cond, &ctx)?.to_bool()? {
                                 break;
                             }
@@ -753,11 +971,36 @@ impl Value {
                             ctx.add_variable_from_value(&comprehension.accu_var, accu);
                         }
                     }
-                    t => todo!("Support {t:?}"),
+                    value => {
+                        return Err(ExecutionError::UnexpectedType {
+                            got: value.type_of().to_string(),
+                            want: "list or map".to_string(),
+                        });
+                    }
                 }
                 Value::resolve(&comprehension.result, &ctx)
             }
-            Expr::Struct(_) => todo!("Support structs!"),
+            // There are no message types, so struct literals such as `Foo{a: 1}` are evaluated
+            // to a map of their fields, and the type name is ignored.
+            Expr::Struct(structure) => {
+                let mut map = HashMap::with_capacity(structure.entries.len());
+                for entry in structure.entries.iter() {
+                    let field = match &entry.expr {
+                        EntryExpr::StructField(field) => field,
+                        EntryExpr::MapEntry(_) => {
+                            return Err(ExecutionError::UnexpectedType {
+                                got: "map entry".to_string(),
+                                want: "struct field".to_string(),
+                            });
+                        }
+                    };
+                    let value = Value::resolve(&field.value, ctx)?;
+                    map.insert(Key::from(field.field.as_str()), value);
+                }
+                Ok(Value::Map(Map {
+                    map: Arc::from(map),
+                }))
+            }
             Expr::Unspecified => panic!("Can't evaluate Unspecified Expr"),
         }
     }
@@ -793,7 +1036,7 @@ impl Value {
     }
 
     #[inline(always)]
-    fn to_bool(&self) -> Result<bool, ExecutionError> {
+    pub(crate) fn to_bool(&self) -> Result<bool, ExecutionError> {
         match self {
             Value::Bool(v) => Ok(*v),
             _ => Err(ExecutionError::NoSuchOverload),
@@ -801,6 +1044,10 @@ impl Value {
     }
 }
 
+// As in CEL, the arithmetic operators are only defined between values of the same numeric type:
+// `1 + 2.0` is an error and must be written `Float(1) + 2.0`. Implicitly converting ints to floats
+// would silently lose precision above 2^53, and would make the type of the result depend on the
+// values. Only the comparisons mix numeric types, see `PartialEq` and `PartialOrd`.
 impl ops::Add<Value> for Value {
     type Output = ResolveResult;
 
@@ -812,10 +1059,10 @@ impl ops::Add<Value> for Value {
                 .ok_or(ExecutionError::Overflow("add", l.into(), r.into()))
                 .map(Value::Int),
 
-            // (Value::UInt(l), Value::UInt(r)) => l
-            //     .checked_add(r)
-            //     .ok_or(ExecutionError::Overflow("add", l.into(), r.into()))
-            //     .map(Value::UInt),
+            (Value::UInt(l), Value::UInt(r)) => l
+                .checked_add(r)
+                .ok_or(ExecutionError::Overflow("add", l.into(), r.into()))
+                .map(Value::UInt),
             (Value::Float(l), Value::Float(r)) => Value::Float(l + r).into(),
 
             (Value::List(mut l), Value::List(mut r)) => {
@@ -868,10 +1115,10 @@ impl ops::Sub<Value> for Value {
                 .ok_or(ExecutionError::Overflow("sub", l.into(), r.into()))
                 .map(Value::Int),
 
-            // (Value::UInt(l), Value::UInt(r)) => l
-            //     .checked_sub(r)
-            //     .ok_or(ExecutionError::Overflow("sub", l.into(), r.into()))
-            //     .map(Value::UInt),
+            (Value::UInt(l), Value::UInt(r)) => l
+                .checked_sub(r)
+                .ok_or(ExecutionError::Overflow("sub", l.into(), r.into()))
+                .map(Value::UInt),
             (Value::Float(l), Value::Float(r)) => Value::Float(l - r).into(),
 
             #[cfg(feature = "time")]
@@ -904,10 +1151,10 @@ impl ops::Div<Value> for Value {
                 }
             }
 
-            // (Value::UInt(l), Value::UInt(r)) => l
-            //     .checked_div(r)
-            //     .ok_or(ExecutionError::DivisionByZero(l.into()))
-            //     .map(Value::UInt),
+            (Value::UInt(l), Value::UInt(r)) => l
+                .checked_div(r)
+                .ok_or(ExecutionError::DivisionByZero(l.into()))
+                .map(Value::UInt),
             (Value::Float(l), Value::Float(r)) => Value::Float(l / r).into(),
 
             (left, right) => Err(ExecutionError::UnsupportedBinaryOperator("div", left, right)),
@@ -926,10 +1173,10 @@ impl ops::Mul<Value> for Value {
                 .ok_or(ExecutionError::Overflow("mul", l.into(), r.into()))
                 .map(Value::Int),
 
-            // (Value::UInt(l), Value::UInt(r)) => l
-            //     .checked_mul(r)
-            //     .ok_or(ExecutionError::Overflow("mul", l.into(), r.into()))
-            //     .map(Value::UInt),
+            (Value::UInt(l), Value::UInt(r)) => l
+                .checked_mul(r)
+                .ok_or(ExecutionError::Overflow("mul", l.into(), r.into()))
+                .map(Value::UInt),
             (Value::Float(l), Value::Float(r)) => Value::Float(l * r).into(),
 
             (left, right) => Err(ExecutionError::UnsupportedBinaryOperator("mul", left, right)),
@@ -953,10 +1200,10 @@ impl ops::Rem<Value> for Value {
                 }
             }
 
-            // (Value::UInt(l), Value::UInt(r)) => l
-            //     .checked_rem(r)
-            //     .ok_or(ExecutionError::RemainderByZero(l.into()))
-            //     .map(Value::UInt),
+            (Value::UInt(l), Value::UInt(r)) => l
+                .checked_rem(r)
+                .ok_or(ExecutionError::RemainderByZero(l.into()))
+                .map(Value::UInt),
             (left, right) => Err(ExecutionError::UnsupportedBinaryOperator("rem", left, right)),
         }
     }
@@ -1004,7 +1251,10 @@ fn checked_op(op: TsOp, lhs: &chrono::DateTime<chrono::FixedOffset>, rhs: &chron
 mod tests {
     use std::{collections::HashMap, sync::Arc};
 
-    use crate::{Context, ExecutionError, Program, Value, objects::Key};
+    use crate::{
+        Context, ExecutionError, Program, Value,
+        objects::{Key, Map},
+    };
 
     #[test]
     fn test_indexed_map_access() {
@@ -1030,25 +1280,144 @@ mod tests {
         assert_eq!(value, "one".into());
     }
 
+    #[test]
+    fn test_uint_map_keys_and_indexes() {
+        let mut context = Context::default();
+        let mut numbers = HashMap::new();
+        numbers.insert(Key::Int(1), "one".to_string());
+        numbers.insert(Key::Uint(2), "two".to_string());
+        context.add_variable_from_value("numbers", numbers);
+
+        for (script, expected) in [
+            ("numbers[1]", "one"),
+            ("numbers[1u]", "one"),
+            ("numbers[2]", "two"),
+            ("numbers[2u]", "two"),
+            ("[\"zero\", \"one\"][1u]", "one"),
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&context), Ok(expected.into()), "{script}");
+        }
+
+        // negative ints are never uint keys
+        let map = Map::from(HashMap::from([(Key::Uint(u64::MAX), Value::Null)]));
+        assert_eq!(map.get(&Key::Int(-1)), None);
+    }
+
+    #[test]
+    fn test_cross_type_map_keys() {
+        let ints = Map::from(HashMap::from([(Key::Int(1), Value::from("int"))]));
+        assert_eq!(ints.get(&Key::Uint(1)), Some(&Value::from("int")));
+        assert_eq!(ints.get(&Key::Uint(2)), None);
+
+        let uints = Map::from(HashMap::from([(Key::Uint(1), Value::from("uint"))]));
+        assert_eq!(uints.get(&Key::Int(1)), Some(&Value::from("uint")));
+        assert_eq!(uints.get(&Key::Int(-1)), None);
+
+        // a key of the same type takes precedence
+        let both = Map::from(HashMap::from([
+            (Key::Int(1), Value::from("int")),
+            (Key::Uint(1), Value::from("uint")),
+        ]));
+        assert_eq!(both.get(&Key::Int(1)), Some(&Value::from("int")));
+        assert_eq!(both.get(&Key::Uint(1)), Some(&Value::from("uint")));
+
+        // uints greater than i64::MAX have no int equivalent
+        let large = Map::from(HashMap::from([(Key::Uint(u64::MAX), Value::Null)]));
+        assert_eq!(large.get(&Key::Int(i64::MAX)), None);
+
+        for script in [
+            r#"{1: "a"}[1u] == "a""#,
+            r#"{1u: "a"}[1] == "a""#,
+            r#"{1: "a"}[1.0] == "a""#,
+            r#"{1u: "a"}[1.0] == "a""#,
+            r#"{18446744073709551615u: "a"}[18446744073709549568.0] == null"#,
+            r#"{1: "a"}[1.5] == null"#,
+            r#"1u in {1: "a"} && 1 in {1u: "a"}"#,
+            r#"1.0 in {1: "a"} && 1.0 in {1u: "a"}"#,
+            r#"!(1.5 in {1: "a"}) && !(-1 in {18446744073709551615u: "a"})"#,
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&Context::default()), Ok(Value::Bool(true)), "{script}");
+        }
+    }
+
+    #[test]
+    fn test_uint_math_and_compare() {
+        let context = Context::default();
+        for script in [
+            "1u + 2u == 3u",
+            "10u - 3u == 7u",
+            "4u * 5u == 20u",
+            "7u / 2u == 3u",
+            "7u % 2u == 1u",
+            "1 == 1u && 1u == 1 && 1u == 1.0 && 1.0 == 1u",
+            "-1 != 18446744073709551615u",
+            "18446744073709551615u > 9223372036854775807",
+            "1u < 1.5 && 2.5 > 2u",
+            "Uint(\"42\") == 42u && Uint(-0.0) == 0u",
+            "String(42u) == \"42\" && Float(42u) == 42.0",
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&context), Ok(true.into()), "{script}");
+        }
+
+        for script in ["Uint(-1)", "Uint(1e20)", "Int(18446744073709551615u)", "-1u", "1u + 1"] {
+            let program = Program::compile(script).unwrap();
+            assert!(program.execute(&context).is_err(), "{script}");
+        }
+    }
+
     #[test]
     fn test_heterogeneous_compare() {
         let context = Context::default();
 
-        // let program = Program::compile("1 < Uint(2)").unwrap();
-        // let value = program.execute(&context).unwrap();
-        // assert_eq!(value, true.into());
+        let program = Program::compile("1 < Uint(2)").unwrap();
+        let value = program.execute(&context).unwrap();
+        assert_eq!(value, true.into());
 
         let program = Program::compile("1 < 1.1").unwrap();
         let value = program.execute(&context).unwrap();
         assert_eq!(value, true.into());
 
-        // let program = Program::compile("Uint(0) > -10").unwrap();
-        // let value = program.execute(&context).unwrap();
-        // assert_eq!(
-        //     value,
-        //     true.into(),
-        //     "negative signed ints should be less than uints"
-        // );
+        let program = Program::compile("Uint(0) > -10").unwrap();
+        let value = program.execute(&context).unwrap();
+        assert_eq!(value, true.into(), "negative signed ints should be less than uints");
+    }
+
+    #[test]
+    fn test_mixed_numeric_arithmetic() {
+        test_execution_error(
+            "1 + 2.0",
+            ExecutionError::UnsupportedBinaryOperator("add", Value::Int(1), Value::Float(2.0)),
+        );
+        test_execution_error(
+            "5 / 2.0",
+            ExecutionError::UnsupportedBinaryOperator("div", Value::Int(5), Value::Float(2.0)),
+        );
+        test_execution_error(
+            "2.0 * Uint(3)",
+            ExecutionError::UnsupportedBinaryOperator("mul", Value::Float(2.0), Value::UInt(3)),
+        );
+        test_execution_error(
+            "Uint(3) - 1",
+            ExecutionError::UnsupportedBinaryOperator("sub", Value::UInt(3), Value::Int(1)),
+        );
+        test_execution_error(
+            "5.0 % 2",
+            ExecutionError::UnsupportedBinaryOperator("rem", Value::Float(5.0), Value::Int(2)),
+        );
+
+        // the conversions must be explicit
+        let context = Context::default();
+        for (script, expected) in [
+            ("Float(1) + 2.0", Value::Float(3.0)),
+            ("Float(5) / 2.0", Value::Float(2.5)),
+            ("5 / Int(2.0)", Value::Int(2)),
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&context).unwrap(), expected, "{script}");
+        }
     }
 
     #[test]
@@ -1068,6 +1437,59 @@ mod tests {
         assert!(result.is_err(), "NaN should not be comparable with inequality operators");
     }
 
+    #[test]
+    fn test_bytes_compare() {
+        let context = Context::default();
+
+        let tests = [
+            (r#"b"a" < b"b""#, true),
+            (r#"b"b" < b"a""#, false),
+            (r#"b"abc" == b"abc""#, true),
+            (r#"b"abc" <= b"abc""#, true),
+            (r#"b"abc" >= b"abc""#, true),
+            (r#"b"a" < b"ab""#, true),
+            (r#"b"ab" > b"a""#, true),
+            (r#"b"" < b"a""#, true),
+            (r#"b"\xff" > b"a""#, true),
+        ];
+        for (script, expected) in tests {
+            let program = Program::compile(script).unwrap();
+            let value = program.execute(&context).unwrap();
+            assert_eq!(value, expected.into(), "{script}");
+        }
+    }
+
+    #[test]
+    fn test_in() {
+        let context = Context::default();
+
+        let tests = [
+            ("1 in [1, 2, 3]", true),
+            ("4 in [1, 2, 3]", false),
+            ("1u in [1, 2] && 2.0 in [1, 2]", true),
+            ("[1] in [[1], [2]]", true),
+            (r#""x" in {"x": 1}"#, true),
+            (r#""y" in {"x": 1}"#, false),
+            ("1u in {1: true} && 2 in {2u: true}", true),
+            ("[1] in {1: true}", false),
+            ("1.0 in {1: true}", false),
+            (r#""ell" in "hello""#, true),
+            (r#""" in "hello""#, true),
+            (r#""hello" in "ell""#, false),
+        ];
+        for (script, expected) in tests {
+            let program = Program::compile(script).unwrap();
+            let value = program.execute(&context).unwrap();
+            assert_eq!(value, expected.into(), "{script}");
+        }
+
+        let program = Program::compile(r#"1 in "1""#).unwrap();
+        assert_eq!(
+            program.execute(&context),
+            Err(ExecutionError::ValuesNotComparable(1.into(), "1".into()))
+        );
+    }
+
     #[test]
     fn test_invalid_compare() {
         let context = Context::default();
@@ -1136,6 +1558,30 @@ mod tests {
         assert_eq!(result.unwrap(), Value::Null);
     }
 
+    #[test]
+    fn string_index() {
+        let mut context = Context::default();
+        context.add_variable("ascii", "hello").unwrap();
+        context.add_variable("unicode", "héllo wörld 👋").unwrap();
+
+        for (script, expected) in [
+            ("ascii[0]", Value::from("h")),
+            ("ascii[4]", Value::from("o")),
+            ("unicode[1]", Value::from("é")),
+            ("unicode[2]", Value::from("l")),
+            ("unicode[7]", Value::from("ö")),
+            ("unicode[12]", Value::from("👋")),
+            ("ascii[5]", Value::Null),
+            ("unicode[13]", Value::Null),
+            ("unicode[-1]", Value::Null),
+            (r#""é"[0]"#, Value::from("é")),
+            (r#"""[0]"#, Value::Null),
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&context).unwrap(), expected, "{script}");
+        }
+    }
+
     #[test]
     fn reference_to_value() {
         let test = "example".to_string();
@@ -1181,28 +1627,305 @@ mod tests {
         }
     }
 
-    // #[test]
-    // fn invalid_uint_math() {
-    //     use ExecutionError::*;
-
-    //     let cases = [
-    //         ("1u / 0u", DivisionByZero(1u64.into())),
-    //         ("1u % 0u", RemainderByZero(1u64.into())),
-    //         (
-    //             &format!("{}u + 1u", u64::MAX),
-    //             Overflow("add", u64::MAX.into(), 1u64.into()),
-    //         ),
-    //         ("0u - 1u", Overflow("sub", 0u64.into(), 1u64.into())),
-    //         (
-    //             &format!("{}u * 2u", u64::MAX),
-    //             Overflow("mul", u64::MAX.into(), 2u64.into()),
-    //         ),
-    //     ];
-
-    //     for (expr, err) in cases {
-    //         test_execution_error(expr, err);
-    //     }
-    // }
+    #[test]
+    fn invalid_uint_math() {
+        use ExecutionError::*;
+
+        let cases = [
+            ("1u / 0u", DivisionByZero(1u64.into())),
+            ("1u % 0u", RemainderByZero(1u64.into())),
+            (&format!("{}u + 1u", u64::MAX), Overflow("add", u64::MAX.into(), 1u64.into())),
+            ("0u - 1u", Overflow("sub", 0u64.into(), 1u64.into())),
+            (&format!("{}u * 2u", u64::MAX), Overflow("mul", u64::MAX.into(), 2u64.into())),
+        ];
+
+        for (expr, err) in cases {
+            test_execution_error(expr, err);
+        }
+    }
+
+    #[test]
+    fn test_approx_size_atoms() {
+        let base = std::mem::size_of::<Value>();
+        assert_eq!(Value::Null.approx_size(), base);
+        assert_eq!(Value::Int(42).approx_size(), base);
+        assert_eq!(Value::Float(4.2).approx_size(), base);
+        assert_eq!(Value::Bool(true).approx_size(), base);
+        assert_eq!(Value::from("abc").approx_size(), base + 3);
+        assert_eq!(Value::from(vec![1u8, 2, 3, 4]).approx_size(), base + 4);
+    }
+
+    #[test]
+    fn test_approx_size_nested() {
+        let base = std::mem::size_of::<Value>();
+
+        let list: Value = vec![Value::Int(1); 10].into();
+        assert_eq!(list.approx_size(), base + 10 * base);
+
+        let nested: Value = vec![list.clone(), list.clone()].into();
+        assert_eq!(nested.approx_size(), base + 2 * list.approx_size());
+
+        let small: Value = HashMap::from([("a", "x".to_string())]).into();
+        let big: Value = HashMap::from([("a", "x".repeat(1000))]).into();
+        assert_eq!(big.approx_size() - small.approx_size(), 999);
+
+        let map: Value = HashMap::from([("key", list.clone())]).into();
+        assert!(map.approx_size() > list.approx_size());
+    }
+
+    #[test]
+    fn test_deep_eq() {
+        let nan = Value::Float(f64::NAN);
+
+        let list: Value = vec![Value::Int(1), Value::from("a")].into();
+        assert!(list.deep_eq(&vec![Value::Int(1), Value::from("a")].into()));
+        assert!(!list.deep_eq(&vec![Value::Int(1)].into()));
+        assert!(Value::Int(1).deep_eq(&Value::Float(1.0)));
+
+        let nested: Value = vec![list.clone(), HashMap::from([("a", list.clone())]).into()].into();
+        assert!(nested.deep_eq(&nested.clone()));
+
+        let map: Value = HashMap::from([("a", 1.5), ("b", 2.0)]).into();
+        assert!(map.deep_eq(&HashMap::from([("b", 2.0), ("a", 1.5)]).into()));
+        assert!(!map.deep_eq(&HashMap::from([("a", 1.5)]).into()));
+        assert!(!map.deep_eq(&HashMap::from([("a", 1.5), ("c", 2.0)]).into()));
+
+        // NaN is never equal, at any depth
+        assert!(!nan.deep_eq(&nan));
+        let with_nan: Value = vec![Value::Int(1), nan.clone()].into();
+        assert!(!with_nan.deep_eq(&vec![Value::Int(1), nan.clone()].into()));
+        assert!(!with_nan.deep_eq(&with_nan.clone()));
+        let nested_nan: Value = HashMap::from([("a", vec![with_nan.clone()])]).into();
+        assert!(!nested_nan.deep_eq(&nested_nan.clone()));
+
+        // unlike `==`, which short-circuits on shared allocations
+        assert!(with_nan == with_nan.clone());
+        assert!(with_nan != vec![Value::Int(1), nan.clone()].into());
+    }
+
+    #[test]
+    fn test_equality_ordering() {
+        // maps are equal whatever the insertion order of their entries, lists are ordered
+        let keys: Vec<String> = (0..64).map(|i| format!("key{i}")).collect();
+        let forward: HashMap<_, _> = keys.iter().map(|k| (k.as_str(), vec![k.as_str(), "x"])).collect();
+        let backward: HashMap<_, _> = keys.iter().rev().map(|k| (k.as_str(), vec![k.as_str(), "x"])).collect();
+        let forward = Value::from(forward);
+        assert_eq!(forward, Value::from(backward.clone()));
+        assert!(forward.deep_eq(&Value::from(backward.clone())));
+
+        let reversed: HashMap<_, _> = keys.iter().map(|k| (k.as_str(), vec!["x", k.as_str()])).collect();
+        assert_ne!(forward, Value::from(reversed.clone()));
+        assert!(!forward.deep_eq(&Value::from(reversed)));
+
+        let list: Value = vec![Value::Int(1), Value::Int(2)].into();
+        assert_ne!(list, vec![Value::Int(2), Value::Int(1)].into());
+        assert!(!list.deep_eq(&vec![Value::Int(2), Value::Int(1)].into()));
+
+        let context = Context::default();
+        for (script, expected) in [
+            (r#"{"a": 1, "b": 2} == {"b": 2, "a": 1}"#, true),
+            (r#"{"a": {"x": 1, "y": [1]}} == {"a": {"y": [1], "x": 1}}"#, true),
+            (r#"[{"a": 1, "b": 2}] == [{"b": 2, "a": 1}]"#, true),
+            ("[1, 2] == [2, 1]", false),
+            (r#"{"a": [1, 2]} == {"a": [2, 1]}"#, false),
+            (r#"[[1, 2], [3]] == [[3], [1, 2]]"#, false),
+            (r#"{"a": 1} == {"a": 1, "b": 2}"#, false),
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&context), Ok(Value::Bool(expected)), "{script}");
+        }
+    }
+
+    #[test]
+    fn test_set_pointer() {
+        let mut value = crate::vmap! { "a" => crate::vmap! { "b" => 1 }, "list" => crate::vlist![1, 2] };
+        let original = value.clone();
+
+        // new nested keys, creating intermediate maps
+        value.set_pointer("/a/c", "new".into()).unwrap();
+        value.set_pointer("/x/y/z", true.into()).unwrap();
+        value.set_pointer("/a~1b/~0", 3.into()).unwrap();
+        // overwrite existing values
+        value.set_pointer("/a/b", 2.into()).unwrap();
+        value.set_pointer("/list/0", 10.into()).unwrap();
+        value.set_pointer("/list/-", 3.into()).unwrap();
+
+        let expected = crate::vmap! {
+            "a" => crate::vmap! { "b" => 2, "c" => "new" },
+            "x" => crate::vmap! { "y" => crate::vmap! { "z" => true } },
+            "a/b" => crate::vmap! { "~" => 3 },
+            "list" => crate::vlist![10, 2, 3],
+        };
+        assert_eq!(value, expected);
+        // the clone still references the original data
+        assert_eq!(
+            original,
+            crate::vmap! { "a" => crate::vmap! { "b" => 1 }, "list" => crate::vlist![1, 2] }
+        );
+
+        value.set_pointer("", Value::Null).unwrap();
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn test_set_pointer_errors() {
+        let mut value = crate::vmap! { "a" => 1, "list" => crate::vlist![1] };
+        let original = value.clone();
+
+        assert_eq!(
+            value.set_pointer("/a/b", 2.into()),
+            Err(ExecutionError::UnsupportedIndex(Value::Int(1), "b".into()))
+        );
+        assert_eq!(
+            value.set_pointer("/list/x", 2.into()),
+            Err(ExecutionError::UnsupportedListIndex("x".into()))
+        );
+        assert_eq!(
+            value.set_pointer("/list/1", 2.into()),
+            Err(ExecutionError::UnsupportedListIndex(Value::UInt(1)))
+        );
+        assert!(value.set_pointer("a", 2.into()).is_err());
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn test_comprehension_early_exit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let tests = [
+            ("[1, 2, 3, 4, 5].all(x, visit(x) < 2)", false, 2),
+            ("[1, 2, 3, 4, 5].all(x, visit(x) < 10)", true, 5),
+            ("[1, 2, 3, 4, 5].any(x, visit(x) == 3)", true, 3),
+            ("[1, 2, 3, 4, 5].any(x, visit(x) == 10)", false, 5),
+            ("{1: 1, 2: 2, 3: 3}.all(x, visit(x) > 10)", false, 1),
+            ("[1, 2, 3].map(x, visit(x)) == [1, 2, 3]", true, 3),
+        ];
+
+        for (script, expected, expected_visits) in tests {
+            let visits = Arc::new(AtomicUsize::new(0));
+            let mut context = Context::default();
+            let counter = visits.clone();
+            context.add_function("visit", move |x: i64| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                x
+            });
+
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&context), Ok(expected.into()), "{script}");
+            assert_eq!(visits.load(Ordering::Relaxed), expected_visits, "{script}");
+        }
+    }
+
+    #[test]
+    fn test_builders() {
+        let built = crate::vmap! {
+            "name" => "bel",
+            "versions" => crate::vlist![1, 2.5, "three"],
+            "nested" => crate::vmap! { 1 => true, true => crate::vlist![], "empty" => crate::vmap! {} },
+        };
+
+        let mut nested = HashMap::new();
+        nested.insert(Key::Int(1), Value::Bool(true));
+        nested.insert(Key::Bool(true), Value::List(Arc::new(vec![])));
+        nested.insert(
+            Key::String(Arc::new("empty".to_string())),
+            Value::Map(Map {
+                map: Arc::new(HashMap::new()),
+            }),
+        );
+        let mut map = HashMap::new();
+        map.insert(Key::String(Arc::new("name".to_string())), Value::String(Arc::new("bel".to_string())));
+        map.insert(
+            Key::String(Arc::new("versions".to_string())),
+            Value::List(Arc::new(vec![
+                Value::Int(1),
+                Value::Float(2.5),
+                Value::String(Arc::new("three".to_string())),
+            ])),
+        );
+        map.insert(
+            Key::String(Arc::new("nested".to_string())),
+            Value::Map(Map {
+                map: Arc::new(nested),
+            }),
+        );
+        let manual = Value::Map(Map {
+            map: Arc::new(map),
+        });
+
+        assert_eq!(built, manual);
+        assert_eq!(crate::vlist![1, 2,], Value::list_from([Value::Int(1), Value::Int(2)]));
+        assert_eq!(
+            crate::vmap! { "a" => 1, "a" => 2 },
+            Value::map_from([(Key::from("a"), Value::Int(2))])
+        );
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let values = vec![
+            Value::Int(1),
+            Value::from("a"),
+            Value::Null,
+            Value::List(Arc::new(vec![Value::Bool(true)])),
+        ];
+        let collected: Value = values.clone().into_iter().collect();
+        assert_eq!(collected, Value::List(Arc::new(values)));
+
+        let collected: Value = (1..=3).map(|i: i64| i * 2).collect();
+        assert_eq!(
+            collected,
+            Value::List(Arc::new(vec![Value::Int(2), Value::Int(4), Value::Int(6)]))
+        );
+        assert_eq!(collected, Value::from(vec![2i64, 4, 6]));
+
+        let collected: Value = std::iter::empty::<Value>().collect();
+        assert_eq!(collected, Value::List(Arc::new(Vec::new())));
+    }
+
+    #[test]
+    fn test_comprehension_over_non_iterable() {
+        let mut context = Context::default();
+        context.add_variable_from_value("n", Value::Null);
+
+        for (script, got) in [
+            (r#""abc".map(c, c)"#, "string"),
+            ("(1).all(x, x > 0)", "int"),
+            ("n.exists(x, x)", "null"),
+            ("null.filter(x, true)", "null"),
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(
+                program.execute(&context),
+                Err(ExecutionError::UnexpectedType {
+                    got: got.to_string(),
+                    want: "list or map".to_string(),
+                }),
+                "{script}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_struct_literal() {
+        let mut context = Context::default();
+        context.add_variable_from_value("x", 2);
+
+        for script in [
+            r#"Foo{a: 1, b: "b"} == {"a": 1, "b": "b"}"#,
+            "Foo{a: x * 2}.a == 4",
+            "my.pkg.Foo{} == {}",
+            "Foo{inner: Bar{a: [1, 2]}}.inner.a == [1, 2]",
+            r#"has(Foo{a: 1}.a) && !has(Foo{a: 1}.b)"#,
+        ] {
+            let program = Program::compile(script).unwrap();
+            assert_eq!(program.execute(&context), Ok(Value::Bool(true)), "{script}");
+        }
+
+        // errors in the fields are returned instead of panicking
+        let program = Program::compile("Foo{a: 1 / 0}").unwrap();
+        assert_eq!(program.execute(&context), Err(ExecutionError::DivisionByZero(1.into())));
+    }
 
     #[test]
     fn test_function_identifier() {
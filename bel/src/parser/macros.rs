@@ -19,6 +19,8 @@ pub fn find_expander(func_name: &str, target: Option<&IdedExpr>, args: &[IdedExp
         // }
         operators::MAP if args.len() == 2 && target.is_some() => Some(map_macro_expander),
         operators::FILTER if args.len() == 2 && target.is_some() => Some(filter_macro_expander),
+        operators::SORT_BY if args.len() == 2 && target.is_some() => Some(sort_by_macro_expander),
+        operators::REDUCE if args.len() == 4 && target.is_some() => Some(reduce_macro_expander),
         _ => None,
     }
 }
@@ -320,6 +322,98 @@ fn filter_macro_expander(
     }))))
 }
 
+fn sort_by_macro_expander(
+    helper: &mut MacroExprHelper,
+    target: Option<IdedExpr>,
+    mut args: Vec<IdedExpr>,
+) -> Result<IdedExpr, ParseError> {
+    if target.is_none() {
+        unreachable!("Expected a target, but got `None`!")
+    }
+    if args.len() != 2 {
+        unreachable!("Expected two args!")
+    }
+
+    let var = args.remove(0);
+    let v = extract_ident(var.clone(), helper)?;
+    let key = args.pop().unwrap();
+
+    let init = helper.next_expr(Expr::List(ListExpr {
+        elements: vec![],
+    }));
+    let result_binding = "@result".to_string();
+    let condition = helper.next_expr(Expr::Literal(Boolean(true)));
+
+    // the elements are collected with their key as `[key, element]` pairs, which are sorted by
+    // key once the whole list has been iterated
+    let pair = helper.next_expr(Expr::List(ListExpr {
+        elements: vec![key, var],
+    }));
+    let args = vec![
+        helper.next_expr(Expr::Ident(result_binding.clone())),
+        helper.next_expr(Expr::List(ListExpr {
+            elements: vec![pair],
+        })),
+    ];
+    let step = helper.next_expr(Expr::Call(CallExpr {
+        func_name: operators::ADD.to_string(),
+        target: None,
+        args,
+    }));
+
+    let accu = helper.next_expr(Expr::Ident(result_binding.clone()));
+    let result = helper.next_expr(Expr::Call(CallExpr {
+        func_name: operators::SORT_BY_KEY.to_string(),
+        target: None,
+        args: vec![accu],
+    }));
+
+    Ok(helper.next_expr(Expr::Comprehension(Box::new(ComprehensionExpr {
+        iter_range: target.unwrap(),
+        iter_var: v,
+        iter_var2: None,
+        accu_var: result_binding,
+        accu_init: init,
+        loop_cond: condition,
+        loop_step: step,
+        result,
+    }))))
+}
+
+/// Expands `list.reduce(acc, x, init, step)` to a comprehension whose accumulator is `acc`, so
+/// that `step` can refer to both the accumulator and the current item.
+fn reduce_macro_expander(
+    helper: &mut MacroExprHelper,
+    target: Option<IdedExpr>,
+    mut args: Vec<IdedExpr>,
+) -> Result<IdedExpr, ParseError> {
+    if target.is_none() {
+        unreachable!("Expected a target, but got `None`!")
+    }
+    if args.len() != 4 {
+        unreachable!("Expected four args!")
+    }
+
+    let step = args.pop().unwrap();
+    let init = args.pop().unwrap();
+    let v = extract_ident(args.pop().unwrap(), helper)?;
+    let accu = extract_ident(args.pop().unwrap(), helper)?;
+
+    let condition = helper.next_expr(Expr::Literal(Boolean(true)));
+    let result = helper.next_expr(Expr::Ident(accu.clone()));
+
+    Ok(helper.next_expr(Expr::Comprehension(Box::new(ComprehensionExpr {
+        iter_range: target.unwrap(),
+        iter_var: v,
+        iter_var2: None,
+        accu_var: accu,
+        accu_init: init,
+        loop_cond: condition,
+        loop_step: step,
+        result,
+    }))))
+}
+
 fn extract_ident(expr: IdedExpr, helper: &mut MacroExprHelper) -> Result<String, ParseError> {
     match expr.expr {
         Expr::Ident(ident) => Ok(ident),
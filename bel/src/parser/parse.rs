@@ -56,7 +56,7 @@ pub fn parse_bytes(s: &str) -> Result<Vec<u8>, ParseSequenceError> {
                 }
                 Some((idx, c2)) => {
                     let byte: u8 = match c2 {
-                        'x' => {
+                        'x' | 'X' => {
                             let hex: String = [
                                 chars
                                     .next()
@@ -111,13 +111,30 @@ pub fn parse_bytes(s: &str) -> Result<Vec<u8>, ParseSequenceError> {
                                 string: s.to_string(),
                             })?
                         }
-                        _ => {
-                            return Err(ParseSequenceError::InvalidEscape {
-                                escape: format!("{c}{c2}"),
-                                index: idx,
-                                string: String::from(s),
-                            });
+                        'u' | 'U' => {
+                            let length = if c2 == 'u' { 4 } else { 8 };
+                            let unicode = parse_unicode_hex(length, &mut chars).map_err(|x| {
+                                ParseSequenceError::InvalidUnicode {
+                                    source: x,
+                                    index: idx,
+                                    string: String::from(s),
+                                }
+                            })?;
+                            let mut buffer = [0; 4];
+                            res.extend_from_slice(unicode.encode_utf8(&mut buffer).as_bytes());
+                            continue;
                         }
+                        _ => match simple_escape(c2) {
+                            // all the simple escapes are ASCII characters
+                            Some(value) => value as u8,
+                            None => {
+                                return Err(ParseSequenceError::InvalidEscape {
+                                    escape: format!("{c}{c2}"),
+                                    index: idx,
+                                    string: String::from(s),
+                                });
+                            }
+                        },
                     };
 
                     res.push(byte);
@@ -133,6 +150,18 @@ pub fn parse_bytes(s: &str) -> Result<Vec<u8>, ParseSequenceError> {
     Ok(res)
 }
 
+/// Parses the provided bytes literal, without its `b` prefix.
+///
+/// The literal is quoted with single or double quotes (`'...'`, `"..."`), or with triple single or
+/// double quotes (`'''...'''`, `"""..."""`), and its escape sequences are interpreted by [parse_bytes](parse_bytes), unless it is prefixed with
+/// `r` or `R`, in which case the content between the quotes is returned as-is.
+pub fn parse_bytes_literal(s: &str) -> Result<Vec<u8>, ParseSequenceError> {
+    match s.strip_prefix(['r', 'R']) {
+        Some(raw) => Ok(strip_quotes(raw)?.as_bytes().to_vec()),
+        None => parse_bytes(strip_quotes(s)?),
+    }
+}
+
 /// Parse the provided quoted string.
 /// This function was adopted from [snailquote](https://docs.rs/snailquote/latest/snailquote/).
 ///
@@ -141,7 +170,10 @@ pub fn parse_bytes(s: &str) -> Result<Vec<u8>, ParseSequenceError> {
 /// Parses a single or double quoted string and interprets escape sequences such as
 /// '\n', '\r', '\'', etc.
 ///
-/// Supports raw strings prefixed with `r` or `R` in which case all escape sequences are ignored.///
+/// Supports raw strings prefixed with `r` or `R` in which case all escape sequences are ignored.
+///
+/// Strings quoted with triple double quotes (`"""..."""`) can span multiple lines and contain
+/// unescaped double quotes.
 ///
 /// The full set of supported escapes between quotes may be found below:
 ///
@@ -173,8 +205,12 @@ pub fn parse_string(s: &str) -> Result<String, ParseSequenceError> {
     let res = String::with_capacity(s.len());
 
     match chars.next() {
+        Some((_, c)) if (c == 'r' || c == 'R') && s[1..].starts_with(TRIPLE_QUOTE) => {
+            strip_quotes(&s[1..]).map(String::from)
+        }
         Some((_, c)) if c == 'r' || c == 'R' => parse_raw_string(&mut chars, res),
         // Some((_, c)) if c == '\'' || c == '"' => parse_quoted_string(s, &mut chars, res, c),
+        Some((_, '"')) if s.starts_with(TRIPLE_QUOTE) => parse_triple_quoted_string(s, res),
         Some((_, c)) if c == '"' => parse_quoted_string(s, &mut chars, res, c),
         _ => Err(ParseSequenceError::MissingOpeningQuote),
     }
@@ -353,6 +389,93 @@ fn parse_quoted_string(
     Ok(res)
 }
 
+fn parse_triple_quoted_string(s: &str, mut res: String) -> Result<String, ParseSequenceError> {
+    let content = strip_quotes(s)?;
+    // iterate over `s` so that the indices in errors are relative to `s`
+    let mut chars = s
+        .chars()
+        .enumerate()
+        .skip(TRIPLE_QUOTE.len())
+        .take(content.chars().count());
+
+    while let Some((idx, c)) = chars.next() {
+        if c != '\\' {
+            res.push(c);
+            continue;
+        }
+
+        let Some((idx, c2)) = chars.next() else {
+            return Err(ParseSequenceError::InvalidEscape {
+                escape: format!("{c}"),
+                index: idx,
+                string: String::from(s),
+            });
+        };
+        let value = match c2 {
+            'x' | 'u' | 'U' => {
+                let length = match c2 {
+                    'x' => 2,
+                    'u' => 4,
+                    'U' => 8,
+                    _ => unreachable!(),
+                };
+
+                parse_unicode_hex(length, &mut chars).map_err(|x| ParseSequenceError::InvalidUnicode {
+                    source: x,
+                    index: idx,
+                    string: String::from(s),
+                })?
+            }
+            n if ('0'..='3').contains(&n) => {
+                parse_unicode_oct(&n, &mut chars).map_err(|x| ParseSequenceError::InvalidUnicode {
+                    source: x,
+                    index: idx,
+                    string: String::from(s),
+                })?
+            }
+            _ => simple_escape(c2).ok_or_else(|| ParseSequenceError::InvalidEscape {
+                escape: format!("{c}{c2}"),
+                index: idx,
+                string: String::from(s),
+            })?,
+        };
+        res.push(value);
+    }
+
+    Ok(res)
+}
+
+const TRIPLE_QUOTE: &str = "\"\"\"";
+const TRIPLE_SINGLE_QUOTE: &str = "'''";
+
+/// Returns the content of a string quoted with single or double quotes, or with triple single or
+/// double quotes.
+fn strip_quotes(s: &str) -> Result<&str, ParseSequenceError> {
+    let quote = [TRIPLE_QUOTE, TRIPLE_SINGLE_QUOTE, "\"", "'"]
+        .into_iter()
+        .find(|quote| s.starts_with(quote))
+        .ok_or(ParseSequenceError::MissingOpeningQuote)?;
+
+    s[quote.len()..]
+        .strip_suffix(quote)
+        .ok_or(ParseSequenceError::MissingClosingQuote)
+}
+
+/// Returns the character represented by a single character escape sequence such as `\n`.
+fn simple_escape(c: char) -> Option<char> {
+    match c {
+        'a' => Some('\u{07}'),
+        'b' => Some('\u{08}'),
+        'v' => Some('\u{0B}'),
+        'f' => Some('\u{0C}'),
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        '\\' | '?' | '"' | '\'' | '`' => Some(c),
+        _ => None,
+    }
+}
+
 fn parse_unicode_hex<I>(length: usize, chars: &mut I) -> Result<char, ParseUnicodeError>
 where
     I: Iterator<Item = (usize, char)>,
@@ -399,7 +522,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{ParseSequenceError, parse_bytes, parse_string};
+    use super::{ParseSequenceError, parse_bytes, parse_bytes_literal, parse_string};
 
     // #[test]
     // fn single_quotes_interprets_escapes() {
@@ -522,4 +645,86 @@ mod tests {
         let bytes = parse_bytes("abc💖\\xFF\\376").expect("Must parse!");
         assert_eq!([97, 98, 99, 240, 159, 146, 150, 255, 254], *bytes)
     }
+
+    #[test]
+    fn raw_string_keeps_backslashes() {
+        assert_eq!(parse_string(r#"r"\n""#), Ok(String::from("\\n")));
+        assert_eq!(parse_string(r#"r"\n""#).unwrap().len(), 2);
+        assert_eq!(parse_string(r#"R"\x61\u00e9""#), Ok(String::from("\\x61\\u00e9")));
+    }
+
+    #[test]
+    fn triple_quoted_strings() {
+        let tests: Vec<(&str, Result<String, ParseSequenceError>)> = vec![
+            ("\"\"\"Hello\"\"\"", Ok(String::from("Hello"))),
+            ("\"\"\"\"\"\"", Ok(String::new())),
+            ("\"\"\"line 1\nline 2\n\"\"\"", Ok(String::from("line 1\nline 2\n"))),
+            ("\"\"\"say \"hi\" 'there'\"\"\"", Ok(String::from("say \"hi\" 'there'"))),
+            ("\"\"\"tab\\t \\x41 \\u270c \\101 \\'\"\"\"", Ok(String::from("tab\t A ✌ A '"))),
+            ("r\"\"\"raw \\n \"quoted\"\n\"\"\"", Ok(String::from("raw \\n \"quoted\"\n"))),
+            ("\"\"\"Missing closing quotes\"", Err(ParseSequenceError::MissingClosingQuote)),
+            (
+                "\"\"\"\\z\"\"\"",
+                Err(ParseSequenceError::InvalidEscape {
+                    escape: String::from("\\z"),
+                    index: 4,
+                    string: String::from("\"\"\"\\z\"\"\""),
+                }),
+            ),
+        ];
+
+        for (s, expected) in tests {
+            let result = parse_string(s);
+            assert_eq!(result, expected, "Testing {s}");
+        }
+    }
+
+    #[test]
+    fn parses_bytes_escapes() {
+        let tests: Vec<(&str, Vec<u8>)> = vec![
+            ("\\x61", vec![0x61]),
+            ("\\X61\\x62", vec![0x61, 0x62]),
+            ("\\141", vec![0x61]),
+            ("\\n\\r\\t\\\\\\\"\\'", b"\n\r\t\\\"'".to_vec()),
+            ("\\a\\b\\v\\f\\?\\`", vec![0x07, 0x08, 0x0b, 0x0c, b'?', b'`']),
+            ("\\u00e9", "é".as_bytes().to_vec()),
+            ("\\U0001f431", "🐱".as_bytes().to_vec()),
+        ];
+
+        for (s, expected) in tests {
+            assert_eq!(parse_bytes(s), Ok(expected), "Testing {s}");
+        }
+
+        assert_eq!(
+            parse_bytes("\\z"),
+            Err(ParseSequenceError::InvalidEscape {
+                escape: String::from("\\z"),
+                index: 1,
+                string: String::from("\\z"),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_bytes_literals() {
+        assert_eq!(parse_bytes_literal(r#""a\x62c""#), Ok(b"abc".to_vec()));
+        assert_eq!(parse_bytes_literal(r#"r"a\x62c""#), Ok(br"a\x62c".to_vec()));
+        assert_eq!(parse_bytes_literal("\"\"\"a\"b\nc\"\"\""), Ok(b"a\"b\nc".to_vec()));
+        assert_eq!(parse_bytes_literal("R\"\"\"a\\n\"\"\""), Ok(b"a\\n".to_vec()));
+        assert_eq!(parse_bytes_literal("\"abc"), Err(ParseSequenceError::MissingClosingQuote));
+    }
+
+    #[test]
+    fn parses_single_quoted_bytes_literals() {
+        assert_eq!(parse_bytes_literal(r"'a\x62c'"), Ok(b"abc".to_vec()));
+        assert_eq!(parse_bytes_literal(r#"'a"\'b'"#), Ok(b"a\"'b".to_vec()));
+        assert_eq!(parse_bytes_literal("''"), Ok(Vec::new()));
+        assert_eq!(parse_bytes_literal(r"r'a\x62c'"), Ok(br"a\x62c".to_vec()));
+        assert_eq!(parse_bytes_literal("'''a'b\nc'''"), Ok(b"a'b\nc".to_vec()));
+        assert_eq!(parse_bytes_literal("''''''"), Ok(Vec::new()));
+        assert_eq!(parse_bytes_literal("R'''a\\n'''"), Ok(b"a\\n".to_vec()));
+        assert_eq!(parse_bytes_literal("'abc"), Err(ParseSequenceError::MissingClosingQuote));
+        assert_eq!(parse_bytes_literal("'''abc'"), Err(ParseSequenceError::MissingClosingQuote));
+        assert_eq!(parse_bytes_literal("abc"), Err(ParseSequenceError::MissingOpeningQuote));
+    }
 }
@@ -175,7 +175,13 @@ impl Parser {
         }
     }
 
-    pub fn parse(mut self, source: &str) -> Result<IdedExpr, ParseErrors> {
+    pub fn parse(self, source: &str) -> Result<IdedExpr, ParseErrors> {
+        self.parse_with_source_info(source).map(|(expr, _)| expr)
+    }
+
+    /// Parses `source` like [`Parser::parse`], and also returns the positions of the
+    /// expressions, to report errors found after parsing.
+    pub(crate) fn parse_with_source_info(mut self, source: &str) -> Result<(IdedExpr, Arc<SourceInfo>), ParseErrors> {
         let parse_errors = Rc::new(RefCell::new(Vec::<ParseError>::new()));
         let stream = InputStream::new(source);
         let mut lexer = generated::CELLexer::new(stream);
@@ -211,7 +217,7 @@ impl Parser {
         errors.sort_by(|a, b| a.pos.cmp(&b.pos));
 
         if errors.is_empty() {
-            r.map_err(|e| ParseErrors {
+            r.map(|expr| (expr, source_info)).map_err(|e| ParseErrors {
                 errors: vec![e],
             })
         } else {
@@ -797,7 +803,7 @@ impl generated::CELVisitorCompat<'_> for Parser {
     fn visit_Bytes(&mut self, ctx: &BytesContext<'_>) -> Self::Return {
         let token = ctx.tok.as_deref().expect("Has to have bytes!");
         let string = ctx.get_text();
-        match parse::parse_bytes(&string[2..string.len() - 1]) {
+        match parse::parse_bytes_literal(&string[1..]) {
             Ok(bytes) => self.helper.next_expr(token, Expr::Literal(CelVal::Bytes(bytes))),
             Err(e) => {
                 self.report_error::<ParseError, _>(token, None, format!("invalid bytes literal: {e:?}"));
@@ -1005,6 +1011,36 @@ mod tests {
                 p: "15u^#1:*expr.Constant_Uint64Value#",
                 e: "",
             },
+            TestInfo {
+                i: "42u",
+                p: "42u^#1:*expr.Constant_Uint64Value#",
+                e: "",
+            },
+            TestInfo {
+                i: "42U",
+                p: "42u^#1:*expr.Constant_Uint64Value#",
+                e: "",
+            },
+            TestInfo {
+                i: "0u",
+                p: "0u^#1:*expr.Constant_Uint64Value#",
+                e: "",
+            },
+            TestInfo {
+                i: "0U",
+                p: "0u^#1:*expr.Constant_Uint64Value#",
+                e: "",
+            },
+            TestInfo {
+                i: "18446744073709551615u",
+                p: "18446744073709551615u^#1:*expr.Constant_Uint64Value#",
+                e: "",
+            },
+            TestInfo {
+                i: "0xFFFFFFFFFFFFFFFFu",
+                p: "18446744073709551615u^#1:*expr.Constant_Uint64Value#",
+                e: "",
+            },
             TestInfo {
                 i: "-1",
                 p: "-1^#1:*expr.Constant_Int64Value#",
@@ -1031,6 +1067,36 @@ mod tests {
                 p: r#"b"abc"^#1:*expr.Constant_BytesValue#"#,
                 e: "",
             },
+            TestInfo {
+                i: r#"b"\x61\142é""#,
+                p: r#"b"abé"^#1:*expr.Constant_BytesValue#"#,
+                e: "",
+            },
+            TestInfo {
+                i: "b'abc'",
+                p: r#"b"abc"^#1:*expr.Constant_BytesValue#"#,
+                e: "",
+            },
+            TestInfo {
+                i: r"b'''a\x62c'''",
+                p: r#"b"abc"^#1:*expr.Constant_BytesValue#"#,
+                e: "",
+            },
+            TestInfo {
+                i: r#"br"\x61""#,
+                p: r#"b"\x61"^#1:*expr.Constant_BytesValue#"#,
+                e: "",
+            },
+            TestInfo {
+                i: r#"r"\n""#,
+                p: r#""\n"^#1:*expr.Constant_StringValue#"#,
+                e: "",
+            },
+            TestInfo {
+                i: "\"\"\"multi\n\"line\" string\"\"\"",
+                p: "\"multi\n\"line\" string\"^#1:*expr.Constant_StringValue#",
+                e: "",
+            },
             TestInfo {
                 i: "23.39",
                 p: "23.39^#1:*expr.Constant_FloatValue#",
@@ -1189,12 +1255,11 @@ mod tests {
             },
             TestInfo {
                 i: "a in b",
-//                 p: "@in(
-//     a^#1:*expr.Expr_IdentExpr#,
-//     b^#3:*expr.Expr_IdentExpr#
-// )^#2:*expr.Expr_CallExpr#",
-p: "",
-                e: "ERROR: <input>:1:3: Unknown `in` operator!\n| a in b\n| ..^",
+                p: "@in(
+    a^#1:*expr.Expr_IdentExpr#,
+    b^#3:*expr.Expr_IdentExpr#
+)^#2:*expr.Expr_CallExpr#",
+                e: "",
             },
             TestInfo {
                 i: "a == b",
@@ -1530,13 +1595,20 @@ _?_:_(
 | 0xFFFFFFFFFFFFFFFFF
 | ^",
             },
-//             TestInfo {
-//                 i: "0xFFFFFFFFFFFFFFFFFu",
-//                 p: "",
-//                 e: "ERROR: <input>:1:1: invalid uint literal
-// | 0xFFFFFFFFFFFFFFFFFu
-// | ^",
-            // },
+            TestInfo {
+                i: "0xFFFFFFFFFFFFFFFFFu",
+                p: "",
+                e: "ERROR: <input>:1:1: invalid uint literal
+| 0xFFFFFFFFFFFFFFFFFu
+| ^",
+            },
+            TestInfo {
+                i: "18446744073709551616u",
+                p: "",
+                e: "ERROR: <input>:1:1: invalid uint literal
+| 18446744073709551616u
+| ^",
+            },
             TestInfo {
                 i: "1.99e90000009",
                 p: "",
@@ -1621,6 +1693,13 @@ ERROR: <input>:1:24: unsupported syntax '?'
 | 1.all(2, 3)
 | ......^",
             },
+            TestInfo {
+                i: "[1].reduce(acc, 2, 0, acc)",
+                p: "",
+                e: "ERROR: <input>:1:17: argument must be a simple name
+| [1].reduce(acc, 2, 0, acc)
+| ................^",
+            },
         ];
 
         for test_case in test_cases {
@@ -1,11 +1,13 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::common::ast::{Expr, IdedExpr};
 
 /// A collection of all the references that an expression makes to variables and functions.
 pub struct ExpressionReferences<'expr> {
     variables: HashSet<&'expr str>,
-    functions: HashSet<&'expr str>,
+    /// The free variables and the functions, with the ID of the first expression referencing them.
+    free_variables: HashMap<&'expr str, u64>,
+    functions: HashMap<&'expr str, u64>,
 }
 
 impl ExpressionReferences<'_> {
@@ -32,7 +34,7 @@ impl ExpressionReferences<'_> {
     /// assert!(references.has_function("length"));
     /// ```
     pub fn has_function(&self, name: impl AsRef<str>) -> bool {
-        self.functions.contains(name.as_ref())
+        self.functions.contains_key(name.as_ref())
     }
 
     /// Returns a list of all variables referenced in the expression.
@@ -48,6 +50,26 @@ impl ExpressionReferences<'_> {
         self.variables.iter().copied().collect()
     }
 
+    /// Returns a list of the variables referenced in the expression that are not bound by an
+    /// enclosing comprehension, i.e. the variables that must be provided by the context.
+    ///
+    /// Unlike [`ExpressionReferences::variables`], the variables of macros such as `map` or `all`
+    /// are only included when they are referenced outside of the macro.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use bel::parser::Parser;
+    /// let expression = Parser::new().parse("foo.map(x, x + bar)").unwrap();
+    /// let references = expression.references();
+    /// let mut variables = references.free_variables();
+    /// variables.sort();
+    /// assert_eq!(vec!["bar", "foo"], variables);
+    /// assert!(references.has_variable("x"));
+    /// ```
+    pub fn free_variables(&self) -> Vec<&str> {
+        self.free_variables.keys().copied().collect()
+    }
+
     /// Returns a list of all functions referenced in the expression.
     ///
     /// # Example
@@ -59,7 +81,17 @@ impl ExpressionReferences<'_> {
     /// assert!(references.functions().contains(&"length"));
     /// ```
     pub fn functions(&self) -> Vec<&str> {
-        self.functions.iter().copied().collect()
+        self.functions.keys().copied().collect()
+    }
+
+    /// Returns the ID of the first expression referencing the free variable `name`.
+    pub(crate) fn free_variable_id(&self, name: &str) -> Option<u64> {
+        self.free_variables.get(name).copied()
+    }
+
+    /// Returns the ID of the first call of the function `name`.
+    pub(crate) fn function_id(&self, name: &str) -> Option<u64> {
+        self.functions.get(name).copied()
     }
 }
 
@@ -76,44 +108,55 @@ impl IdedExpr {
     /// assert!(references.has_function("length"));
     /// ```
     pub fn references(&self) -> ExpressionReferences<'_> {
-        let mut variables = HashSet::new();
-        let mut functions = HashSet::new();
-        self._references(&mut variables, &mut functions);
-        ExpressionReferences {
-            variables,
-            functions,
-        }
+        let mut references = ExpressionReferences {
+            variables: HashSet::new(),
+            free_variables: HashMap::new(),
+            functions: HashMap::new(),
+        };
+        self._references(&mut references, &mut Vec::new());
+        references
     }
 
     /// Internal recursive function to collect all variable and function references in the expression.
-    fn _references<'expr>(&'expr self, variables: &mut HashSet<&'expr str>, functions: &mut HashSet<&'expr str>) {
+    ///
+    /// `bound` contains the variables of the enclosing comprehensions.
+    fn _references<'expr>(&'expr self, references: &mut ExpressionReferences<'expr>, bound: &mut Vec<&'expr str>) {
         match &self.expr {
             Expr::Unspecified => {}
             Expr::Call(call) => {
-                functions.insert(&call.func_name);
+                references.functions.entry(&call.func_name).or_insert(self.id);
                 if let Some(target) = &call.target {
-                    target._references(variables, functions);
+                    target._references(references, bound);
                 }
                 for arg in &call.args {
-                    arg._references(variables, functions);
+                    arg._references(references, bound);
                 }
             }
             Expr::Comprehension(comp) => {
-                comp.iter_range._references(variables, functions);
-                comp.accu_init._references(variables, functions);
-                comp.loop_cond._references(variables, functions);
-                comp.loop_step._references(variables, functions);
-                comp.result._references(variables, functions);
+                comp.iter_range._references(references, bound);
+                comp.accu_init._references(references, bound);
+                // the accumulator is visible in the loop and the result, the iteration variable only
+                // in the loop
+                bound.push(&comp.accu_var);
+                bound.push(&comp.iter_var);
+                comp.loop_cond._references(references, bound);
+                comp.loop_step._references(references, bound);
+                bound.pop();
+                comp.result._references(references, bound);
+                bound.pop();
             }
             Expr::Ident(name) => {
-                // todo! Might want to make this "smarter" (are we in a comprehension?) and better encode these in const
+                // todo! Better encode the internal identifiers of macros in const
                 if !name.starts_with('@') {
-                    variables.insert(name);
+                    references.variables.insert(name);
+                    if !bound.contains(&name.as_str()) {
+                        references.free_variables.entry(name).or_insert(self.id);
+                    }
                 }
             }
             Expr::List(list) => {
                 for elem in &list.elements {
-                    elem._references(variables, functions);
+                    elem._references(references, bound);
                 }
             }
             Expr::Literal(_) => {}
@@ -121,27 +164,27 @@ impl IdedExpr {
                 for entry in &map.entries {
                     match &entry.expr {
                         crate::common::ast::EntryExpr::StructField(field) => {
-                            field.value._references(variables, functions);
+                            field.value._references(references, bound);
                         }
                         crate::common::ast::EntryExpr::MapEntry(map_entry) => {
-                            map_entry.key._references(variables, functions);
-                            map_entry.value._references(variables, functions);
+                            map_entry.key._references(references, bound);
+                            map_entry.value._references(references, bound);
                         }
                     }
                 }
             }
             Expr::Select(select) => {
-                select.operand._references(variables, functions);
+                select.operand._references(references, bound);
             }
             Expr::Struct(struct_expr) => {
                 for entry in &struct_expr.entries {
                     match &entry.expr {
                         crate::common::ast::EntryExpr::StructField(field) => {
-                            field.value._references(variables, functions);
+                            field.value._references(references, bound);
                         }
                         crate::common::ast::EntryExpr::MapEntry(map_entry) => {
-                            map_entry.key._references(variables, functions);
-                            map_entry.value._references(variables, functions);
+                            map_entry.key._references(references, bound);
+                            map_entry.value._references(references, bound);
                         }
                     }
                 }
@@ -1,6 +1,11 @@
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "subdivisions")]
+mod subdivisions;
+#[cfg(feature = "subdivisions")]
+pub use subdivisions::{Subdivision, subdivisions};
+
 /// Code used as a fallback when a country is not found.
 pub const UNKNOWN_CODE: &str = "XX";
 
@@ -63,6 +68,10 @@ pub const fn countries() -> &'static [Country] {
 
 /// Look up a country name by its ISO 3166-1 alpha-2 code.
 ///
+/// The code is normalized before the lookup: surrounding ASCII whitespace is trimmed and letters
+/// are uppercased, so `" us "` and `"Us"` resolve like `"US"`. Use [`name_strict`] to only accept
+/// codes exactly as listed.
+///
 /// Returns [`UNKNOWN`] when the code is not recognised or is not exactly 2
 /// characters long.
 ///
@@ -70,18 +79,50 @@ pub const fn countries() -> &'static [Country] {
 ///
 /// ```
 /// assert_eq!(countries::name("FR"), "France");
+/// assert_eq!(countries::name(" fr "), "France");
 /// assert_eq!(countries::name("XX"), "Unknown");
 /// assert_eq!(countries::name("??"), "Unknown");
 /// ```
 pub const fn name(code: &str) -> &'static str {
-    // Binary search over `COUNTRIES_DATA`, which is sorted by code and checked
-    // at compile time by the `const _: ()` assertion above.  All codes in the
-    // table are exactly 2 ASCII bytes, so we can compare them as a single u16
-    // value for a cheaper inner-loop comparison.
+    match normalize(code) {
+        Some(code) => lookup(code),
+        None => UNKNOWN,
+    }
+}
+
+/// Trims and uppercases a code, returning `None` if it's not 2 characters long.
+const fn normalize(code: &str) -> Option<[u8; 2]> {
+    let code = code.as_bytes().trim_ascii();
+    if code.len() != 2 {
+        return None;
+    }
+    Some([code[0].to_ascii_uppercase(), code[1].to_ascii_uppercase()])
+}
+
+/// Look up a country name by its ISO 3166-1 alpha-2 code, which must be exactly as listed:
+/// 2 uppercase letters, without whitespace.
+///
+/// Returns [`UNKNOWN`] when the code is not recognised.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(countries::name_strict("FR"), "France");
+/// assert_eq!(countries::name_strict("fr"), "Unknown");
+/// ```
+pub const fn name_strict(code: &str) -> &'static str {
     let code = code.as_bytes();
     if code.len() != 2 {
         return UNKNOWN;
     }
+    lookup([code[0], code[1]])
+}
+
+const fn lookup(code: [u8; 2]) -> &'static str {
+    // Binary search over `COUNTRIES_DATA`, which is sorted by code and checked
+    // at compile time by the `const _: ()` assertion above.  All codes in the
+    // table are exactly 2 ASCII bytes, so we can compare them as a single u16
+    // value for a cheaper inner-loop comparison.
     let needle = (code[0] as u16) << 8 | code[1] as u16;
     let mut lo = 0usize;
     let mut hi = COUNTRIES_DATA.len();
@@ -1132,6 +1173,24 @@ mod tests {
         assert_eq!(name("FRA"), "Unknown");
     }
 
+    #[test]
+    fn normalized_code() {
+        assert_eq!(name("fr"), "France");
+        assert_eq!(name(" us "), "United States");
+        assert_eq!(name("\tGb\n"), "United Kingdom");
+        assert_eq!(name("dE"), "Germany");
+        assert_eq!(name(" "), "Unknown");
+        assert_eq!(name("f r"), "Unknown");
+    }
+
+    #[test]
+    fn strict_code() {
+        assert_eq!(name_strict("FR"), "France");
+        assert_eq!(name_strict("fr"), "Unknown");
+        assert_eq!(name_strict(" FR"), "Unknown");
+        assert_eq!(name_strict(""), "Unknown");
+    }
+
     #[test]
     fn default_country() {
         let c = Country::default();
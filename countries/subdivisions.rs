@@ -0,0 +1,696 @@
+//! First-level subdivisions of countries (ISO 3166-2), enabled by the `subdivisions` feature.
+//!
+//! Only the subdivisions of a few countries are embedded for now: Australia, Brazil, Canada,
+//! Germany, Switzerland and the United States (the states and the District of Columbia). Names
+//! are the ones of the ISO 3166-2 standard, in the local language of the country.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A first-level subdivision of a country (state, province, region...) with its ISO 3166-2 code
+/// and name.
+///
+/// # Examples
+///
+/// ```
+/// use countries::Subdivision;
+///
+/// let subdivision = Subdivision { code: "CA-QC", name: "Quebec" };
+/// assert_eq!(subdivision.code, "CA-QC");
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct Subdivision {
+    /// The ISO 3166-2 code: the alpha-2 code of the country, a hyphen and up to 3 alphanumeric
+    /// characters.
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+/// Returns the subdivisions of a country by its ISO 3166-1 alpha-2 code, ordered by code.
+///
+/// The code is normalized like with [`name`](crate::name). Returns an empty slice when the code
+/// is not recognised or when there is no data for the country.
+///
+/// # Examples
+///
+/// ```
+/// let states = countries::subdivisions("us");
+/// assert!(states.iter().any(|s| s.code == "US-CA" && s.name == "California"));
+/// assert!(countries::subdivisions("XX").is_empty());
+/// ```
+pub const fn subdivisions(code: &str) -> &'static [Subdivision] {
+    let Some(code) = crate::normalize(code) else {
+        return &[];
+    };
+    // the table is small, a linear search is enough
+    let mut i = 0;
+    while i < SUBDIVISIONS_DATA.len() {
+        let (country, subdivisions) = SUBDIVISIONS_DATA[i];
+        let country = country.as_bytes();
+        if country[0] == code[0] && country[1] == code[1] {
+            return subdivisions;
+        }
+        i += 1;
+    }
+    &[]
+}
+
+const SUBDIVISIONS_DATA: &[(&str, &[Subdivision])] =
+    &[("AU", AU), ("BR", BR), ("CA", CA), ("CH", CH), ("DE", DE), ("US", US)];
+
+const AU: &[Subdivision] = &[
+    Subdivision {
+        code: "AU-ACT",
+        name: "Australian Capital Territory",
+    },
+    Subdivision {
+        code: "AU-NSW",
+        name: "New South Wales",
+    },
+    Subdivision {
+        code: "AU-NT",
+        name: "Northern Territory",
+    },
+    Subdivision {
+        code: "AU-QLD",
+        name: "Queensland",
+    },
+    Subdivision {
+        code: "AU-SA",
+        name: "South Australia",
+    },
+    Subdivision {
+        code: "AU-TAS",
+        name: "Tasmania",
+    },
+    Subdivision {
+        code: "AU-VIC",
+        name: "Victoria",
+    },
+    Subdivision {
+        code: "AU-WA",
+        name: "Western Australia",
+    },
+];
+
+const BR: &[Subdivision] = &[
+    Subdivision {
+        code: "BR-AC",
+        name: "Acre",
+    },
+    Subdivision {
+        code: "BR-AL",
+        name: "Alagoas",
+    },
+    Subdivision {
+        code: "BR-AM",
+        name: "Amazonas",
+    },
+    Subdivision {
+        code: "BR-AP",
+        name: "Amapá",
+    },
+    Subdivision {
+        code: "BR-BA",
+        name: "Bahia",
+    },
+    Subdivision {
+        code: "BR-CE",
+        name: "Ceará",
+    },
+    Subdivision {
+        code: "BR-DF",
+        name: "Distrito Federal",
+    },
+    Subdivision {
+        code: "BR-ES",
+        name: "Espírito Santo",
+    },
+    Subdivision {
+        code: "BR-GO",
+        name: "Goiás",
+    },
+    Subdivision {
+        code: "BR-MA",
+        name: "Maranhão",
+    },
+    Subdivision {
+        code: "BR-MG",
+        name: "Minas Gerais",
+    },
+    Subdivision {
+        code: "BR-MS",
+        name: "Mato Grosso do Sul",
+    },
+    Subdivision {
+        code: "BR-MT",
+        name: "Mato Grosso",
+    },
+    Subdivision {
+        code: "BR-PA",
+        name: "Pará",
+    },
+    Subdivision {
+        code: "BR-PB",
+        name: "Paraíba",
+    },
+    Subdivision {
+        code: "BR-PE",
+        name: "Pernambuco",
+    },
+    Subdivision {
+        code: "BR-PI",
+        name: "Piauí",
+    },
+    Subdivision {
+        code: "BR-PR",
+        name: "Paraná",
+    },
+    Subdivision {
+        code: "BR-RJ",
+        name: "Rio de Janeiro",
+    },
+    Subdivision {
+        code: "BR-RN",
+        name: "Rio Grande do Norte",
+    },
+    Subdivision {
+        code: "BR-RO",
+        name: "Rondônia",
+    },
+    Subdivision {
+        code: "BR-RR",
+        name: "Roraima",
+    },
+    Subdivision {
+        code: "BR-RS",
+        name: "Rio Grande do Sul",
+    },
+    Subdivision {
+        code: "BR-SC",
+        name: "Santa Catarina",
+    },
+    Subdivision {
+        code: "BR-SE",
+        name: "Sergipe",
+    },
+    Subdivision {
+        code: "BR-SP",
+        name: "São Paulo",
+    },
+    Subdivision {
+        code: "BR-TO",
+        name: "Tocantins",
+    },
+];
+
+const CA: &[Subdivision] = &[
+    Subdivision {
+        code: "CA-AB",
+        name: "Alberta",
+    },
+    Subdivision {
+        code: "CA-BC",
+        name: "British Columbia",
+    },
+    Subdivision {
+        code: "CA-MB",
+        name: "Manitoba",
+    },
+    Subdivision {
+        code: "CA-NB",
+        name: "New Brunswick",
+    },
+    Subdivision {
+        code: "CA-NL",
+        name: "Newfoundland and Labrador",
+    },
+    Subdivision {
+        code: "CA-NS",
+        name: "Nova Scotia",
+    },
+    Subdivision {
+        code: "CA-NT",
+        name: "Northwest Territories",
+    },
+    Subdivision {
+        code: "CA-NU",
+        name: "Nunavut",
+    },
+    Subdivision {
+        code: "CA-ON",
+        name: "Ontario",
+    },
+    Subdivision {
+        code: "CA-PE",
+        name: "Prince Edward Island",
+    },
+    Subdivision {
+        code: "CA-QC",
+        name: "Quebec",
+    },
+    Subdivision {
+        code: "CA-SK",
+        name: "Saskatchewan",
+    },
+    Subdivision {
+        code: "CA-YT",
+        name: "Yukon",
+    },
+];
+
+const CH: &[Subdivision] = &[
+    Subdivision {
+        code: "CH-AG",
+        name: "Aargau",
+    },
+    Subdivision {
+        code: "CH-AI",
+        name: "Appenzell Innerrhoden",
+    },
+    Subdivision {
+        code: "CH-AR",
+        name: "Appenzell Ausserrhoden",
+    },
+    Subdivision {
+        code: "CH-BE",
+        name: "Bern",
+    },
+    Subdivision {
+        code: "CH-BL",
+        name: "Basel-Landschaft",
+    },
+    Subdivision {
+        code: "CH-BS",
+        name: "Basel-Stadt",
+    },
+    Subdivision {
+        code: "CH-FR",
+        name: "Fribourg",
+    },
+    Subdivision {
+        code: "CH-GE",
+        name: "Genève",
+    },
+    Subdivision {
+        code: "CH-GL",
+        name: "Glarus",
+    },
+    Subdivision {
+        code: "CH-GR",
+        name: "Graubünden",
+    },
+    Subdivision {
+        code: "CH-JU",
+        name: "Jura",
+    },
+    Subdivision {
+        code: "CH-LU",
+        name: "Luzern",
+    },
+    Subdivision {
+        code: "CH-NE",
+        name: "Neuchâtel",
+    },
+    Subdivision {
+        code: "CH-NW",
+        name: "Nidwalden",
+    },
+    Subdivision {
+        code: "CH-OW",
+        name: "Obwalden",
+    },
+    Subdivision {
+        code: "CH-SG",
+        name: "Sankt Gallen",
+    },
+    Subdivision {
+        code: "CH-SH",
+        name: "Schaffhausen",
+    },
+    Subdivision {
+        code: "CH-SO",
+        name: "Solothurn",
+    },
+    Subdivision {
+        code: "CH-SZ",
+        name: "Schwyz",
+    },
+    Subdivision {
+        code: "CH-TG",
+        name: "Thurgau",
+    },
+    Subdivision {
+        code: "CH-TI",
+        name: "Ticino",
+    },
+    Subdivision {
+        code: "CH-UR",
+        name: "Uri",
+    },
+    Subdivision {
+        code: "CH-VD",
+        name: "Vaud",
+    },
+    Subdivision {
+        code: "CH-VS",
+        name: "Valais",
+    },
+    Subdivision {
+        code: "CH-ZG",
+        name: "Zug",
+    },
+    Subdivision {
+        code: "CH-ZH",
+        name: "Zürich",
+    },
+];
+
+const DE: &[Subdivision] = &[
+    Subdivision {
+        code: "DE-BB",
+        name: "Brandenburg",
+    },
+    Subdivision {
+        code: "DE-BE",
+        name: "Berlin",
+    },
+    Subdivision {
+        code: "DE-BW",
+        name: "Baden-Württemberg",
+    },
+    Subdivision {
+        code: "DE-BY",
+        name: "Bayern",
+    },
+    Subdivision {
+        code: "DE-HB",
+        name: "Bremen",
+    },
+    Subdivision {
+        code: "DE-HE",
+        name: "Hessen",
+    },
+    Subdivision {
+        code: "DE-HH",
+        name: "Hamburg",
+    },
+    Subdivision {
+        code: "DE-MV",
+        name: "Mecklenburg-Vorpommern",
+    },
+    Subdivision {
+        code: "DE-NI",
+        name: "Niedersachsen",
+    },
+    Subdivision {
+        code: "DE-NW",
+        name: "Nordrhein-Westfalen",
+    },
+    Subdivision {
+        code: "DE-RP",
+        name: "Rheinland-Pfalz",
+    },
+    Subdivision {
+        code: "DE-SH",
+        name: "Schleswig-Holstein",
+    },
+    Subdivision {
+        code: "DE-SL",
+        name: "Saarland",
+    },
+    Subdivision {
+        code: "DE-SN",
+        name: "Sachsen",
+    },
+    Subdivision {
+        code: "DE-ST",
+        name: "Sachsen-Anhalt",
+    },
+    Subdivision {
+        code: "DE-TH",
+        name: "Thüringen",
+    },
+];
+
+const US: &[Subdivision] = &[
+    Subdivision {
+        code: "US-AK",
+        name: "Alaska",
+    },
+    Subdivision {
+        code: "US-AL",
+        name: "Alabama",
+    },
+    Subdivision {
+        code: "US-AR",
+        name: "Arkansas",
+    },
+    Subdivision {
+        code: "US-AZ",
+        name: "Arizona",
+    },
+    Subdivision {
+        code: "US-CA",
+        name: "California",
+    },
+    Subdivision {
+        code: "US-CO",
+        name: "Colorado",
+    },
+    Subdivision {
+        code: "US-CT",
+        name: "Connecticut",
+    },
+    Subdivision {
+        code: "US-DC",
+        name: "District of Columbia",
+    },
+    Subdivision {
+        code: "US-DE",
+        name: "Delaware",
+    },
+    Subdivision {
+        code: "US-FL",
+        name: "Florida",
+    },
+    Subdivision {
+        code: "US-GA",
+        name: "Georgia",
+    },
+    Subdivision {
+        code: "US-HI",
+        name: "Hawaii",
+    },
+    Subdivision {
+        code: "US-IA",
+        name: "Iowa",
+    },
+    Subdivision {
+        code: "US-ID",
+        name: "Idaho",
+    },
+    Subdivision {
+        code: "US-IL",
+        name: "Illinois",
+    },
+    Subdivision {
+        code: "US-IN",
+        name: "Indiana",
+    },
+    Subdivision {
+        code: "US-KS",
+        name: "Kansas",
+    },
+    Subdivision {
+        code: "US-KY",
+        name: "Kentucky",
+    },
+    Subdivision {
+        code: "US-LA",
+        name: "Louisiana",
+    },
+    Subdivision {
+        code: "US-MA",
+        name: "Massachusetts",
+    },
+    Subdivision {
+        code: "US-MD",
+        name: "Maryland",
+    },
+    Subdivision {
+        code: "US-ME",
+        name: "Maine",
+    },
+    Subdivision {
+        code: "US-MI",
+        name: "Michigan",
+    },
+    Subdivision {
+        code: "US-MN",
+        name: "Minnesota",
+    },
+    Subdivision {
+        code: "US-MO",
+        name: "Missouri",
+    },
+    Subdivision {
+        code: "US-MS",
+        name: "Mississippi",
+    },
+    Subdivision {
+        code: "US-MT",
+        name: "Montana",
+    },
+    Subdivision {
+        code: "US-NC",
+        name: "North Carolina",
+    },
+    Subdivision {
+        code: "US-ND",
+        name: "North Dakota",
+    },
+    Subdivision {
+        code: "US-NE",
+        name: "Nebraska",
+    },
+    Subdivision {
+        code: "US-NH",
+        name: "New Hampshire",
+    },
+    Subdivision {
+        code: "US-NJ",
+        name: "New Jersey",
+    },
+    Subdivision {
+        code: "US-NM",
+        name: "New Mexico",
+    },
+    Subdivision {
+        code: "US-NV",
+        name: "Nevada",
+    },
+    Subdivision {
+        code: "US-NY",
+        name: "New York",
+    },
+    Subdivision {
+        code: "US-OH",
+        name: "Ohio",
+    },
+    Subdivision {
+        code: "US-OK",
+        name: "Oklahoma",
+    },
+    Subdivision {
+        code: "US-OR",
+        name: "Oregon",
+    },
+    Subdivision {
+        code: "US-PA",
+        name: "Pennsylvania",
+    },
+    Subdivision {
+        code: "US-RI",
+        name: "Rhode Island",
+    },
+    Subdivision {
+        code: "US-SC",
+        name: "South Carolina",
+    },
+    Subdivision {
+        code: "US-SD",
+        name: "South Dakota",
+    },
+    Subdivision {
+        code: "US-TN",
+        name: "Tennessee",
+    },
+    Subdivision {
+        code: "US-TX",
+        name: "Texas",
+    },
+    Subdivision {
+        code: "US-UT",
+        name: "Utah",
+    },
+    Subdivision {
+        code: "US-VA",
+        name: "Virginia",
+    },
+    Subdivision {
+        code: "US-VT",
+        name: "Vermont",
+    },
+    Subdivision {
+        code: "US-WA",
+        name: "Washington",
+    },
+    Subdivision {
+        code: "US-WI",
+        name: "Wisconsin",
+    },
+    Subdivision {
+        code: "US-WV",
+        name: "West Virginia",
+    },
+    Subdivision {
+        code: "US-WY",
+        name: "Wyoming",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_subdivisions() {
+        let states = subdivisions("US");
+        assert_eq!(states.len(), 51);
+        assert!(states.iter().any(|s| s.code == "US-NY" && s.name == "New York"));
+        assert!(states.iter().any(|s| s.code == "US-DC"));
+
+        let provinces = subdivisions("CA");
+        assert_eq!(provinces.len(), 13);
+        assert_eq!(provinces[0].code, "CA-AB");
+        assert_eq!(provinces[0].name, "Alberta");
+
+        assert_eq!(subdivisions(" de ").len(), 16);
+    }
+
+    #[test]
+    fn no_subdivisions() {
+        // a known country without data
+        assert_eq!(crate::name("FR"), "France");
+        assert!(subdivisions("FR").is_empty());
+
+        assert!(subdivisions("ZZ").is_empty());
+        assert!(subdivisions("XX").is_empty());
+        assert!(subdivisions("USA").is_empty());
+        assert!(subdivisions("").is_empty());
+    }
+
+    #[test]
+    fn subdivisions_are_valid() {
+        for win in SUBDIVISIONS_DATA.windows(2) {
+            assert!(win[0].0 < win[1].0, "out of order: {} >= {}", win[0].0, win[1].0);
+        }
+        for (country, subdivisions) in SUBDIVISIONS_DATA {
+            assert_ne!(crate::name_strict(country), crate::UNKNOWN, "unknown country {country}");
+            for win in subdivisions.windows(2) {
+                assert!(win[0].code < win[1].code, "out of order: {} >= {}", win[0].code, win[1].code);
+            }
+            for subdivision in *subdivisions {
+                let (prefix, suffix) = subdivision.code.split_once('-').unwrap();
+                assert_eq!(prefix, *country, "wrong country for {}", subdivision.code);
+                assert!(
+                    (1..=3).contains(&suffix.len()) && suffix.bytes().all(|b| b.is_ascii_alphanumeric()),
+                    "invalid code {}",
+                    subdivision.code
+                );
+            }
+        }
+    }
+}
@@ -24,8 +24,11 @@ pub mod hmac;
 pub mod mldsa;
 pub mod mlkem;
 pub mod poly1305;
+pub mod sha1;
 pub mod sha2;
 pub mod sha3;
+#[cfg(feature = "alloc")]
+pub mod totp;
 pub mod xwing;
 
 mod bytes;
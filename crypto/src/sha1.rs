@@ -0,0 +1,225 @@
+use crate::{Bytes, Hash, Hasher};
+
+/// SHA-1 hash function (RFC 3174 / FIPS 180-4).
+///
+/// Implements the [`Hasher`] trait.
+///
+/// SHA-1 is **not** collision resistant and must not be used for signatures or to identify data.
+/// It's only provided for the protocols that still require it: HMAC-SHA1, such as
+/// [TOTP](crate::totp), where HMAC doesn't rely on the collision resistance of the hash, and
+/// name-based UUIDs v5.
+///
+/// # One-shot API
+///
+/// ```ignore
+/// use crypto::{Hasher, sha1::Sha1};
+///
+/// let hash = Sha1::hash(b"hello world");
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: [u8; 64],
+    buffer_len: usize,
+    total_len: u64,
+}
+
+impl Hasher for Sha1 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 20;
+
+    #[inline]
+    fn new() -> Self {
+        return Sha1 {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0],
+            buffer: [0u8; 64],
+            buffer_len: 0,
+            total_len: 0,
+        };
+    }
+
+    #[inline]
+    fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+
+        if self.buffer_len > 0 {
+            let to_fill = (64 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + to_fill].copy_from_slice(&data[..to_fill]);
+            self.buffer_len += to_fill;
+            data = &data[to_fill..];
+
+            if self.buffer_len == 64 {
+                process_block(&mut self.state, &self.buffer);
+                self.buffer_len = 0;
+            }
+        }
+
+        let mut chunks = data.chunks_exact(64);
+        for chunk in &mut chunks {
+            process_block(&mut self.state, chunk.try_into().unwrap());
+        }
+
+        let remainder = chunks.remainder();
+        if !remainder.is_empty() {
+            self.buffer[..remainder.len()].copy_from_slice(remainder);
+            self.buffer_len = remainder.len();
+        }
+    }
+
+    #[inline]
+    fn sum(mut self) -> Hash {
+        let bit_len = self.total_len.wrapping_mul(8);
+
+        let mut tail = [0u8; 128];
+        tail[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+        tail[self.buffer_len] = 0x80;
+
+        let padding_len = if self.buffer_len < 56 {
+            56 - self.buffer_len
+        } else {
+            120 - self.buffer_len
+        };
+
+        let length_offset = self.buffer_len + padding_len;
+        tail[length_offset..length_offset + 8].copy_from_slice(&bit_len.to_be_bytes());
+
+        let total_tail_len = length_offset + 8;
+        for chunk in tail[..total_tail_len].chunks_exact(64) {
+            process_block(&mut self.state, chunk.try_into().unwrap());
+        }
+
+        let mut hash = Bytes::<64>::new();
+        for word in self.state.iter() {
+            hash.append(&word.to_be_bytes());
+        }
+
+        return Hash(hash);
+    }
+}
+
+#[inline]
+fn process_block(state: &mut [u32; 5], block: &[u8; 64]) {
+    let mut w = [0u32; 80];
+    let mut i = 0usize;
+    while i < 16 {
+        let offset = i * 4;
+        w[i] = u32::from_be_bytes([block[offset], block[offset + 1], block[offset + 2], block[offset + 3]]);
+        i += 1;
+    }
+
+    while i < 80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        i += 1;
+    }
+
+    let mut a = state[0];
+    let mut b = state[1];
+    let mut c = state[2];
+    let mut d = state[3];
+    let mut e = state[4];
+
+    i = 0;
+    while i < 80 {
+        let (f, k) = match i {
+            0..20 => ((b & c) | ((!b) & d), 0x5a827999),
+            20..40 => (b ^ c ^ d, 0x6ed9eba1),
+            40..60 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(w[i]);
+
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+
+        i += 1;
+    }
+
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+    state[4] = state[4].wrapping_add(e);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sha1;
+    use crate::{Hasher, hmac::Hmac};
+
+    fn vectors_sha1() -> Vec<(Vec<u8>, [u8; 20])> {
+        vec![
+            // RFC 3174 / NIST FIPS 180-4
+            (
+                b"".to_vec(),
+                hex::decode_array::<20>(b"da39a3ee5e6b4b0d3255bfef95601890afd80709").unwrap(),
+            ),
+            (
+                b"abc".to_vec(),
+                hex::decode_array::<20>(b"a9993e364706816aba3e25717850c26c9cd0d89d").unwrap(),
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+                hex::decode_array::<20>(b"84983e441c3bd26ebaae4aa1f95129e5e54670f1").unwrap(),
+            ),
+            (
+                b"0123456701234567012345670123456701234567012345670123456701234567".repeat(10),
+                hex::decode_array::<20>(b"dea356a2cddd90c7a7ecedc5ebb563934f460452").unwrap(),
+            ),
+            (
+                vec![b'a'; 1_000_000],
+                hex::decode_array::<20>(b"34aa973cd4c4daa4f61eeb2bdbad27316534016f").unwrap(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn known_vectors_single_update() {
+        for (input, expected) in vectors_sha1() {
+            assert_eq!(Sha1::hash(&input).as_ref(), expected);
+        }
+    }
+
+    #[test]
+    fn known_vectors_split_updates() {
+        for (input, expected) in vectors_sha1() {
+            for split in [1, 55, 56, 63, 64, 65] {
+                let mut hasher = Sha1::new();
+                for chunk in input.chunks(split) {
+                    hasher.update(chunk);
+                }
+                assert_eq!(hasher.sum().as_ref(), expected, "split={split}");
+            }
+        }
+    }
+
+    #[test]
+    fn hmac_sha1_vectors() {
+        // RFC 2202
+        let vectors: [(&[u8], &[u8], &[u8]); 3] = [
+            (&[0x0b; 20], b"Hi There", b"b617318655057264e28bc0b6fb378c8ef146be00"),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                b"effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+            ),
+            (
+                &[0xaa; 80],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                b"aa4ae5e15272d00e95705637ce8a3b55ed402112",
+            ),
+        ];
+        for (key, data, expected) in vectors {
+            let expected = hex::decode_array::<20>(expected).unwrap();
+            assert_eq!(Hmac::<Sha1>::mac(key, data).as_ref(), expected);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! HOTP (RFC 4226) and TOTP (RFC 6238) one-time passwords, as generated by authenticator apps.
+//!
+//! The codes are computed with HMAC-SHA1, which is what authenticator apps use by default. The
+//! shared secret is usually given to the user as base32 text, see [`decode_secret`].
+//!
+//! ```ignore
+//! use crypto::totp;
+//!
+//! let secret = totp::decode_secret("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap();
+//! let code = totp::totp_sha1(&secret, 59, 8, 30);
+//! assert_eq!(code, "94287082");
+//! assert!(totp::verify(&secret, &code, 59 + 30, 8, 30));
+//! ```
+
+use alloc::{string::String, vec::Vec};
+
+use constant_time_eq::constant_time_eq;
+
+use crate::{hmac::Hmac, sha1::Sha1};
+
+/// Returns the HOTP code for `counter`, with `digits` digits (6 for most authenticator apps).
+///
+/// # Panics
+///
+/// Panics if `digits` is not between 1 and 9.
+pub fn hotp_sha1(secret: &[u8], counter: u64, digits: u32) -> String {
+    assert!((1..=9).contains(&digits), "HOTP codes must have between 1 and 9 digits");
+
+    let hash = Hmac::<Sha1>::mac(secret, &counter.to_be_bytes());
+    let hash = hash.as_ref();
+
+    // dynamic truncation: 31 bits read at an offset given by the last 4 bits of the MAC
+    let offset = (hash[19] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+    let code = binary % 10u32.pow(digits);
+
+    return alloc::format!("{code:0width$}", width = digits as usize);
+}
+
+/// Returns the TOTP code at `time`, the number of seconds since the Unix epoch, for steps of
+/// `period` seconds (30 for most authenticator apps).
+///
+/// # Panics
+///
+/// Panics if `digits` is not between 1 and 9, or if `period` is 0.
+pub fn totp_sha1(secret: &[u8], time: u64, digits: u32, period: u64) -> String {
+    assert!(period > 0, "the TOTP period must not be 0");
+    return hotp_sha1(secret, time / period, digits);
+}
+
+/// Returns `true` if `code` is the TOTP code at `time`, or at the previous or next step, to
+/// accept codes from clocks that are slightly out of sync and codes entered at the end of a
+/// step.
+///
+/// The 3 codes are always computed and compared in constant time, so the time taken doesn't
+/// reveal which one matched. Callers should also prevent the reuse of a code that was accepted,
+/// and limit the number of attempts.
+///
+/// # Panics
+///
+/// Panics if `digits` is not between 1 and 9, or if `period` is 0.
+pub fn verify(secret: &[u8], code: &str, time: u64, digits: u32, period: u64) -> bool {
+    assert!(period > 0, "the TOTP period must not be 0");
+    let counter = time / period;
+
+    let mut valid = false;
+    for counter in [counter.checked_sub(1), Some(counter), counter.checked_add(1)] {
+        let Some(counter) = counter else {
+            continue;
+        };
+        let expected = hotp_sha1(secret, counter, digits);
+        // codes of the wrong length are rejected by constant_time_eq
+        valid |= constant_time_eq(expected.as_bytes(), code.as_bytes());
+    }
+
+    return valid;
+}
+
+/// Decodes a shared secret encoded in base32 (RFC 4648), as displayed by the services that
+/// offer TOTP and in `otpauth://` URIs.
+///
+/// Secrets are often lowercase, split in groups of characters, or missing their padding, so
+/// whitespace, hyphens and trailing `=` are ignored, and letters are case-insensitive. The
+/// decoding runs in constant time, see [`base32::decode_constant_time`].
+pub fn decode_secret(secret: &str) -> Result<Vec<u8>, base32::DecodeError> {
+    let mut normalized: Vec<u8> = secret
+        .trim_end_matches(|c: char| c == '=' || c.is_ascii_whitespace())
+        .bytes()
+        .filter(|b| !b.is_ascii_whitespace() && *b != b'-')
+        .map(|b| b.to_ascii_uppercase())
+        .collect();
+
+    let decoded = base32::decode_constant_time(&normalized, base32::Alphabet::Rfc4648NoPadding);
+
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut normalized);
+
+    return decoded;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B, SHA1 mode
+    const SECRET: &[u8] = b"12345678901234567890";
+    const TOTP_VECTORS: [(u64, &str); 6] = [
+        (59, "94287082"),
+        (1111111109, "07081804"),
+        (1111111111, "14050471"),
+        (1234567890, "89005924"),
+        (2000000000, "69279037"),
+        (20000000000, "65353130"),
+    ];
+
+    #[test]
+    fn hotp_vectors() {
+        // RFC 4226 appendix D
+        let expected = [
+            "755224", "287082", "359152", "969429", "338314", "254676", "287922", "162583", "399871", "520489",
+        ];
+        for (counter, expected) in expected.iter().enumerate() {
+            assert_eq!(hotp_sha1(SECRET, counter as u64, 6), *expected, "counter={counter}");
+        }
+    }
+
+    #[test]
+    fn totp_vectors() {
+        for (time, expected) in TOTP_VECTORS {
+            assert_eq!(totp_sha1(SECRET, time, 8, 30), expected, "time={time}");
+            // the 6 digits codes are the end of the 8 digits codes
+            assert_eq!(totp_sha1(SECRET, time, 6, 30), expected[2..], "time={time}");
+        }
+    }
+
+    #[test]
+    fn verify_window() {
+        for (time, code) in TOTP_VECTORS {
+            assert!(verify(SECRET, code, time, 8, 30), "time={time}");
+            assert!(verify(SECRET, code, time - 30, 8, 30), "time={time}");
+            assert!(verify(SECRET, code, time + 30, 8, 30), "time={time}");
+            assert!(!verify(SECRET, code, time + 60, 8, 30), "time={time}");
+            if time > 60 {
+                assert!(!verify(SECRET, code, time - 60, 8, 30), "time={time}");
+            }
+            assert!(!verify(SECRET, &code[2..], time, 8, 30), "time={time}");
+        }
+
+        // the first step has no previous step
+        let code = totp_sha1(SECRET, 0, 6, 30);
+        assert!(verify(SECRET, &code, 0, 6, 30));
+        assert!(!verify(SECRET, "", 0, 6, 30));
+    }
+
+    #[test]
+    fn secret() {
+        assert_eq!(decode_secret("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap(), SECRET);
+        assert_eq!(decode_secret("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap(), SECRET);
+        assert_eq!(decode_secret("GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\n").unwrap(), SECRET);
+        assert_eq!(decode_secret("MZXW6===").unwrap(), b"foo");
+        assert_eq!(decode_secret("mzxw-6").unwrap(), b"foo");
+        assert_eq!(decode_secret("MZXW!"), Err(base32::DecodeError::InvalidInput));
+        assert_eq!(decode_secret("MZX"), Err(base32::DecodeError::InvalidLength));
+    }
+}
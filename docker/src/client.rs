@@ -1,23 +1,29 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Instant};
 
 use bytes::Bytes;
 use hyper::{
-    Method, StatusCode, Uri,
+    Method, Response, StatusCode, Uri,
+    body::Incoming,
     client::conn::http1::SendRequest,
-    header::{CONTENT_TYPE, HOST},
+    header::{CONTENT_ENCODING, CONTENT_TYPE, HOST, HeaderValue},
 };
 use hyper_utils::{
-    http_body_util::{BodyExt, Full},
+    http_body_util::{BodyExt, Full, LimitError},
     rt::TokioIo,
 };
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use tokio::{net::UnixStream, sync::Mutex};
-use tracing::{debug, error};
+use tracing::{Span, debug, error, field};
 
 use crate::error::Error;
 
+/// The default maximum size of the responses of the Docker daemon, see
+/// [`Client::with_max_response_size`].
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 64 * 1024 * 1024;
+
 pub struct Client {
     socket_path: PathBuf,
+    max_response_size: usize,
     // we use the interior mutability pattern to avoid users needing to make the client mut
     // each time they want to send a request.
     // See here to learn more about the Interior Mutability Pattern
@@ -33,10 +39,19 @@ impl Client {
 
         return Client {
             socket_path: socket_path,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
             socket: Mutex::new(None),
         };
     }
 
+    /// Set the maximum size in bytes of the response bodies, after decompression.
+    /// Larger responses fail with [`Error::ResponseTooLarge`] instead of being buffered in memory.
+    /// Defaults to [`DEFAULT_MAX_RESPONSE_SIZE`].
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Client {
+        self.max_response_size = max_response_size;
+        return self;
+    }
+
     /// connect to the docker host.
     /// Note that you don't necessarily need to call `connect`. The client automatically connects
     /// to the Docker host on the first request if `connect` is not called before.
@@ -67,8 +82,20 @@ impl Client {
         return Ok(());
     }
 
+    /// Close the connection to the docker host, if any. The next request opens a new one.
+    pub(crate) async fn disconnect(&self) {
+        self.socket.lock().await.take();
+    }
+
+    #[tracing::instrument(
+        name = "docker_request",
+        level = "debug",
+        skip_all,
+        fields(method = %method, path = path, status = field::Empty, elapsed_ms = field::Empty)
+    )]
     pub(crate) async fn send_request<R: DeserializeOwned, S: Serialize>(
         &self,
+        method: Method,
         path: &str,
         query: Option<S>,
         body: Option<S>,
@@ -100,14 +127,18 @@ impl Client {
             .map_err(|err| Error::Unspecified(format!("encoding body to JSON: {err}")))?;
         let body_bytes = Bytes::from(body);
 
-        let hyper_request = hyper::Request::builder()
-            .method(Method::GET)
+        let request_builder = hyper::Request::builder()
+            .method(method)
             .uri(hyper_uri)
             .header(HOST, "docker")
-            .header(CONTENT_TYPE, "application/json")
+            .header(CONTENT_TYPE, "application/json");
+        #[cfg(feature = "gzip")]
+        let request_builder = request_builder.header(hyper::header::ACCEPT_ENCODING, "gzip");
+        let hyper_request = request_builder
             .body(Full::new(body_bytes))
             .map_err(|err| Error::Unspecified(format!("building request: {err}")))?;
 
+        let start = Instant::now();
         let response = {
             let mut socket = self.socket.lock().await;
             // we can safely unwrap here as `connect` would have returned an error earlier if the connection
@@ -120,11 +151,25 @@ impl Client {
                 .map_err(|err| Error::Unspecified(format!("sending request: {err}")))?
         };
 
-        if response.status() != StatusCode::OK {
-            return Err(Error::Unspecified(format!(
-                "received not OK status code: {}",
-                response.status()
-            )));
+        let span = Span::current();
+        span.record("status", response.status().as_u16());
+
+        let status = response.status();
+        if !status.is_success() {
+            // the error message is best effort, the status code is the error
+            let message = self
+                .read_body(response)
+                .await
+                .ok()
+                .and_then(|body| error_message(&body));
+            span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+            let message = message.unwrap_or_else(|| {
+                status
+                    .canonical_reason()
+                    .unwrap_or("received not OK status code")
+                    .to_string()
+            });
+            return Err(Error::Status(status, message));
         }
 
         // let mut response_body = BytesMut::with_capacity(response.size_hint().upper().unwrap_or(500) as usize);
@@ -135,14 +180,394 @@ impl Client {
         //     }
         // }
 
+        let content_type = response.headers().get(CONTENT_TYPE).cloned();
+        let response_body = self.read_body(response).await?;
+        span.record("elapsed_ms", start.elapsed().as_millis() as u64);
+        debug!(response_size = response_body.len(), "received response");
+
+        // some endpoints, such as `/containers/{id}/rename`, respond with `204 No Content`: an empty
+        // body is deserialized like `null`, e.g. into `()`
+        let response_body = if response_body.is_empty() {
+            Bytes::from_static(b"null")
+        } else {
+            check_content_type(content_type.as_ref())?;
+            response_body
+        };
+        let res = serde_json::from_slice(&response_body)
+            .map_err(|err| Error::Unspecified(format!("parsing response: {err}")))?;
+
+        return Ok(res);
+    }
+
+    /// Read and decode the body of `response`, up to the maximum response size.
+    async fn read_body(&self, response: Response<Incoming>) -> Result<Bytes, Error> {
+        let content_encoding = response.headers().get(CONTENT_ENCODING).cloned();
         let response_body = response
+            .limit(self.max_response_size)
             .collect()
             .await
-            .map_err(|err| Error::Unspecified(format!("reading response: {err}")))?
+            .map_err(|err| match err {
+                LimitError::LengthLimitExceeded(limit) => Error::ResponseTooLarge(limit),
+                LimitError::Body(err) => Error::Unspecified(format!("reading response: {err}")),
+            })?
             .to_bytes();
-        let res = serde_json::from_slice(&response_body)
-            .map_err(|err| Error::Unspecified(format!("parsing response: {err}")))?;
+        return decode_body(content_encoding.as_ref(), response_body, self.max_response_size);
+    }
+}
 
-        return Ok(res);
+/// Returns the message of an error response of the Docker daemon, e.g.
+/// `{"message": "No such container: web"}`.
+fn error_message(body: &[u8]) -> Option<String> {
+    #[derive(Deserialize)]
+    struct ErrorResponse {
+        message: String,
+    }
+
+    return serde_json::from_slice::<ErrorResponse>(body)
+        .ok()
+        .map(|response| response.message);
+}
+
+/// Check that the `Content-Type` header of a response, if any, is JSON, so that errors such as an
+/// HTML page returned by a proxy are reported as such instead of as a JSON syntax error.
+fn check_content_type(content_type: Option<&HeaderValue>) -> Result<(), Error> {
+    let content_type = match content_type {
+        Some(content_type) => content_type.as_bytes(),
+        None => return Ok(()),
+    };
+
+    // the media type may be followed by parameters, e.g. `application/json; charset=utf-8`
+    let media_type = content_type
+        .split(|b| *b == b';')
+        .next()
+        .unwrap_or_default()
+        .trim_ascii();
+    if media_type.eq_ignore_ascii_case(b"application/json")
+        || (media_type.len() > 5 && media_type[media_type.len() - 5..].eq_ignore_ascii_case(b"+json"))
+    {
+        return Ok(());
+    }
+
+    return Err(Error::Unspecified(format!(
+        "unexpected response Content-Type: {}",
+        String::from_utf8_lossy(content_type)
+    )));
+}
+
+/// Decode the response body according to its `Content-Encoding` header. Decoded bodies larger than
+/// `max_size` return [`Error::ResponseTooLarge`].
+#[cfg_attr(not(feature = "gzip"), allow(unused_variables))]
+fn decode_body(content_encoding: Option<&HeaderValue>, body: Bytes, max_size: usize) -> Result<Bytes, Error> {
+    let content_encoding = match content_encoding {
+        Some(content_encoding) => content_encoding.as_bytes(),
+        None => return Ok(body),
+    };
+
+    match content_encoding {
+        b"identity" => return Ok(body),
+        #[cfg(feature = "gzip")]
+        b"gzip" | b"x-gzip" => {
+            // the decompression stops as soon as the body is too large, such as for gzip bombs
+            let decoded = inflate::gunzip(&body, max_size).map_err(|err| match err {
+                inflate::Error::OutputTooLarge => Error::ResponseTooLarge(max_size),
+                err => Error::Unspecified(format!("decompressing gzip response: {err}")),
+            })?;
+            return Ok(Bytes::from(decoded));
+        }
+        _ => {
+            return Err(Error::Unspecified(format!(
+                "unsupported response Content-Encoding: {}",
+                String::from_utf8_lossy(content_encoding)
+            )));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tracing::span::{Attributes, Id, Record};
+
+    use super::*;
+
+    type CapturedSpan = (String, Vec<(String, String)>);
+
+    /// A minimal subscriber recording the name and fields of every span.
+    #[derive(Clone, Default)]
+    struct SpanCapture {
+        spans: Arc<StdMutex<Vec<CapturedSpan>>>,
+    }
+
+    struct FieldVisitor<'a>(&'a mut Vec<(String, String)>);
+
+    impl field::Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &field::Field, value: &str) {
+            self.0.push((field.name().to_string(), value.to_string()));
+        }
+
+        fn record_debug(&mut self, field: &field::Field, value: &dyn std::fmt::Debug) {
+            self.0.push((field.name().to_string(), format!("{value:?}")));
+        }
+    }
+
+    impl tracing::Subscriber for SpanCapture {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let mut fields = Vec::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((attrs.metadata().name().to_string(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            values.record(&mut FieldVisitor(&mut spans[span.into_u64() as usize - 1].1));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &tracing::Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[tokio::test]
+    async fn send_request_emits_span() {
+        let socket_path = std::env::temp_dir().join(format!("docker-client-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n[]")
+                .await
+                .unwrap();
+        });
+
+        let capture = SpanCapture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+
+        let client = Client::new(socket_path.to_str());
+        let containers = client.list_containers(None).await.unwrap();
+        assert!(containers.is_empty());
+        let _ = std::fs::remove_file(&socket_path);
+
+        let spans = capture.spans.lock().unwrap();
+        let (_, fields) = spans
+            .iter()
+            .find(|(name, _)| name == "docker_request")
+            .expect("docker_request span not found");
+        let field = |name: &str| fields.iter().find(|(field, _)| field == name).map(|(_, value)| value.as_str());
+        assert_eq!(field("path"), Some("/containers/json"));
+        assert_eq!(field("method"), Some("GET"));
+        assert_eq!(field("status"), Some("200"));
+        assert!(field("elapsed_ms").is_some());
+    }
+
+    #[tokio::test]
+    async fn no_content_response() {
+        let socket_path = std::env::temp_dir().join(format!("docker-client-rename-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let n = stream.read(&mut request).await.unwrap();
+            stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").await.unwrap();
+            String::from_utf8_lossy(&request[..n]).into_owned()
+        });
+
+        let client = Client::new(socket_path.to_str());
+        client.rename_container("web", "web-old").await.unwrap();
+        let _ = std::fs::remove_file(&socket_path);
+
+        let request = server.await.unwrap();
+        assert!(
+            request.starts_with("POST /containers/web/rename?name=web-old HTTP/1.1\r\n"),
+            "{request}"
+        );
+    }
+
+    /// Serve `response` to the first request received on a new socket, and return the path of the
+    /// socket.
+    fn serve_once(name: &str, response: &'static [u8]) -> std::path::PathBuf {
+        let socket_path = std::env::temp_dir().join(format!("docker-client-{name}-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).await.unwrap();
+            let _ = stream.write_all(response).await;
+        });
+        return socket_path;
+    }
+
+    #[tokio::test]
+    async fn response_within_limit() {
+        let socket_path = serve_once(
+            "within-limit",
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: 27\r\n\r\n[{\"Id\":\"abc\"},{\"Id\":\"def\"}]",
+        );
+
+        let client = Client::new(socket_path.to_str()).with_max_response_size(27);
+        let containers = client.list_containers(None).await.unwrap();
+        let _ = std::fs::remove_file(&socket_path);
+        assert_eq!(containers.len(), 2);
+        assert_eq!(containers[1].id.as_deref(), Some("def"));
+    }
+
+    #[tokio::test]
+    async fn response_over_limit() {
+        let socket_path = serve_once(
+            "over-limit",
+            b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 27\r\n\r\n[{\"Id\":\"abc\"},{\"Id\":\"def\"}]",
+        );
+
+        let client = Client::new(socket_path.to_str()).with_max_response_size(26);
+        let err = client.list_containers(None).await.unwrap_err();
+        let _ = std::fs::remove_file(&socket_path);
+        assert!(matches!(err, Error::ResponseTooLarge(26)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn response_not_json() {
+        let socket_path = serve_once(
+            "not-json",
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 13\r\n\r\n<html></html>",
+        );
+
+        let client = Client::new(socket_path.to_str());
+        let err = client.list_containers(None).await.unwrap_err();
+        let _ = std::fs::remove_file(&socket_path);
+        assert_eq!(err.to_string(), "unexpected response Content-Type: text/html");
+    }
+
+    #[tokio::test]
+    async fn error_response() {
+        let socket_path = serve_once(
+            "error",
+            b"HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: 36\r\n\r\n{\"message\":\"No such container: web\"}",
+        );
+
+        let client = Client::new(socket_path.to_str());
+        let err = client.list_containers(None).await.unwrap_err();
+        let _ = std::fs::remove_file(&socket_path);
+        assert!(
+            matches!(&err, Error::Status(StatusCode::NOT_FOUND, message) if message == "No such container: web"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn error_response_without_message() {
+        let socket_path = serve_once(
+            "error-without-message",
+            b"HTTP/1.1 502 Bad Gateway\r\nContent-Type: text/html\r\nContent-Length: 13\r\n\r\n<html></html>",
+        );
+
+        let client = Client::new(socket_path.to_str());
+        let err = client.list_containers(None).await.unwrap_err();
+        let _ = std::fs::remove_file(&socket_path);
+        assert!(
+            matches!(&err, Error::Status(StatusCode::BAD_GATEWAY, message) if message == "Bad Gateway"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn wait_container_timeout() {
+        use crate::model::WaitCondition;
+
+        let socket_path = std::env::temp_dir().join(format!("docker-client-wait-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).unwrap();
+        tokio::spawn(async move {
+            // the first connection never responds, like a container that never exits
+            let (_waiting, _) = listener.accept().await.unwrap();
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request).await.unwrap();
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n[]")
+                .await;
+        });
+
+        let client = Client::new(socket_path.to_str());
+        let timeout = std::time::Duration::from_millis(50);
+        let err = client
+            .wait_container("job", WaitCondition::NextExit, timeout)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout(t) if t == timeout), "{err}");
+
+        // the next request uses a new connection
+        let containers = client.list_containers(None).await.unwrap();
+        let _ = std::fs::remove_file(&socket_path);
+        assert!(containers.is_empty());
+    }
+
+    #[test]
+    fn json_content_types() {
+        for content_type in [
+            "application/json",
+            "Application/JSON",
+            "application/json; charset=utf-8",
+            "application/problem+json",
+        ] {
+            let content_type = HeaderValue::from_static(content_type);
+            assert!(check_content_type(Some(&content_type)).is_ok(), "{content_type:?}");
+        }
+        assert!(check_content_type(None).is_ok());
+        for content_type in ["text/plain", "application/jsonp", "+json", ""] {
+            let content_type = HeaderValue::from_static(content_type);
+            assert!(check_content_type(Some(&content_type)).is_err(), "{content_type:?}");
+        }
+    }
+
+    #[test]
+    fn decode_identity_body() {
+        let body = Bytes::from_static(br#"{"Id":"abc"}"#);
+        assert_eq!(decode_body(None, body.clone(), 1024).unwrap(), body);
+        assert_eq!(
+            decode_body(Some(&HeaderValue::from_static("identity")), body.clone(), 1024).unwrap(),
+            body
+        );
+        assert!(decode_body(Some(&HeaderValue::from_static("br")), body, 1024).is_err());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn decode_gzip_body() {
+        use crate::model::ContainerSummary;
+
+        let json = br#"[{"Id":"abc","Names":["/test"]}]"#;
+        let gzipped = Bytes::from_static(
+            b"\
+            \x1f\x8b\x08\x00\x00\x00\x00\x00\x02\xff\x8b\xae\x56\xf2\x4c\x51\xb2\x52\x4a\x4c\x4a\x56\xd2\x51\
+            \xf2\x4b\xcc\x4d\x2d\x56\xb2\x8a\x56\xd2\x2f\x49\x2d\x2e\x51\x8a\xad\x8d\x05\x00\x28\x6c\x48\xd3\
+            \x20\x00\x00\x00",
+        );
+
+        let decoded = decode_body(Some(&HeaderValue::from_static("gzip")), gzipped.clone(), json.len()).unwrap();
+        assert_eq!(decoded.as_ref(), json);
+
+        let containers: Vec<ContainerSummary> = serde_json::from_slice(&decoded).unwrap();
+        assert_eq!(containers[0].id.as_deref(), Some("abc"));
+        assert_eq!(containers[0].names, Some(vec!["/test".to_string()]));
+
+        // the limit applies to the decompressed body
+        let err = decode_body(Some(&HeaderValue::from_static("gzip")), gzipped, json.len() - 1).unwrap_err();
+        assert!(matches!(err, Error::ResponseTooLarge(max) if max == json.len() - 1));
     }
 }
@@ -1,13 +1,463 @@
+use std::time::Duration;
+
+use hyper::Method;
+use serde::Serialize;
+
 use crate::{
     Client, Error,
-    model::{ContainerSummary, ListContainersOptions},
+    model::{
+        ContainerStats, ContainerSummary, ContainerTop, ListContainersOptions, UpdateConfig, UpdateContainerResponse,
+        WaitCondition, WaitResponse,
+    },
 };
 
+#[derive(Debug, Clone, Serialize)]
+struct ContainerStatsOptions {
+    stream: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ContainerTopOptions<'a> {
+    ps_args: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct RenameContainerOptions<'a> {
+    name: &'a str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WaitContainerOptions {
+    condition: WaitCondition,
+}
+
 impl Client {
     pub async fn list_containers(
         &self,
         options: Option<ListContainersOptions>,
     ) -> Result<Vec<ContainerSummary>, Error> {
-        return self.send_request("/containers/json", options, None).await;
+        return self.send_request(Method::GET, "/containers/json", options, None).await;
+    }
+
+    /// Get the resource usage statistics of a container (CPU, memory, network and block I/O).
+    ///
+    /// The statistics are read once (`stream=false`): Docker waits for a second read to fill
+    /// `precpu_stats`, so the call takes about one second, and
+    /// [`ContainerStats::cpu_percent`] can be computed from the returned value.
+    pub async fn container_stats(&self, id: &str) -> Result<ContainerStats, Error> {
+        let options = ContainerStatsOptions {
+            stream: false,
+        };
+        return self
+            .send_request(Method::GET, &format!("/containers/{id}/stats"), Some(options), None)
+            .await;
+    }
+
+    /// List the processes running inside a container, like `docker top`.
+    ///
+    /// `ps_args` are the arguments passed to `ps` (e.g. `aux`), Docker uses `-ef` by default.
+    pub async fn container_top(&self, id: &str, ps_args: Option<&str>) -> Result<ContainerTop, Error> {
+        let options = ps_args.map(|ps_args| ContainerTopOptions {
+            ps_args,
+        });
+        return self
+            .send_request(Method::GET, &format!("/containers/{id}/top"), options, None)
+            .await;
+    }
+
+    /// Rename a container to `new_name`.
+    pub async fn rename_container(&self, id: &str, new_name: &str) -> Result<(), Error> {
+        let options = RenameContainerOptions {
+            name: new_name,
+        };
+        return self
+            .send_request(Method::POST, &format!("/containers/{id}/rename"), Some(options), None)
+            .await;
+    }
+
+    /// Update the resource limits of a container without restarting it, e.g. its memory limit.
+    pub async fn update_container(&self, id: &str, config: UpdateConfig) -> Result<UpdateContainerResponse, Error> {
+        return self
+            .send_request(Method::POST, &format!("/containers/{id}/update"), None, Some(config))
+            .await;
+    }
+
+    /// Block until a container reaches the given `condition`, e.g. until a one-shot job exits,
+    /// and return its exit code.
+    ///
+    /// This is a long-poll: Docker only responds once the condition is met, which can take
+    /// arbitrarily long, so the wait fails with [`Error::Timeout`] after `timeout`. The client
+    /// sends its requests over a single connection, so the other requests of this client wait
+    /// until this one completes. On timeout, the connection is closed and the next request opens
+    /// a new one.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn example(client: docker::Client) -> Result<(), docker::Error> {
+    /// use std::time::Duration;
+    ///
+    /// use docker::model::WaitCondition;
+    ///
+    /// let res = client
+    ///     .wait_container("my-job", WaitCondition::NextExit, Duration::from_secs(600))
+    ///     .await?;
+    /// println!("exited with code {}", res.status_code);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_container(
+        &self,
+        id: &str,
+        condition: WaitCondition,
+        timeout: Duration,
+    ) -> Result<WaitResponse, Error> {
+        let options = WaitContainerOptions {
+            condition,
+        };
+        let request = self.send_request(Method::POST, &format!("/containers/{id}/wait"), Some(options), None);
+        match tokio::time::timeout(timeout, request).await {
+            Ok(res) => return res,
+            Err(_) => {
+                // the connection is still waiting for the response, so it can't be reused
+                self.disconnect().await;
+                return Err(Error::Timeout(timeout));
+            }
+        }
+    }
+
+    /// List containers page by page, from the most recently created to the oldest.
+    ///
+    /// Docker's API has no cursors, so each page is requested with `limit = page_size` and a
+    /// `before` filter set to the last container of the previous page. The `limit` and `before`
+    /// fields of `options` are overwritten. Note that Docker includes non-running containers when
+    /// a `limit` is set, so `all` is effectively always `true`.
+    ///
+    /// Consistency caveats: pages are not a snapshot. Containers created while paging are not
+    /// returned, and if the last container of a page is removed before the next page is
+    /// requested, Docker returns an error for the next page.
+    ///
+    /// # Panics
+    /// Panics if `page_size` is `0`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn example(client: docker::Client) -> Result<(), docker::Error> {
+    /// let mut pages = client.list_containers_paged(None, 100);
+    /// while let Some(page) = pages.next_page().await? {
+    ///     println!("{} containers", page.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_containers_paged(
+        &self,
+        options: Option<ListContainersOptions>,
+        page_size: usize,
+    ) -> ContainerPages<'_> {
+        assert!(page_size > 0, "page_size must be greater than 0");
+
+        return ContainerPages {
+            client: self,
+            options: options.unwrap_or_default(),
+            page_size,
+            cursor: None,
+            done: false,
+        };
+    }
+}
+
+/// Successive pages of containers returned by [`Client::list_containers_paged`].
+pub struct ContainerPages<'a> {
+    client: &'a Client,
+    options: ListContainersOptions,
+    page_size: usize,
+    /// ID of the last container of the previous page.
+    cursor: Option<String>,
+    done: bool,
+}
+
+impl ContainerPages<'_> {
+    /// Fetch the next page of containers. Returns `None` once all the containers have been
+    /// returned.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<ContainerSummary>>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let page = self.client.list_containers(Some(self.page_options())).await?;
+        return Ok(self.advance(page));
+    }
+
+    fn page_options(&self) -> ListContainersOptions {
+        let mut options = self.options.clone();
+        options.limit = Some(self.page_size as isize);
+        match &self.cursor {
+            Some(cursor) => {
+                options.filters.insert("before".to_string(), vec![cursor.clone()]);
+            }
+            None => {
+                options.filters.remove("before");
+            }
+        }
+        return options;
+    }
+
+    /// Record the position of `page` and return it, or `None` if it's empty.
+    fn advance(&mut self, page: Vec<ContainerSummary>) -> Option<Vec<ContainerSummary>> {
+        // a short page means that there is nothing left. Same if we can't build a cursor.
+        let cursor = page.last().and_then(|container| container.id.clone());
+        if page.len() < self.page_size || cursor.is_none() {
+            self.done = true;
+        }
+        self.cursor = cursor;
+
+        if page.is_empty() {
+            return None;
+        }
+        return Some(page);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Captured from `GET /containers/{id}/stats?stream=false` (cgroups v1, trimmed).
+    const STATS: &str = r#"{
+        "read": "2024-05-12T10:21:43.526011729Z",
+        "preread": "2024-05-12T10:21:42.516685325Z",
+        "pids_stats": {"current": 12, "limit": 18446744073709551615},
+        "blkio_stats": {
+            "io_service_bytes_recursive": [
+                {"major": 8, "minor": 0, "op": "read", "value": 6328320},
+                {"major": 8, "minor": 0, "op": "write", "value": 16384}
+            ],
+            "io_serviced_recursive": null,
+            "io_queue_recursive": null
+        },
+        "num_procs": 0,
+        "storage_stats": {},
+        "cpu_stats": {
+            "cpu_usage": {
+                "total_usage": 1227032000,
+                "percpu_usage": [613516000, 613516000],
+                "usage_in_kernelmode": 480000000,
+                "usage_in_usermode": 740000000
+            },
+            "system_cpu_usage": 7620410000000,
+            "online_cpus": 2,
+            "throttling_data": {"periods": 0, "throttled_periods": 0, "throttled_time": 0}
+        },
+        "precpu_stats": {
+            "cpu_usage": {
+                "total_usage": 1207032000,
+                "percpu_usage": [603516000, 603516000],
+                "usage_in_kernelmode": 470000000,
+                "usage_in_usermode": 730000000
+            },
+            "system_cpu_usage": 7618410000000,
+            "online_cpus": 2,
+            "throttling_data": {"periods": 0, "throttled_periods": 0, "throttled_time": 0}
+        },
+        "memory_stats": {
+            "usage": 10293248,
+            "stats": {"cache": 1621, "inactive_file": 1024000, "rss": 8192000},
+            "limit": 4109926400
+        },
+        "name": "/web",
+        "id": "4a3a4fdf1e2bfa0b36ac36cbd56b4a0e1b6fadcd52d5fcb4e9ebbd8f0fae3a5e",
+        "networks": {
+            "eth0": {
+                "rx_bytes": 5338, "rx_packets": 36, "rx_errors": 0, "rx_dropped": 0,
+                "tx_bytes": 648, "tx_packets": 8, "tx_errors": 0, "tx_dropped": 0
+            }
+        }
+    }"#;
+
+    #[test]
+    fn deserialize_container_stats() {
+        let stats: ContainerStats = serde_json::from_str(STATS).unwrap();
+
+        assert_eq!(stats.name.as_deref(), Some("/web"));
+        let memory = stats.memory_stats.as_ref().unwrap();
+        assert_eq!(memory.usage, Some(10293248));
+        assert_eq!(memory.limit, Some(4109926400));
+        assert_eq!(memory.stats.as_ref().unwrap()["inactive_file"], 1024000);
+
+        let eth0 = &stats.networks.as_ref().unwrap()["eth0"];
+        assert_eq!(eth0.rx_bytes, 5338);
+        assert_eq!(eth0.tx_bytes, 648);
+
+        let blkio = stats.blkio_stats.as_ref().unwrap();
+        let io = blkio.io_service_bytes_recursive.as_ref().unwrap();
+        assert_eq!((io[0].op.as_str(), io[0].value), ("read", 6328320));
+        assert_eq!(blkio.io_serviced_recursive, None);
+
+        let cpu = stats.cpu_stats.as_ref().unwrap();
+        assert_eq!(cpu.cpu_usage.total_usage, 1227032000);
+        assert_eq!(cpu.online_cpus, Some(2));
+    }
+
+    #[test]
+    fn container_stats_cpu_percent() {
+        let mut stats: ContainerStats = serde_json::from_str(STATS).unwrap();
+        // 20ms of CPU time over 2s of system time (1s on each of the 2 CPUs)
+        let percent = stats.cpu_percent().unwrap();
+        assert!((percent - 2.0).abs() < 1e-9, "{percent}");
+
+        // without `online_cpus`, the number of CPUs is the length of `percpu_usage`
+        stats.cpu_stats.as_mut().unwrap().online_cpus = None;
+        assert!((stats.cpu_percent().unwrap() - 2.0).abs() < 1e-9);
+
+        // the first read of a stream has empty `precpu_stats`
+        stats.precpu_stats = Some(Default::default());
+        stats.cpu_stats.as_mut().unwrap().cpu_usage.total_usage = 0;
+        assert_eq!(stats.cpu_percent(), Some(0.0));
+
+        stats.cpu_stats = None;
+        assert_eq!(stats.cpu_percent(), None);
+    }
+
+    #[test]
+    fn deserialize_container_top() {
+        // captured from `GET /containers/{id}/top`
+        let top = r#"{
+            "Processes": [
+                ["root", "13642", "882", "0", "17:03", "pts/0", "00:00:00", "/bin/bash"],
+                ["root", "13735", "13642", "0", "17:06", "pts/0", "00:00:00", "sleep 10"]
+            ],
+            "Titles": ["UID", "PID", "PPID", "C", "STIME", "TTY", "TIME", "CMD"]
+        }"#;
+        let top: ContainerTop = serde_json::from_str(top).unwrap();
+
+        assert_eq!(top.titles.len(), 8);
+        assert_eq!(top.titles[1], "PID");
+        assert_eq!(top.processes.len(), 2);
+        assert_eq!(top.processes[1][1], "13735");
+        assert_eq!(top.processes[1][7], "sleep 10");
+    }
+
+    #[test]
+    fn container_top_ps_args_query() {
+        let options = ContainerTopOptions {
+            ps_args: "aux",
+        };
+        assert_eq!(serde_urlencoded::to_string(options).unwrap(), "ps_args=aux");
+
+        let options = ContainerTopOptions {
+            ps_args: "-o pid,comm&x=y",
+        };
+        assert_eq!(serde_urlencoded::to_string(options).unwrap(), "ps_args=-o+pid%2Ccomm%26x%3Dy");
+    }
+
+    #[test]
+    fn rename_container_query() {
+        let options = RenameContainerOptions {
+            name: "web-old",
+        };
+        assert_eq!(serde_urlencoded::to_string(options).unwrap(), "name=web-old");
+
+        let options = RenameContainerOptions {
+            name: "a b&c",
+        };
+        assert_eq!(serde_urlencoded::to_string(options).unwrap(), "name=a+b%26c");
+    }
+
+    #[test]
+    fn serialize_update_config() {
+        let config = UpdateConfig {
+            nano_cpus: Some(1_500_000_000),
+            memory: Some(512 * 1024 * 1024),
+            memory_swap: Some(-1),
+            ..Default::default()
+        };
+        // the fields that are not set are omitted, so that they are not updated
+        assert_eq!(
+            serde_json::to_value(&config).unwrap(),
+            serde_json::json!({"NanoCpus": 1500000000, "Memory": 536870912, "MemorySwap": -1})
+        );
+        assert_eq!(serde_json::to_string(&UpdateConfig::default()).unwrap(), "{}");
+
+        let res: UpdateContainerResponse = serde_json::from_str(r#"{"Warnings": []}"#).unwrap();
+        assert_eq!(res.warnings, Some(vec![]));
+    }
+
+    #[test]
+    fn deserialize_wait_response() {
+        let res: WaitResponse = serde_json::from_str(r#"{"StatusCode": 137, "Error": null}"#).unwrap();
+        assert_eq!(res.status_code, 137);
+        assert_eq!(res.error, None);
+
+        let res: WaitResponse =
+            serde_json::from_str(r#"{"StatusCode": -1, "Error": {"Message": "container not found"}}"#).unwrap();
+        assert_eq!(res.status_code, -1);
+        assert_eq!(res.error.unwrap().message.as_deref(), Some("container not found"));
+
+        // older versions of Docker don't return the `Error` field
+        let res: WaitResponse = serde_json::from_str(r#"{"StatusCode": 0}"#).unwrap();
+        assert_eq!(res, WaitResponse::default());
+    }
+
+    #[test]
+    fn wait_container_condition_query() {
+        for (condition, query) in [
+            (WaitCondition::NotRunning, "condition=not-running"),
+            (WaitCondition::NextExit, "condition=next-exit"),
+            (WaitCondition::Removed, "condition=removed"),
+        ] {
+            let options = WaitContainerOptions {
+                condition,
+            };
+            assert_eq!(serde_urlencoded::to_string(options).unwrap(), query);
+        }
+        assert_eq!(WaitCondition::default(), WaitCondition::NotRunning);
+    }
+
+    /// Mimics the Docker daemon: containers are sorted from the most recently created, and the
+    /// `before` filter and `limit` are applied.
+    fn mock_list_containers(containers: &[ContainerSummary], options: &ListContainersOptions) -> Vec<ContainerSummary> {
+        let start = match options.filters.get("before") {
+            Some(before) => {
+                containers
+                    .iter()
+                    .position(|container| container.id.as_ref() == Some(&before[0]))
+                    .unwrap()
+                    + 1
+            }
+            None => 0,
+        };
+        let limit = options.limit.map(|limit| limit as usize).unwrap_or(containers.len());
+        return containers.iter().skip(start).take(limit).cloned().collect();
+    }
+
+    #[test]
+    fn paging_yields_all_containers() {
+        let containers: Vec<ContainerSummary> = (0..23)
+            .rev()
+            .map(|i| ContainerSummary {
+                id: Some(format!("container-{i}")),
+                created: Some(1_700_000_000 + i),
+                ..Default::default()
+            })
+            .collect();
+        let client = Client::new(None);
+
+        for page_size in [1, 5, 10, 23, 50] {
+            let mut pages = client.list_containers_paged(None, page_size);
+            let mut paged = Vec::new();
+            while !pages.done {
+                let page = mock_list_containers(&containers, &pages.page_options());
+                match pages.advance(page) {
+                    Some(page) => {
+                        assert!(page.len() <= page_size);
+                        paged.extend(page);
+                    }
+                    None => break,
+                }
+            }
+
+            let all = mock_list_containers(&containers, &ListContainersOptions::default());
+            assert_eq!(paged, all, "page_size: {page_size}");
+        }
     }
 }
@@ -3,5 +3,5 @@ pub mod containers;
 mod error;
 pub mod model;
 
-pub use client::Client;
+pub use client::{Client, DEFAULT_MAX_RESPONSE_SIZE};
 pub use error::Error;
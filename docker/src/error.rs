@@ -1,7 +1,89 @@
+use std::time::Duration;
+
+use hyper::StatusCode;
+use hyper_utils::HttpClientError;
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("connecting to docker socket: {0}")]
-    Connecting(Box<dyn std::error::Error>),
+    Connecting(#[source] Box<dyn std::error::Error + Send + Sync>),
+    /// The status code and the error message of a response of the Docker daemon.
+    #[error("received not OK status code: {0}: {1}")]
+    Status(StatusCode, String),
+    #[error("response body is larger than the maximum of {0} bytes")]
+    ResponseTooLarge(usize),
+    #[error("request timed out after {0:?}")]
+    Timeout(Duration),
     #[error("{0}")]
     Unspecified(String),
 }
+
+/// The message of the `HttpClientError` doesn't repeat its source: the connection errors are
+/// returned by `source()`, and the status errors have the error message of the daemon as message.
+impl From<Error> for HttpClientError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::Connecting(source) => HttpClientError {
+                code: None,
+                message: "connecting to docker socket".to_string(),
+                source: Some(source),
+            },
+            Error::Status(status, message) => HttpClientError {
+                code: Some(status.as_u16() as i64),
+                message,
+                source: None,
+            },
+            Error::ResponseTooLarge(_) | Error::Timeout(_) => HttpClientError {
+                code: None,
+                message: err.to_string(),
+                source: None,
+            },
+            Error::Unspecified(message) => HttpClientError {
+                code: None,
+                message,
+                source: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn into_http_client_error() {
+        let err = Error::Status(StatusCode::NOT_FOUND, "No such container: web".to_string());
+        assert_eq!(
+            err.to_string(),
+            "received not OK status code: 404 Not Found: No such container: web"
+        );
+        let err: HttpClientError = err.into();
+        assert_eq!(err.code, Some(404));
+        assert_eq!(err.message, "No such container: web");
+        assert_eq!(err.to_string(), "No such container: web (code: 404)");
+        assert!(err.source().is_none());
+
+        let err: HttpClientError = Error::ResponseTooLarge(1024).into();
+        assert_eq!(err.code, None);
+        assert_eq!(err.message, "response body is larger than the maximum of 1024 bytes");
+
+        let err: HttpClientError = Error::Timeout(Duration::from_secs(5)).into();
+        assert_eq!(err.code, None);
+        assert_eq!(err.message, "request timed out after 5s");
+
+        let err: HttpClientError = Error::Unspecified("parsing response: EOF".to_string()).into();
+        assert_eq!(err.code, None);
+        assert_eq!(err.message, "parsing response: EOF");
+
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = Error::Connecting(io_err.into());
+        assert_eq!(err.source().unwrap().to_string(), "no such file");
+        let err: HttpClientError = err.into();
+        assert_eq!(err.code, None);
+        assert_eq!(err.message, "connecting to docker socket");
+        assert_eq!(err.source().unwrap().to_string(), "no such file");
+    }
+}
@@ -374,3 +374,261 @@ pub struct EndpointIpamConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub link_local_i_ps: Option<Vec<String>>,
 }
+
+/// The processes running inside a container, as returned by `/containers/{id}/top`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerTop {
+    /// The column names of `ps` (e.g. `PID` or `CMD`)
+    #[serde(rename = "Titles")]
+    pub titles: Vec<String>,
+
+    /// The processes, with one value for each of the `titles`
+    #[serde(rename = "Processes")]
+    pub processes: Vec<Vec<String>>,
+}
+
+/// The condition to wait for with [`Client::wait_container`](crate::Client::wait_container).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WaitCondition {
+    /// Wait until the container is not running. Returns immediately if it's already stopped
+    #[default]
+    #[serde(rename = "not-running")]
+    NotRunning,
+    /// Wait until the container exits the next time, even if it's not running yet
+    #[serde(rename = "next-exit")]
+    NextExit,
+    /// Wait until the container is removed
+    #[serde(rename = "removed")]
+    Removed,
+}
+
+/// The result of waiting for a container, as returned by `/containers/{id}/wait`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WaitResponse {
+    /// The exit code of the container
+    #[serde(rename = "StatusCode")]
+    pub status_code: i64,
+
+    /// Set if the daemon failed to wait for the container
+    #[serde(rename = "Error")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<WaitExitError>,
+}
+
+/// An error that happened while waiting for a container.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WaitExitError {
+    /// Details of the error
+    #[serde(rename = "Message")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// The resource limits of a container, updated with
+/// [`Client::update_container`](crate::Client::update_container).
+///
+/// Only the fields that are set are updated.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    /// CPU shares, the relative weight of the container against the other containers
+    #[serde(rename = "CpuShares")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_shares: Option<i64>,
+
+    /// CPU quota in units of 10<sup>-9</sup> CPUs, e.g. `1_500_000_000` for 1.5 CPUs
+    #[serde(rename = "NanoCpus")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nano_cpus: Option<i64>,
+
+    /// The length of a CPU period in microseconds, for `cpu_quota`
+    #[serde(rename = "CpuPeriod")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_period: Option<i64>,
+
+    /// Microseconds of CPU time that the container can get in a CPU period
+    #[serde(rename = "CpuQuota")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_quota: Option<i64>,
+
+    /// The CPUs on which the container can run (e.g. `0-3` or `0,1`)
+    #[serde(rename = "CpusetCpus")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpuset_cpus: Option<String>,
+
+    /// The memory nodes (NUMA) on which the container can run (e.g. `0-3` or `0,1`)
+    #[serde(rename = "CpusetMems")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpuset_mems: Option<String>,
+
+    /// Memory limit in bytes
+    #[serde(rename = "Memory")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<i64>,
+
+    /// Memory soft limit in bytes
+    #[serde(rename = "MemoryReservation")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_reservation: Option<i64>,
+
+    /// Total memory limit (memory + swap) in bytes, `-1` for unlimited swap
+    #[serde(rename = "MemorySwap")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_swap: Option<i64>,
+
+    /// Maximum number of processes, `0` or `-1` for unlimited
+    #[serde(rename = "PidsLimit")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pids_limit: Option<i64>,
+}
+
+/// The result of updating a container, as returned by `/containers/{id}/update`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateContainerResponse {
+    /// Warnings encountered when updating the container
+    #[serde(rename = "Warnings")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<String>>,
+}
+
+/// Resource usage statistics of a container, as returned by `/containers/{id}/stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerStats {
+    /// The ID of the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// The name of the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// When the statistics were collected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read: Option<String>,
+
+    /// When the previous statistics (`precpu_stats`) were collected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preread: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_stats: Option<ContainerCpuStats>,
+
+    /// The CPU statistics of the previous read, used to compute the CPU usage between two reads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub precpu_stats: Option<ContainerCpuStats>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_stats: Option<ContainerMemoryStats>,
+
+    /// Network statistics, by interface name (e.g. `eth0`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub networks: Option<HashMap<String, ContainerNetworkStats>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blkio_stats: Option<ContainerBlkioStats>,
+}
+
+impl ContainerStats {
+    /// Returns the CPU usage of the container between the previous read and this one, in
+    /// percent of one CPU (like `docker stats`, the value can exceed 100 on multi-core hosts).
+    ///
+    /// Returns `None` if the CPU statistics are missing, and `0.0` if the deltas are not
+    /// positive, for example on the first read of a stream.
+    pub fn cpu_percent(&self) -> Option<f64> {
+        let cpu = self.cpu_stats.as_ref()?;
+        let precpu = self.precpu_stats.as_ref()?;
+
+        let cpu_delta = cpu.cpu_usage.total_usage as f64 - precpu.cpu_usage.total_usage as f64;
+        let system_delta = cpu.system_cpu_usage? as f64 - precpu.system_cpu_usage.unwrap_or(0) as f64;
+        let online_cpus = match cpu.online_cpus {
+            Some(online_cpus) if online_cpus > 0 => online_cpus as f64,
+            _ => cpu
+                .cpu_usage
+                .percpu_usage
+                .as_ref()
+                .map_or(1, |percpu| percpu.len())
+                .max(1) as f64,
+        };
+
+        if cpu_delta <= 0.0 || system_delta <= 0.0 {
+            return Some(0.0);
+        }
+        return Some(cpu_delta / system_delta * online_cpus * 100.0);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerCpuStats {
+    #[serde(default)]
+    pub cpu_usage: ContainerCpuUsage,
+
+    /// The cumulated CPU time of the host, in nanoseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system_cpu_usage: Option<u64>,
+
+    /// The number of CPUs available to the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub online_cpus: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerCpuUsage {
+    /// The cumulated CPU time consumed by the container, in nanoseconds
+    #[serde(default)]
+    pub total_usage: u64,
+
+    /// The cumulated CPU time consumed per core, in nanoseconds. Not available with cgroups v2
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percpu_usage: Option<Vec<u64>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_in_kernelmode: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage_in_usermode: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerMemoryStats {
+    /// The current memory usage, in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<u64>,
+
+    /// The memory limit of the container, in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+
+    /// Detailed memory statistics from the cgroup (e.g. `cache` or `inactive_file`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<HashMap<String, u64>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerNetworkStats {
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerBlkioStats {
+    /// Bytes read and written, by device and operation (e.g. `read` or `write`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_service_bytes_recursive: Option<Vec<ContainerBlkioStatEntry>>,
+
+    /// Number of I/O operations, by device and operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub io_serviced_recursive: Option<Vec<ContainerBlkioStatEntry>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContainerBlkioStatEntry {
+    pub major: u64,
+    pub minor: u64,
+    pub op: String,
+    pub value: u64,
+}
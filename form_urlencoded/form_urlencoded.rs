@@ -38,21 +38,53 @@ use percent_encoding::{percent_decode, percent_encode_byte};
 ///
 /// The names and values are percent-decoded. For instance, `%23first=%25try%25` will be
 /// converted to `[("#first", "%try%")]`.
+///
+/// Percent-decoded bytes that are not valid UTF-8 are replaced with U+FFFD, use [`parse_bytes`]
+/// to get them unchanged.
 #[inline]
 pub fn parse(input: &[u8]) -> Parse<'_> {
     Parse {
-        input,
+        inner: parse_bytes(input),
     }
 }
 /// The return type of `parse()`.
 #[derive(Copy, Clone)]
 pub struct Parse<'a> {
-    input: &'a [u8],
+    inner: ParseBytes<'a>,
 }
 
 impl<'a> Iterator for Parse<'a> {
     type Item = (Cow<'a, str>, Cow<'a, str>);
 
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(name, value)| (decode_utf8_lossy(name), decode_utf8_lossy(value)))
+    }
+}
+
+/// Convert a byte string in the `application/x-www-form-urlencoded` syntax
+/// into a iterator of (name, value) pairs of bytes.
+///
+/// Like `parse()`, but the percent-decoded names and values are not decoded as UTF-8, e.g. for
+/// forms submitted in legacy encodings. For instance, `name=%FF` will be converted to
+/// `[(b"name", b"\xFF")]`.
+#[inline]
+pub fn parse_bytes(input: &[u8]) -> ParseBytes<'_> {
+    ParseBytes {
+        input,
+    }
+}
+
+/// The return type of `parse_bytes()`.
+#[derive(Copy, Clone)]
+pub struct ParseBytes<'a> {
+    input: &'a [u8],
+}
+
+impl<'a> Iterator for ParseBytes<'a> {
+    type Item = (Cow<'a, [u8]>, Cow<'a, [u8]>);
+
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             if self.input.is_empty() {
@@ -72,12 +104,12 @@ impl<'a> Iterator for Parse<'a> {
     }
 }
 
-fn decode(input: &[u8]) -> Cow<'_, str> {
+fn decode(input: &[u8]) -> Cow<'_, [u8]> {
     let replaced = replace_plus(input);
-    decode_utf8_lossy(match percent_decode(&replaced).into() {
+    match percent_decode(&replaced).into() {
         Cow::Owned(vec) => Cow::Owned(vec),
         Cow::Borrowed(_) => replaced,
-    })
+    }
 }
 
 /// Replace b'+' with b' '
@@ -104,6 +136,12 @@ impl<'a> Parse<'a> {
             inner: self,
         }
     }
+
+    /// Return a new iterator that yields the remaining pairs as bytes, without decoding them as
+    /// UTF-8, like `parse_bytes()`.
+    pub fn into_bytes(self) -> ParseBytes<'a> {
+        self.inner
+    }
 }
 
 /// Like `Parse`, but yields pairs of `String` instead of pairs of `Cow<str>`.
@@ -1,40 +1,186 @@
 use std::cmp::max;
 
+/// Escapes `src` so it can be inserted in HTML text or in an attribute value, quoted or not.
+///
+/// NUL characters are replaced with `&#65533;` (U+FFFD REPLACEMENT CHARACTER), as browsers
+/// would do anyway, so escaping is intentionally lossy for them: [`unescape`] returns U+FFFD, not
+/// NUL. The other control characters are passed through verbatim, apart from the whitespace ones.
 pub fn escape(src: &str) -> String {
-    let mut ret_val = String::with_capacity(max(4, src.len()));
+    // Escaping a character can take up to 8 bytes, so we compute the exact length of the output
+    // to allocate only once, even for heavily-escaped inputs. All the escaped characters are
+    // ASCII, so we can count them by iterating over the bytes, which is much faster than escaping.
+    let escaped_len = src
+        .bytes()
+        .map(|b| replacement(b as char).map_or(1, str::len))
+        .sum::<usize>();
+
+    let mut ret_val = String::with_capacity(max(4, escaped_len));
     for c in src.chars() {
-        let replacement = match c {
-            // this character, when confronted, will start a tag
-            '<' => "&lt;",
-            // in an unquoted attribute, will end the attribute value
-            '>' => "&gt;",
-            // in an attribute surrounded by double quotes, this character will end the attribute value
-            '\"' => "&quot;",
-            // in an attribute surrounded by single quotes, this character will end the attribute value
-            '\'' => "&apos;",
-            // in HTML5, returns a bogus parse error in an unquoted attribute, while in SGML/HTML, it will end an attribute value surrounded by backquotes
-            '`' => "&grave;",
-            // in an unquoted attribute, this character will end the attribute
-            '/' => "&#47;",
-            // starts an entity reference
-            '&' => "&amp;",
-            // if at the beginning of an unquoted attribute, will get ignored
-            '=' => "&#61;",
-            // will end an unquoted attribute
-            ' ' => "&#32;",
-            '\t' => "&#9;",
-            '\n' => "&#10;",
-            '\x0c' => "&#12;",
-            '\r' => "&#13;",
-            // a spec-compliant browser will perform this replacement anyway, but the middleware might not
-            '\0' => "&#65533;",
-            // ALL OTHER CHARACTERS ARE PASSED THROUGH VERBATIM
-            _ => {
+        match replacement(c) {
+            Some(replacement) => ret_val.push_str(replacement),
+            None => ret_val.push(c),
+        }
+    }
+    ret_val
+}
+
+/// Decodes the character references of `src`, such as the ones produced by [`escape`]: the named
+/// references `&lt;`, `&gt;`, `&quot;`, `&apos;`, `&grave;` and `&amp;`, and the decimal and
+/// hexadecimal numeric references, e.g. `&#61;` or `&#x3D;`.
+///
+/// Like browsers, the numeric references to NUL, to surrogates or outside of the Unicode range
+/// are decoded as U+FFFD REPLACEMENT CHARACTER. Other ampersands are kept verbatim.
+pub fn unescape(src: &str) -> String {
+    let mut ret_val = String::with_capacity(src.len());
+    let mut rest = src;
+    while let Some(start) = rest.find('&') {
+        ret_val.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match decode_reference(rest) {
+            Some((c, len)) => {
                 ret_val.push(c);
-                continue;
+                rest = &rest[len..];
+            }
+            None => {
+                ret_val.push('&');
+                rest = &rest[1..];
             }
-        };
-        ret_val.push_str(replacement);
+        }
     }
+    ret_val.push_str(rest);
     ret_val
 }
+
+/// Decodes the character reference at the start of `src`, and returns the character and the
+/// length of the reference, or `None` if `src` doesn't start with a supported reference.
+fn decode_reference(src: &str) -> Option<(char, usize)> {
+    // the longest supported references are numeric ones, such as `&#x10FFFF;` or `&#1114111;`
+    let end = src.bytes().take(12).position(|b| b == b';')?;
+    let c = match &src[1..end] {
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "grave" => '`',
+        "amp" => '&',
+        reference => {
+            let number = reference.strip_prefix('#')?;
+            let (digits, radix) = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => (hex, 16),
+                None => (number, 10),
+            };
+            if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+                return None;
+            }
+            match u32::from_str_radix(digits, radix) {
+                Ok(0) | Err(_) => char::REPLACEMENT_CHARACTER,
+                Ok(code) => char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER),
+            }
+        }
+    };
+    Some((c, end + 1))
+}
+
+/// Returns the replacement of a character, or `None` if it doesn't need to be escaped.
+fn replacement(c: char) -> Option<&'static str> {
+    let replacement = match c {
+        // this character, when confronted, will start a tag
+        '<' => "&lt;",
+        // in an unquoted attribute, will end the attribute value
+        '>' => "&gt;",
+        // in an attribute surrounded by double quotes, this character will end the attribute value
+        '\"' => "&quot;",
+        // in an attribute surrounded by single quotes, this character will end the attribute value
+        '\'' => "&apos;",
+        // in HTML5, returns a bogus parse error in an unquoted attribute, while in SGML/HTML, it will end an attribute value surrounded by backquotes
+        '`' => "&grave;",
+        // in an unquoted attribute, this character will end the attribute
+        '/' => "&#47;",
+        // starts an entity reference
+        '&' => "&amp;",
+        // if at the beginning of an unquoted attribute, will get ignored
+        '=' => "&#61;",
+        // will end an unquoted attribute
+        ' ' => "&#32;",
+        '\t' => "&#9;",
+        '\n' => "&#10;",
+        '\x0c' => "&#12;",
+        '\r' => "&#13;",
+        // a spec-compliant browser will perform this replacement anyway, but the middleware might not
+        '\0' => "&#65533;",
+        // ALL OTHER CHARACTERS ARE PASSED THROUGH VERBATIM
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_characters() {
+        assert_eq!(escape(""), "");
+        assert_eq!(escape("hello"), "hello");
+        assert_eq!(escape("héllo wörld"), "héllo&#32;wörld");
+        assert_eq!(
+            escape(r#"<a href="/x?a=1&b='2'">`</a>"#),
+            "&lt;a&#32;href&#61;&quot;&#47;x?a&#61;1&amp;b&#61;&apos;2&apos;&quot;&gt;&grave;&lt;&#47;a&gt;"
+        );
+        assert_eq!(escape("\t\n\x0c\r\0"), "&#9;&#10;&#12;&#13;&#65533;");
+    }
+
+    #[test]
+    fn escape_control_characters() {
+        // NUL is replaced like browsers do, so it can't be round-tripped
+        assert_eq!(escape("a\0b"), "a&#65533;b");
+        assert_eq!(unescape(&escape("a\0b")), "a\u{FFFD}b");
+        assert_eq!(unescape(&escape("\u{FFFD}")), "\u{FFFD}");
+
+        // the whitespace control characters are escaped and round-trip
+        assert_eq!(unescape(&escape("\t\n\x0c\r")), "\t\n\x0c\r");
+
+        // the other C0 control characters, and DEL, are passed through verbatim
+        let controls: String = ('\x01'..='\x1f')
+            .filter(|c| !matches!(c, '\t' | '\n' | '\x0c' | '\r'))
+            .chain(['\x7f'])
+            .collect();
+        assert_eq!(escape(&controls), controls);
+        assert_eq!(unescape(&escape(&controls)), controls);
+    }
+
+    #[test]
+    fn unescape_references() {
+        assert_eq!(unescape(""), "");
+        assert_eq!(unescape("hello"), "hello");
+        let input = r#"<a href="/x?a=1&b='2'">`</a> héllo wörld"#;
+        assert_eq!(unescape(&escape(input)), input);
+
+        assert_eq!(unescape("&#60;&#x3C;&#X3c;&lt;"), "<<<<");
+        assert_eq!(unescape("&#128512;&#x1F600;"), "😀😀");
+        // like browsers, invalid code points are replaced
+        assert_eq!(unescape("&#0;&#x0;&#xD800;&#x110000;&#999999999;"), "\u{FFFD}".repeat(5));
+        // unsupported references are kept verbatim
+        assert_eq!(
+            unescape("a & b &nbsp; &#; &#x; &#1a; &#-1; &#+60; &lt"),
+            "a & b &nbsp; &#; &#x; &#1a; &#-1; &#+60; &lt"
+        );
+        assert_eq!(unescape("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn escape_worst_case() {
+        let input = "<".repeat(10_000);
+        let escaped = escape(&input);
+
+        assert_eq!(escaped, "&lt;".repeat(10_000));
+        // the output is allocated once with the exact capacity: growing the string from
+        // `input.len()` would have reallocated it, doubling its capacity each time
+        assert_eq!(escaped.capacity(), escaped.len());
+
+        let input = "\0".repeat(1_000);
+        let escaped = escape(&input);
+        assert_eq!(escaped, "&#65533;".repeat(1_000));
+        assert_eq!(escaped.capacity(), escaped.len());
+    }
+}
@@ -0,0 +1,58 @@
+//! A common error type for HTTP API clients
+use std::{error::Error, fmt};
+
+/// An error returned by an HTTP API client, so that applications using several clients (e.g.
+/// `docker` and `postmark`) can handle their errors uniformly with `?`.
+///
+/// Clients provide `From` conversions from their own error types.
+#[derive(Debug)]
+pub struct HttpClientError {
+    /// The HTTP status code or the API-specific error code returned by the server. `None` if the
+    /// error happened before a response was received, e.g. while connecting or encoding the
+    /// request.
+    pub code: Option<i64>,
+    /// The message of the client, or the error message of the response. It doesn't include the
+    /// message of `source`, so that it isn't repeated when displaying the chain of errors.
+    pub message: String,
+    /// The underlying error, if any. Returned by [`Error::source`].
+    pub source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl fmt::Display for HttpClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "{} (code: {code})", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl Error for HttpClientError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_and_source() {
+        let err = HttpClientError {
+            code: Some(404),
+            message: "not found".to_string(),
+            source: None,
+        };
+        assert_eq!(err.to_string(), "not found (code: 404)");
+        assert!(err.source().is_none());
+
+        let err = HttpClientError {
+            code: None,
+            message: "connecting".to_string(),
+            source: Some(Box::new(std::io::Error::other("connection refused"))),
+        };
+        assert_eq!(err.to_string(), "connecting");
+        assert_eq!(err.source().unwrap().to_string(), "connection refused");
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use crate::http_body_util::{Frame, combinators};
 
 /// An extension trait for [`http_body::Body`] adding various combinators and adapters
@@ -23,6 +25,31 @@ pub trait BodyExt: hyper::body::Body {
             collected: Some(super::Collected::default()),
         }
     }
+
+    /// Turn this body into a [`TimeoutBody`] which returns [`TimeoutError::Timeout`] if a frame
+    /// doesn't arrive within `timeout`. The timer is reset after each frame, so slow but steady
+    /// bodies are not interrupted.
+    ///
+    /// [`TimeoutBody`]: combinators::TimeoutBody
+    /// [`TimeoutError::Timeout`]: combinators::TimeoutError::Timeout
+    fn with_timeout(self, timeout: Duration) -> combinators::TimeoutBody<Self>
+    where
+        Self: Sized,
+    {
+        combinators::TimeoutBody::new(self, timeout)
+    }
+
+    /// Turn this body into a [`LimitedBody`] which returns [`LimitError::LengthLimitExceeded`] once
+    /// more than `limit` bytes of data have been received.
+    ///
+    /// [`LimitedBody`]: combinators::LimitedBody
+    /// [`LimitError::LengthLimitExceeded`]: combinators::LimitError::LengthLimitExceeded
+    fn limit(self, limit: usize) -> combinators::LimitedBody<Self>
+    where
+        Self: Sized,
+    {
+        combinators::LimitedBody::new(self, limit)
+    }
 }
 
 impl<T: ?Sized> BodyExt for T where T: hyper::body::Body {}
@@ -40,6 +40,12 @@ impl<B: Buf> Collected<B> {
         self.bufs.copy_to_bytes(self.bufs.remaining())
     }
 
+    /// Convert this body into its data, as a [`Bytes`], and its trailers, if any.
+    pub fn into_parts(mut self) -> (Bytes, Option<HeaderMap>) {
+        let data = self.bufs.copy_to_bytes(self.bufs.remaining());
+        (data, self.trailers)
+    }
+
     pub(crate) fn push_frame(&mut self, frame: Frame<B>) {
         let frame = match frame.into_data() {
             Ok(data) => {
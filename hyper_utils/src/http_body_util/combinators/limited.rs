@@ -0,0 +1,148 @@
+use std::{
+    error::Error,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Buf;
+use hyper::body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// A body that returns an error once more than a given number of bytes of data have been
+    /// received, e.g. to protect clients against huge responses that would exhaust their memory.
+    ///
+    /// Created by [`BodyExt::limit`].
+    ///
+    /// [`BodyExt::limit`]: crate::http_body_util::BodyExt::limit
+    #[derive(Debug)]
+    pub struct LimitedBody<B> {
+        #[pin]
+        body: B,
+        limit: usize,
+        remaining: usize,
+    }
+}
+
+impl<B> LimitedBody<B> {
+    /// Create a new `LimitedBody`, accepting at most `limit` bytes of data from `body`.
+    pub fn new(body: B, limit: usize) -> Self {
+        Self {
+            body,
+            limit,
+            remaining: limit,
+        }
+    }
+}
+
+impl<B> Body for LimitedBody<B>
+where
+    B: Body,
+{
+    type Data = B::Data;
+    type Error = LimitError<B::Error>;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+
+        let frame = match this.body.poll_frame(cx) {
+            Poll::Ready(Some(Ok(frame))) => frame,
+            Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(LimitError::Body(err)))),
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        if let Some(data) = frame.data_ref() {
+            if data.remaining() > *this.remaining {
+                *this.remaining = 0;
+                return Poll::Ready(Some(Err(LimitError::LengthLimitExceeded(*this.limit))));
+            }
+            *this.remaining -= data.remaining();
+        }
+        Poll::Ready(Some(Ok(frame)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        // the body fails instead of returning more than `remaining` bytes
+        let remaining = self.remaining as u64;
+        let mut hint = self.body.size_hint();
+        if hint.lower() >= remaining {
+            hint.set_exact(remaining);
+        } else if hint.upper().is_none_or(|upper| upper > remaining) {
+            hint.set_upper(remaining);
+        }
+        hint
+    }
+}
+
+/// The error returned by a [`LimitedBody`].
+#[derive(Debug)]
+pub enum LimitError<E> {
+    /// The body has more data than the limit.
+    LengthLimitExceeded(usize),
+    /// The underlying body returned an error.
+    Body(E),
+}
+
+impl<E: fmt::Display> fmt::Display for LimitError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::LengthLimitExceeded(limit) => write!(f, "body is larger than {limit} bytes"),
+            LimitError::Body(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for LimitError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LimitError::LengthLimitExceeded(_) => None,
+            LimitError::Body(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use futures_util::stream;
+
+    use super::*;
+    use crate::http_body_util::{BodyExt, Full, StreamBody};
+
+    #[tokio::test]
+    async fn body_within_limit_succeeds() {
+        let body = Full::new(&b"hello"[..]).limit(5);
+        assert_eq!(body.size_hint().exact(), Some(5));
+        assert_eq!(body.collect().await.unwrap().to_bytes(), &b"hello"[..]);
+
+        let frames = stream::iter([&b"ab"[..], &b"cd"[..]].map(|data| Ok::<_, Infallible>(Frame::data(data))));
+        let body = StreamBody::new(frames).limit(10);
+        assert_eq!(body.size_hint().upper(), Some(10));
+        assert_eq!(body.collect().await.unwrap().to_bytes(), &b"abcd"[..]);
+    }
+
+    #[tokio::test]
+    async fn body_over_limit_fails() {
+        let body = Full::new(&b"hello"[..]).limit(4);
+        assert_eq!(body.size_hint().exact(), Some(4));
+        let err = body.collect().await.unwrap_err();
+        assert!(matches!(err, LimitError::LengthLimitExceeded(4)));
+        assert_eq!(err.to_string(), "body is larger than 4 bytes");
+        assert!(err.source().is_none());
+
+        // the limit applies to the whole body, not to each frame
+        let frames =
+            stream::iter([&b"ab"[..], &b"cd"[..], &b"ef"[..]].map(|data| Ok::<_, Infallible>(Frame::data(data))));
+        let mut body = Box::pin(StreamBody::new(frames).limit(5));
+        assert_eq!(body.frame().await.unwrap().unwrap().into_data().unwrap(), &b"ab"[..]);
+        assert_eq!(body.frame().await.unwrap().unwrap().into_data().unwrap(), &b"cd"[..]);
+        assert!(matches!(body.frame().await.unwrap(), Err(LimitError::LengthLimitExceeded(5))));
+    }
+}
@@ -1,4 +1,8 @@
 pub mod collect;
 pub mod frame;
+pub mod limited;
+pub mod timeout;
 
 pub use collect::Collect;
+pub use limited::{LimitError, LimitedBody};
+pub use timeout::{TimeoutBody, TimeoutError};
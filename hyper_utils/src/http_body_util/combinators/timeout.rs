@@ -0,0 +1,145 @@
+use std::{
+    error::Error,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use hyper::body::{Body, Frame, SizeHint};
+use pin_project_lite::pin_project;
+use tokio::time::Sleep;
+
+pin_project! {
+    /// A body that returns an error if the next frame doesn't arrive within a timeout, e.g. to
+    /// protect clients against servers that stall in the middle of a response.
+    ///
+    /// Created by [`BodyExt::with_timeout`].
+    ///
+    /// [`BodyExt::with_timeout`]: crate::http_body_util::BodyExt::with_timeout
+    #[derive(Debug)]
+    pub struct TimeoutBody<B> {
+        #[pin]
+        body: B,
+        timeout: Duration,
+        // started when waiting for a frame, and reset when a frame is received
+        #[pin]
+        sleep: Option<Sleep>,
+    }
+}
+
+impl<B> TimeoutBody<B> {
+    /// Create a new `TimeoutBody`, waiting at most `timeout` for each frame of `body`.
+    pub fn new(body: B, timeout: Duration) -> Self {
+        Self {
+            body,
+            timeout,
+            sleep: None,
+        }
+    }
+}
+
+impl<B> Body for TimeoutBody<B>
+where
+    B: Body,
+{
+    type Data = B::Data;
+    type Error = TimeoutError<B::Error>;
+
+    fn poll_frame(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let mut this = self.project();
+
+        if this.sleep.is_none() {
+            this.sleep.set(Some(tokio::time::sleep(*this.timeout)));
+        }
+
+        if let Poll::Ready(frame) = this.body.poll_frame(cx) {
+            this.sleep.set(None);
+            return Poll::Ready(frame.map(|frame| frame.map_err(TimeoutError::Body)));
+        }
+
+        if let Some(sleep) = this.sleep.as_mut().as_pin_mut()
+            && sleep.poll(cx).is_ready()
+        {
+            this.sleep.set(None);
+            return Poll::Ready(Some(Err(TimeoutError::Timeout(*this.timeout))));
+        }
+        Poll::Pending
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+/// The error returned by a [`TimeoutBody`].
+#[derive(Debug)]
+pub enum TimeoutError<E> {
+    /// No frame was received within the timeout.
+    Timeout(Duration),
+    /// The underlying body returned an error.
+    Body(E),
+}
+
+impl<E: fmt::Display> fmt::Display for TimeoutError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeoutError::Timeout(timeout) => write!(f, "no body frame received within {timeout:?}"),
+            TimeoutError::Body(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for TimeoutError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TimeoutError::Timeout(_) => None,
+            TimeoutError::Body(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use bytes::Bytes;
+    use futures_util::{StreamExt, stream};
+
+    use super::*;
+    use crate::http_body_util::{BodyExt, Full, StreamBody};
+
+    #[tokio::test]
+    async fn prompt_body_succeeds() {
+        let body = Full::new(&b"hello"[..]).with_timeout(Duration::from_secs(1));
+        assert_eq!(body.size_hint().exact(), Some(5));
+        assert_eq!(body.collect().await.unwrap().to_bytes(), &b"hello"[..]);
+
+        // the timeout applies to each frame, not to the whole body
+        let frames = stream::iter([&b"a"[..], &b"b"[..], &b"c"[..]]).then(|data| async move {
+            tokio::time::sleep(Duration::from_millis(40)).await;
+            Ok::<_, Infallible>(Frame::data(data))
+        });
+        let body = StreamBody::new(frames).with_timeout(Duration::from_millis(100));
+        assert_eq!(body.collect().await.unwrap().to_bytes(), &b"abc"[..]);
+    }
+
+    #[tokio::test]
+    async fn stalled_body_times_out() {
+        let timeout = Duration::from_millis(20);
+        let first = stream::once(async { Ok::<_, Infallible>(Frame::data(Bytes::from("hello"))) });
+        let body = StreamBody::new(first.chain(stream::pending()));
+        let mut body = Box::pin(body.with_timeout(timeout));
+
+        assert_eq!(body.frame().await.unwrap().unwrap().into_data().unwrap(), "hello");
+        let err = body.frame().await.unwrap().unwrap_err();
+        assert!(matches!(err, TimeoutError::Timeout(t) if t == timeout));
+        assert_eq!(err.to_string(), "no body frame received within 20ms");
+        assert!(err.source().is_none());
+    }
+}
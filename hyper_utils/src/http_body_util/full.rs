@@ -6,7 +6,10 @@ use std::{
 };
 
 use bytes::{Buf, Bytes};
-use hyper::body::{Body, Frame, SizeHint};
+use hyper::{
+    body::{Body, Frame, SizeHint},
+    http::HeaderMap,
+};
 use pin_project_lite::pin_project;
 
 pin_project! {
@@ -65,6 +68,63 @@ where
     }
 }
 
+pin_project! {
+    /// A body that consists of a single chunk followed by a trailers frame, e.g. for gRPC
+    /// responses, which send their status in trailers.
+    #[derive(Clone, Debug)]
+    pub struct FullWithTrailers<D> {
+        data: Option<D>,
+        trailers: Option<HeaderMap>,
+    }
+}
+
+impl<D> FullWithTrailers<D>
+where
+    D: Buf,
+{
+    /// Create a new `FullWithTrailers`. Like for [`Full`], no DATA frame is sent if `data` is
+    /// empty.
+    pub fn new(data: D, trailers: HeaderMap) -> Self {
+        let data = if data.has_remaining() { Some(data) } else { None };
+        FullWithTrailers {
+            data,
+            trailers: Some(trailers),
+        }
+    }
+}
+
+impl<D> Body for FullWithTrailers<D>
+where
+    D: Buf,
+{
+    type Data = D;
+    type Error = Infallible;
+
+    fn poll_frame(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Result<Frame<D>, Self::Error>>> {
+        let frame = if let Some(data) = self.data.take() {
+            Frame::data(data)
+        } else if let Some(trailers) = self.trailers.take() {
+            Frame::trailers(trailers)
+        } else {
+            return Poll::Ready(None);
+        };
+
+        Poll::Ready(Some(Ok(frame)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.data.is_none() && self.trailers.is_none()
+    }
+
+    /// Trailers are not part of the body length, so the hint is the length of the data only.
+    fn size_hint(&self) -> SizeHint {
+        self.data
+            .as_ref()
+            .map(|data| SizeHint::with_exact(u64::try_from(data.remaining()).unwrap()))
+            .unwrap_or_else(|| SizeHint::with_exact(0))
+    }
+}
+
 impl<D> From<Bytes> for Full<D>
 where
     D: Buf + From<Bytes>,
@@ -141,4 +201,35 @@ mod tests {
         assert!(Full::<&[u8]>::default().frame().await.is_none());
         assert!(Full::new(&b""[..]).frame().await.is_none());
     }
+
+    #[tokio::test]
+    async fn full_with_trailers() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert("grpc-status", "0".try_into().unwrap());
+
+        let mut body = FullWithTrailers::new(&b"hello"[..], trailers.clone());
+        assert!(!body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(b"hello".len() as u64));
+
+        let (data, collected_trailers) = body.clone().collect().await.unwrap().into_parts();
+        assert_eq!(data, &b"hello"[..]);
+        assert_eq!(collected_trailers, Some(trailers.clone()));
+
+        assert_eq!(body.frame().await.unwrap().unwrap().into_data().unwrap(), &b"hello"[..]);
+        // the trailers are still to be sent
+        assert!(!body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(0));
+        assert_eq!(body.frame().await.unwrap().unwrap().into_trailers().unwrap(), trailers);
+        assert!(body.is_end_stream());
+        assert!(body.frame().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn empty_full_with_trailers_only_sends_trailers() {
+        let mut body = FullWithTrailers::new(&b""[..], HeaderMap::new());
+        assert!(!body.is_end_stream());
+        assert_eq!(body.size_hint().exact(), Some(0));
+        assert!(body.frame().await.unwrap().unwrap().is_trailers());
+        assert!(body.frame().await.is_none());
+    }
 }
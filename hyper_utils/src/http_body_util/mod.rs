@@ -7,6 +7,6 @@ mod stream;
 
 pub use body_ext::BodyExt;
 pub use collected::Collected;
-pub use combinators::frame::Frame;
-pub use full::Full;
+pub use combinators::{LimitError, LimitedBody, TimeoutBody, TimeoutError, frame::Frame};
+pub use full::{Full, FullWithTrailers};
 pub use stream::{BodyDataStream, BodyStream, StreamBody};
@@ -1,2 +1,7 @@
+pub mod error;
+#[cfg(feature = "hyper")]
 pub mod http_body_util;
+#[cfg(feature = "hyper")]
 pub mod rt;
+
+pub use error::HttpClientError;
@@ -0,0 +1,602 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+//! Decompression of DEFLATE (RFC 1951) and gzip (RFC 1952) data.
+//!
+//! The whole input is decompressed at once, and the size of the output is limited, so that small
+//! inputs that decompress to huge outputs (such as gzip bombs) are rejected early.
+//!
+//! # Feature flags
+//!
+//! | Flag    | Description                                             |
+//! |---------|---------------------------------------------------------|
+//! | `std`   | [`std::error::Error`] trait impls (enabled by default)  |
+//!
+//! # Examples
+//!
+//! ```rust
+//! let gzipped = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\
+//!     \xcb\x48\xcd\xc9\xc9\x07\x00\x86\xa6\x10\x36\x05\x00\x00\x00";
+//! let decompressed = inflate::gunzip(gzipped, 1024).unwrap();
+//! assert_eq!(decompressed, b"hello");
+//!
+//! assert_eq!(inflate::gunzip(gzipped, 4), Err(inflate::Error::OutputTooLarge));
+//! ```
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Errors that can occur while decompressing data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The input ends before the end of the compressed data.
+    UnexpectedEof,
+    /// The input is not valid DEFLATE data: invalid block type, Huffman codes, lengths or
+    /// distances.
+    InvalidData,
+    /// The input doesn't start with a valid gzip header.
+    InvalidHeader,
+    /// The CRC-32 or the size in the gzip trailer doesn't match the decompressed data.
+    InvalidChecksum,
+    /// The decompressed data is larger than the maximum size.
+    OutputTooLarge,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of compressed data"),
+            Self::InvalidData => f.write_str("invalid deflate data"),
+            Self::InvalidHeader => f.write_str("invalid gzip header"),
+            Self::InvalidChecksum => f.write_str("invalid gzip checksum"),
+            Self::OutputTooLarge => f.write_str("decompressed data is too large"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Decompresses raw DEFLATE data, without zlib or gzip header. Data after the final block is
+/// ignored.
+///
+/// # Errors
+///
+/// Returns [`Error::OutputTooLarge`] if the decompressed data is larger than `max_size` bytes,
+/// [`Error::UnexpectedEof`] if `data` is truncated, and [`Error::InvalidData`] if it is not valid
+/// DEFLATE data.
+///
+/// # Example
+///
+/// ```rust
+/// let decompressed = inflate::inflate(b"\xcb\x48\xcd\xc9\xc9\x07\x00", 1024).unwrap();
+/// assert_eq!(decompressed, b"hello");
+/// ```
+pub fn inflate(data: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    inflate_into(&mut out, 0, data, max_size)?;
+    Ok(out)
+}
+
+/// Decompresses gzip data. Concatenated gzip members are decompressed one after the other, as
+/// one output.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidHeader`] if `data` (or the data after a member) doesn't start with a
+/// gzip header, [`Error::InvalidChecksum`] if the trailer of a member doesn't match its
+/// decompressed data, and the same errors as [`inflate`] otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// let gzipped = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\
+///     \xcb\x48\xcd\xc9\xc9\x07\x00\x86\xa6\x10\x36\x05\x00\x00\x00";
+/// let decompressed = inflate::gunzip(gzipped, 1024).unwrap();
+/// assert_eq!(decompressed, b"hello");
+/// ```
+pub fn gunzip(mut data: &[u8], max_size: usize) -> Result<Vec<u8>, Error> {
+    let mut out = Vec::new();
+    loop {
+        let header_len = gzip_header_len(data)?;
+        let start = out.len();
+        let compressed_len = inflate_into(&mut out, start, &data[header_len..], max_size)?;
+        data = &data[header_len + compressed_len..];
+
+        let trailer = data.get(..8).ok_or(Error::UnexpectedEof)?;
+        let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        // the size is stored modulo 2^32
+        if crc != crc32fast::hash(&out[start..]) || size != (out.len() - start) as u32 {
+            return Err(Error::InvalidChecksum);
+        }
+
+        data = &data[8..];
+        if data.is_empty() {
+            return Ok(out);
+        }
+    }
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const GZIP_DEFLATE: u8 = 8;
+
+const FHCRC: u8 = 1 << 1;
+const FEXTRA: u8 = 1 << 2;
+const FNAME: u8 = 1 << 3;
+const FCOMMENT: u8 = 1 << 4;
+const RESERVED_FLAGS: u8 = 0b1110_0000;
+
+/// Returns the length of the gzip header at the start of `data`.
+fn gzip_header_len(data: &[u8]) -> Result<usize, Error> {
+    let fixed = data.get(..10).ok_or(Error::UnexpectedEof)?;
+    if fixed[..2] != GZIP_MAGIC || fixed[2] != GZIP_DEFLATE || fixed[3] & RESERVED_FLAGS != 0 {
+        return Err(Error::InvalidHeader);
+    }
+    let flags = fixed[3];
+    let mut len = fixed.len();
+
+    if flags & FEXTRA != 0 {
+        let extra_len = data.get(len..len + 2).ok_or(Error::UnexpectedEof)?;
+        len += 2 + u16::from_le_bytes([extra_len[0], extra_len[1]]) as usize;
+    }
+    // the file name and the comment are zero-terminated
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let field = data.get(len..).ok_or(Error::UnexpectedEof)?;
+            len += field.iter().position(|&c| c == 0).ok_or(Error::UnexpectedEof)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        let header_crc = data.get(len..len + 2).ok_or(Error::UnexpectedEof)?;
+        if u16::from_le_bytes([header_crc[0], header_crc[1]]) != crc32fast::hash(&data[..len]) as u16 {
+            return Err(Error::InvalidHeader);
+        }
+        len += 2;
+    }
+
+    if len > data.len() {
+        return Err(Error::UnexpectedEof);
+    }
+    Ok(len)
+}
+
+/// Decompresses the DEFLATE data at the start of `data` to the end of `out`, and returns the
+/// number of bytes of `data` used. Back-references can't go before `out_start`, the position in
+/// `out` where the output of this data starts.
+fn inflate_into(out: &mut Vec<u8>, out_start: usize, data: &[u8], max_size: usize) -> Result<usize, Error> {
+    let mut input = BitReader::new(data);
+    loop {
+        let is_final = input.bits(1)? == 1;
+        match input.bits(2)? {
+            0 => stored_block(out, &mut input, max_size)?,
+            1 => {
+                let (lengths, distances) = fixed_codes();
+                compressed_block(out, out_start, &mut input, &lengths, &distances, max_size)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic_codes(&mut input)?;
+                compressed_block(out, out_start, &mut input, &lengths, &distances, max_size)?;
+            }
+            _ => return Err(Error::InvalidData),
+        }
+        if is_final {
+            return Ok(input.bytes_read());
+        }
+    }
+}
+
+fn stored_block(out: &mut Vec<u8>, input: &mut BitReader, max_size: usize) -> Result<(), Error> {
+    input.align_to_byte();
+    let len = input.bits(16)? as usize;
+    if input.bits(16)? as usize != !len & 0xffff {
+        return Err(Error::InvalidData);
+    }
+    reserve(out, len, max_size)?;
+    input.copy_bytes(out, len)
+}
+
+fn compressed_block(
+    out: &mut Vec<u8>,
+    out_start: usize,
+    input: &mut BitReader,
+    lengths: &Huffman,
+    distances: &Huffman,
+    max_size: usize,
+) -> Result<(), Error> {
+    loop {
+        let symbol = lengths.decode(input)? as usize;
+        if symbol < 256 {
+            reserve(out, 1, max_size)?;
+            out.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let symbol = symbol - 257;
+        if symbol >= LENGTH_BASE.len() {
+            return Err(Error::InvalidData);
+        }
+        let len = LENGTH_BASE[symbol] as usize + input.bits(LENGTH_EXTRA_BITS[symbol])? as usize;
+
+        let symbol = distances.decode(input)? as usize;
+        if symbol >= DISTANCE_BASE.len() {
+            return Err(Error::InvalidData);
+        }
+        let distance = DISTANCE_BASE[symbol] as usize + input.bits(DISTANCE_EXTRA_BITS[symbol])? as usize;
+        if distance > out.len() - out_start {
+            return Err(Error::InvalidData);
+        }
+
+        reserve(out, len, max_size)?;
+        let start = out.len() - distance;
+        if distance >= len {
+            out.extend_from_within(start..start + len);
+        } else {
+            // the copy overlaps the bytes it writes, which repeats the last `distance` bytes
+            for _ in 0..len {
+                out.push(out[out.len() - distance]);
+            }
+        }
+    }
+}
+
+/// Reserves space for `additional` more bytes in `out`, or fails if that would make it larger than
+/// `max_size`.
+fn reserve(out: &mut Vec<u8>, additional: usize, max_size: usize) -> Result<(), Error> {
+    if additional > max_size - out.len() {
+        return Err(Error::OutputTooLarge);
+    }
+    out.reserve(additional);
+    Ok(())
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+/// The order of the code lengths of the code length alphabet in dynamic blocks.
+const CODE_LENGTHS_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const MAX_CODE_LENGTH: usize = 15;
+const MAX_LENGTH_CODES: usize = 286;
+const MAX_DISTANCE_CODES: usize = 30;
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+    let distances = [5; MAX_DISTANCE_CODES];
+    // both codes are valid
+    (Huffman::new(&lengths).unwrap(), Huffman::new(&distances).unwrap())
+}
+
+fn dynamic_codes(input: &mut BitReader) -> Result<(Huffman, Huffman), Error> {
+    let length_codes = input.bits(5)? as usize + 257;
+    let distance_codes = input.bits(5)? as usize + 1;
+    let code_length_codes = input.bits(4)? as usize + 4;
+    if length_codes > MAX_LENGTH_CODES || distance_codes > MAX_DISTANCE_CODES {
+        return Err(Error::InvalidData);
+    }
+
+    let mut code_lengths = [0; 19];
+    for &symbol in &CODE_LENGTHS_ORDER[..code_length_codes] {
+        code_lengths[symbol] = input.bits(3)? as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths)?;
+
+    // the code lengths of both codes are a single sequence, repeats can cross from one to the other
+    let mut lengths = [0; MAX_LENGTH_CODES + MAX_DISTANCE_CODES];
+    let total = length_codes + distance_codes;
+    let mut i = 0;
+    while i < total {
+        let symbol = code_lengths.decode(input)?;
+        let (length, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 if i > 0 => (lengths[i - 1], 3 + input.bits(2)? as usize),
+            17 => (0, 3 + input.bits(3)? as usize),
+            18 => (0, 11 + input.bits(7)? as usize),
+            _ => return Err(Error::InvalidData),
+        };
+        if i + repeat > total {
+            return Err(Error::InvalidData);
+        }
+        lengths[i..i + repeat].fill(length);
+        i += repeat;
+    }
+
+    // a block without end-of-block code can't be decoded
+    if lengths[256] == 0 {
+        return Err(Error::InvalidData);
+    }
+    Ok((
+        Huffman::new(&lengths[..length_codes])?,
+        Huffman::new(&lengths[length_codes..total])?,
+    ))
+}
+
+/// A canonical Huffman code, decoded one bit at a time.
+struct Huffman {
+    /// The number of codes of each length.
+    counts: [u16; MAX_CODE_LENGTH + 1],
+    /// The symbols, ordered by code.
+    symbols: [u16; 288],
+}
+
+impl Huffman {
+    /// Builds the code from the code length of each symbol, 0 for the unused symbols. Fails if
+    /// there are more codes of a length than possible. Incomplete codes are accepted, and
+    /// decoding fails on their missing codes.
+    fn new(lengths: &[u8]) -> Result<Self, Error> {
+        let mut counts = [0u16; MAX_CODE_LENGTH + 1];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(Error::InvalidData);
+            }
+        }
+
+        let mut offsets = [0u16; MAX_CODE_LENGTH + 2];
+        for length in 1..=MAX_CODE_LENGTH {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = [0; 288];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+
+        Ok(Self { counts, symbols })
+    }
+
+    fn decode(&self, input: &mut BitReader) -> Result<u16, Error> {
+        // the first code of each length, and the index of its symbol
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+        for &count in &self.counts[1..] {
+            code |= input.bits(1)? as i32;
+            let count = count as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(Error::InvalidData)
+    }
+}
+
+/// Reads the bits of the input, starting from the least significant bit of each byte.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u32,
+    buffer_len: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buffer: 0,
+            buffer_len: 0,
+        }
+    }
+
+    /// Reads `count` bits, at most 16, as a number.
+    fn bits(&mut self, count: u32) -> Result<u32, Error> {
+        while self.buffer_len < count {
+            let byte = *self.data.get(self.position).ok_or(Error::UnexpectedEof)?;
+            self.buffer |= (byte as u32) << self.buffer_len;
+            self.position += 1;
+            self.buffer_len += 8;
+        }
+        let bits = self.buffer & ((1 << count) - 1);
+        self.buffer >>= count;
+        self.buffer_len -= count;
+        Ok(bits)
+    }
+
+    /// Skips the bits up to the next byte boundary.
+    fn align_to_byte(&mut self) {
+        self.buffer >>= self.buffer_len % 8;
+        self.buffer_len -= self.buffer_len % 8;
+    }
+
+    /// Copies `len` bytes to `out`. The reader must be aligned to a byte boundary.
+    fn copy_bytes(&mut self, out: &mut Vec<u8>, mut len: usize) -> Result<(), Error> {
+        while len > 0 && self.buffer_len > 0 {
+            out.push(self.bits(8)? as u8);
+            len -= 1;
+        }
+        let bytes = self
+            .data
+            .get(self.position..self.position + len)
+            .ok_or(Error::UnexpectedEof)?;
+        out.extend_from_slice(bytes);
+        self.position += len;
+        Ok(())
+    }
+
+    /// Returns the number of bytes read, including the partially read byte.
+    fn bytes_read(&self) -> usize {
+        self.position - (self.buffer_len / 8) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO_GZIP: &[u8] =
+        b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xcb\x48\xcd\xc9\xc9\x07\x00\x86\xa6\x10\x36\x05\x00\x00\x00";
+
+    #[test]
+    fn inflate_stored_block() {
+        assert_eq!(inflate(b"\x01\x05\x00\xfa\xff\x68\x65\x6c\x6c\x6f", 1024).unwrap(), b"hello");
+        // the length is not the complement of its copy
+        assert_eq!(
+            inflate(b"\x01\x05\x00\xfa\xfe\x68\x65\x6c\x6c\x6f", 1024),
+            Err(Error::InvalidData)
+        );
+        assert_eq!(inflate(b"\x01\x05\x00\xfa\xff\x68\x65", 1024), Err(Error::UnexpectedEof));
+    }
+
+    #[test]
+    fn inflate_fixed_block() {
+        // the back-reference overlaps the bytes it copies
+        let compressed = b"\xcb\x48\xcd\xc9\xc9\x57\xc8\x40\x27\x01";
+        assert_eq!(inflate(compressed, 1024).unwrap(), b"hello hello hello hello");
+
+        let compressed = b"\
+            \xcb\xc9\xcc\x4b\x55\x30\xb0\x52\x28\xc9\x48\x55\x28\x2c\xcd\x4c\xce\x56\x48\x2a\xca\x2f\xcf\x53\
+            \x48\xcb\xaf\x50\xc8\x2a\xcd\x2d\x28\x56\xc8\x2f\x4b\x2d\x02\x4b\xe7\x24\x56\x55\x2a\xa4\xe4\xa7\
+            \x73\xe5\x80\xf4\x18\x92\xa1\xc7\x88\x0c\x3d\xc6\x64\xe8\x31\x21\x43\x8f\x29\x19\x7a\xcc\xc8\xd0\
+            \x33\x1a\xd6\xa3\x61\x3d\x1a\xd6\xa3\x61\x3d\x1a\xd6\xa3\x61\x3d\x04\xc2\x1a\x00";
+        let expected: String = (0..40)
+            .map(|i| format!("line {}: the quick brown fox jumps over the lazy dog\n", i % 7))
+            .collect();
+        assert_eq!(inflate(compressed, 1 << 20).unwrap(), expected.as_bytes());
+    }
+
+    #[test]
+    fn inflate_dynamic_block() {
+        let compressed = b"\
+            \x15\x8e\xc1\x4e\xc3\x30\x10\x44\x7f\x65\x3e\x20\x70\x28\x37\x6e\x45\x0d\x20\x81\x7a\x2a\x1f\xb0\
+            \xb1\xd7\xc4\x4a\xe2\x0d\xbb\x36\x69\xfb\xf5\xdd\xdc\x46\x7a\xa3\x79\x73\x19\x19\xbf\xf7\xbc\x22\
+            \x89\x2e\x54\x3b\xd0\x6c\x82\xa9\xc8\x56\x40\x86\x8f\xf3\x0f\x9c\x76\xd8\x94\x56\xc3\xa9\x7f\xff\
+            \x3e\x5e\x7a\x44\xaa\xf4\x0a\x82\xf1\x5f\xe3\x12\x18\x92\x30\xcc\x12\x26\x43\x1d\xa9\x82\x94\xc1\
+            \xb9\x8e\xac\xb0\x2a\xca\xb1\x83\x28\x82\x2c\xab\xb2\x19\x47\x6c\x0e\x91\xf2\xd5\xa3\x83\x78\x2b\
+            \xb4\xe4\x80\xcf\x96\xd2\x42\xc5\x8b\x91\xcd\xaf\x94\x88\x81\xc2\xf4\xa4\x9c\x58\x77\x8f\xcf\x8b\
+            \x1b\x18\xbe\xf3\x9f\xa5\x19\x5e\x0e\xf8\xca\x6f\xbb\x5f\x5a\x5d\x5b\x7d\x7e\x00";
+        let expected = "The gzip format, also known as GNU zip, wraps DEFLATE data: a sequence of blocks that are \
+                        either stored, or compressed with fixed or dynamic Huffman codes, and back-references to \
+                        the previous 32 KiB of output.";
+        assert_eq!(inflate(compressed, 1024).unwrap(), expected.as_bytes());
+
+        for len in 0..compressed.len() - 1 {
+            assert_eq!(inflate(&compressed[..len], 1024), Err(Error::UnexpectedEof), "{len}");
+        }
+    }
+
+    #[test]
+    fn inflate_multiple_blocks() {
+        // a fixed block, an empty stored block and a final fixed block
+        let compressed = b"\xca\x48\xcd\xc9\xc9\x57\x00\x00\x00\x00\xff\xff\x2b\xcf\x2f\xca\x49\x01\x00";
+        assert_eq!(inflate(compressed, 1024).unwrap(), b"hello world");
+    }
+
+    #[test]
+    fn inflate_invalid_data() {
+        // block type 3
+        assert_eq!(inflate(b"\x07", 1024), Err(Error::InvalidData));
+        // a back-reference before the start of the output
+        assert_eq!(inflate(b"\x03\x02\x00", 1024), Err(Error::InvalidData));
+        assert_eq!(inflate(b"", 1024), Err(Error::UnexpectedEof));
+    }
+
+    #[test]
+    fn inflate_max_size() {
+        let compressed = b"\xcb\x48\xcd\xc9\xc9\x57\xc8\x40\x27\x01";
+        assert_eq!(inflate(compressed, 23).unwrap().len(), 23);
+        assert_eq!(inflate(compressed, 22), Err(Error::OutputTooLarge));
+        assert_eq!(inflate(compressed, 0), Err(Error::OutputTooLarge));
+        assert_eq!(
+            inflate(b"\x01\x05\x00\xfa\xff\x68\x65\x6c\x6c\x6f", 4),
+            Err(Error::OutputTooLarge)
+        );
+    }
+
+    #[test]
+    fn gunzip_members() {
+        assert_eq!(gunzip(HELLO_GZIP, 1024).unwrap(), b"hello");
+
+        // with a file name
+        let gzipped = b"\
+            \x1f\x8b\x08\x08\x00\x00\x00\x00\x02\xff\x74\x65\x73\x74\x2e\x6a\x73\x6f\x6e\x00\x8b\xae\x56\xf2\
+            \x4c\x51\xb2\x52\x4a\x4c\x4a\x56\xd2\x51\xf2\x4b\xcc\x4d\x2d\x56\xb2\x8a\x56\xd2\x2f\x49\x2d\x2e\
+            \x51\x8a\xad\x8d\x05\x00\x28\x6c\x48\xd3\x20\x00\x00\x00";
+        assert_eq!(gunzip(gzipped, 1024).unwrap(), br#"[{"Id":"abc","Names":["/test"]}]"#);
+
+        let concatenated = [HELLO_GZIP, HELLO_GZIP].concat();
+        assert_eq!(gunzip(&concatenated, 1024).unwrap(), b"hellohello");
+        assert_eq!(gunzip(&concatenated, 9), Err(Error::OutputTooLarge));
+
+        // a member can't back-reference the output of the previous member
+        let back_reference = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\x03\x02\x00\xae\x5e\xa2\x83\x03\x00\x00\x00";
+        assert_eq!(gunzip(&[HELLO_GZIP, back_reference].concat(), 1024), Err(Error::InvalidData));
+    }
+
+    #[test]
+    fn gunzip_errors() {
+        assert_eq!(gunzip(b"", 1024), Err(Error::UnexpectedEof));
+        assert_eq!(gunzip(&HELLO_GZIP[..HELLO_GZIP.len() - 1], 1024), Err(Error::UnexpectedEof));
+        assert_eq!(
+            gunzip(b"\xcb\x48\xcd\xc9\xc9\x07\x00\x00\x00\x00", 1024),
+            Err(Error::InvalidHeader)
+        );
+        assert_eq!(gunzip(&[HELLO_GZIP, b"\x00"].concat(), 1024), Err(Error::UnexpectedEof));
+        assert_eq!(gunzip(&[HELLO_GZIP, &[0; 10]].concat(), 1024), Err(Error::InvalidHeader));
+
+        let mut corrupted = HELLO_GZIP.to_vec();
+        let crc = corrupted.len() - 8;
+        corrupted[crc] ^= 1;
+        assert_eq!(gunzip(&corrupted, 1024), Err(Error::InvalidChecksum));
+
+        let mut corrupted = HELLO_GZIP.to_vec();
+        let size = corrupted.len() - 4;
+        corrupted[size] = 4;
+        assert_eq!(gunzip(&corrupted, 1024), Err(Error::InvalidChecksum));
+    }
+
+    #[test]
+    fn gunzip_header_fields() {
+        // the deflate data and trailer of HELLO_GZIP
+        let member = &HELLO_GZIP[10..];
+        let mut header = b"\x1f\x8b\x08\x1e\x00\x00\x00\x00\x02\x03".to_vec();
+        header.extend_from_slice(b"\x03\x00abc");
+        header.extend_from_slice(b"name\x00");
+        header.extend_from_slice(b"comment\x00");
+        let crc = crc32fast::hash(&header) as u16;
+        header.extend_from_slice(&crc.to_le_bytes());
+
+        assert_eq!(gunzip(&[&header, member].concat(), 1024).unwrap(), b"hello");
+
+        let len = header.len();
+        header[len - 1] ^= 1;
+        assert_eq!(gunzip(&[&header, member].concat(), 1024), Err(Error::InvalidHeader));
+        assert_eq!(gunzip(&header[..len - 10], 1024), Err(Error::UnexpectedEof));
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(Error::OutputTooLarge.to_string(), "decompressed data is too large");
+        assert_eq!(Error::InvalidChecksum.to_string(), "invalid gzip checksum");
+    }
+}
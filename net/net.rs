@@ -8,7 +8,21 @@ pub use hostname::hostname;
 /// - "[::1]" -> ("::1", "")
 /// - "[::1]:8080" -> ("::1", "8080")
 /// - "localhost:8080" -> ("localhost", "8080")
+/// - "::1" -> ("::1", "")
+/// - "2001:db8::1" -> ("2001:db8::1", "")
+///
+/// An input without brackets that contains more than one `:` is considered to be a bare IPv6
+/// address without port. This means that a bare IPv6 address can't carry a port: "::1:8080" is
+/// returned as the host ("::1:8080", ""). IPv6 addresses with a port must use brackets.
+///
+/// Splitting `host:port` (or `[host]:port` if the host contains a `:`) returns `host` and `port`
+/// unchanged.
 pub fn split_host_port(input: &str) -> (&str, &str) {
+    if !input.starts_with('[') && input.bytes().filter(|&b| b == b':').count() > 1 {
+        // input is an IPv6 without brackets, and thus without port. e.g. ::1
+        return (input, "");
+    }
+
     if let Some(index) = input.rfind(':') {
         if index == (input.len() - 1) {
             // input is a malformed input. e.g. localhost:
@@ -96,6 +110,35 @@ mod test {
                     port: "",
                 },
             ),
+            (
+                "::1",
+                Expected {
+                    host: "::1",
+                    port: "",
+                },
+            ),
+            (
+                "2001:db8::1",
+                Expected {
+                    host: "2001:db8::1",
+                    port: "",
+                },
+            ),
+            (
+                "[2001:db8::1]:443",
+                Expected {
+                    host: "2001:db8::1",
+                    port: "443",
+                },
+            ),
+            (
+                // ambiguous: bare IPv6 addresses can't have a port
+                "::1:8080",
+                Expected {
+                    host: "::1:8080",
+                    port: "",
+                },
+            ),
         ];
 
         for test in tests {
@@ -104,4 +147,25 @@ mod test {
             assert_eq!(test.1.port, port);
         }
     }
+
+    #[test]
+    fn test_split_host_port_round_trip() {
+        let tests = [
+            ("localhost", "8080"),
+            ("127.0.0.1", "80"),
+            ("::1", "8080"),
+            ("2001:db8::1", "443"),
+            ("example.com", ""),
+        ];
+
+        for (host, port) in tests {
+            let input = match (host.contains(':'), port.is_empty()) {
+                (true, true) => format!("[{host}]"),
+                (true, false) => format!("[{host}]:{port}"),
+                (false, true) => host.to_string(),
+                (false, false) => format!("{host}:{port}"),
+            };
+            assert_eq!(split_host_port(&input), (host, port), "{input}");
+        }
+    }
 }
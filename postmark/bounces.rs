@@ -0,0 +1,204 @@
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, Client, SendRequestInput};
+
+/// Query parameters to list bounces.
+/// See https://postmarkapp.com/developer/api/bounce-api#bounces
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct BounceQuery {
+    /// Number of bounces to return per request. Max 500.
+    pub count: u32,
+
+    /// Number of bounces to skip.
+    pub offset: u32,
+
+    /// Filter by type of bounce, e.g. `HardBounce` or `SpamComplaint`.
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub bounce_type: Option<String>,
+
+    /// Filter by emails that were deactivated by Postmark due to the bounce.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inactive: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Bounce {
+    #[serde(rename = "ID")]
+    pub id: i64,
+    /// Type of bounce, e.g. `HardBounce` or `SpamComplaint`.
+    #[serde(rename = "Type")]
+    pub bounce_type: String,
+    pub type_code: i64,
+    pub name: String,
+    pub tag: Option<String>,
+    #[serde(rename = "MessageID")]
+    pub message_id: Option<String>,
+    #[serde(rename = "ServerID")]
+    pub server_id: Option<i64>,
+    pub message_stream: Option<String>,
+    pub description: String,
+    pub details: String,
+    /// Email address that bounced.
+    pub email: String,
+    pub from: String,
+    pub bounced_at: String,
+    /// Whether the raw SMTP source of the bounce is available.
+    pub dump_available: bool,
+    /// Whether the address was deactivated. Postmark doesn't send emails to inactive addresses.
+    pub inactive: bool,
+    /// Whether the address can be activated again with [`Client::activate_bounce`].
+    pub can_activate: bool,
+    pub subject: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListBouncesResponse {
+    /// Total number of bounces matching the query, not only the ones returned.
+    pub total_count: i64,
+    pub bounces: Vec<Bounce>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ActivateBounceResponse {
+    pub message: String,
+    pub bounce: Bounce,
+}
+
+impl Client {
+    pub async fn list_bounces(
+        &self,
+        server_token: String,
+        query: BounceQuery,
+    ) -> Result<ListBouncesResponse, ApiError> {
+        let query_string = serde_urlencoded::to_string(&query).map_err(|err| ApiError {
+            error_code: 0,
+            message: format!("postmark: error encoding query parameters: {err}"),
+        })?;
+
+        return self
+            .send_request(SendRequestInput::<()> {
+                method: Method::GET,
+                url: format!("/bounces?{query_string}"),
+                body: None,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+
+    /// Activates a deactivated email address so emails can be sent to it again.
+    pub async fn activate_bounce(&self, server_token: String, id: i64) -> Result<ActivateBounceResponse, ApiError> {
+        return self
+            .send_request(SendRequestInput::<()> {
+                method: Method::PUT,
+                url: format!("/bounces/{id}/activate"),
+                body: None,
+                server_token: Some(server_token),
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_query() {
+        let query = BounceQuery {
+            count: 50,
+            offset: 0,
+            ..Default::default()
+        };
+        assert_eq!(serde_urlencoded::to_string(&query).unwrap(), "count=50&offset=0");
+
+        let query = BounceQuery {
+            count: 10,
+            offset: 20,
+            bounce_type: Some("HardBounce".to_string()),
+            inactive: Some(true),
+        };
+        assert_eq!(
+            serde_urlencoded::to_string(&query).unwrap(),
+            "count=10&offset=20&type=HardBounce&inactive=true"
+        );
+
+        let query = BounceQuery {
+            count: 1,
+            offset: 0,
+            bounce_type: Some("Soft Bounce&".to_string()),
+            inactive: None,
+        };
+        assert_eq!(serde_urlencoded::to_string(&query).unwrap(), "count=1&offset=0&type=Soft+Bounce%26");
+    }
+
+    #[test]
+    fn deserialize_bounces() {
+        let response: ListBouncesResponse = serde_json::from_str(
+            r#"{
+                "TotalCount": 253,
+                "Bounces": [
+                    {
+                        "ID": 692560173,
+                        "Type": "HardBounce",
+                        "TypeCode": 1,
+                        "Name": "Hard bounce",
+                        "Tag": "Invitation",
+                        "MessageID": "2c1b63fe-43f2-4db5-91b0-8bdfa44a9316",
+                        "ServerID": 23,
+                        "MessageStream": "outbound",
+                        "Description": "The server was unable to deliver your message (ex: unknown user, mailbox not found).",
+                        "Details": "action: failed\r\n",
+                        "Email": "anything@blackhole.postmarkapp.com",
+                        "From": "sender@example.com",
+                        "BouncedAt": "2014-01-15T16:09:19.6421112-05:00",
+                        "DumpAvailable": false,
+                        "Inactive": false,
+                        "CanActivate": true,
+                        "Subject": "SC show bounce"
+                    },
+                    {
+                        "ID": 676862817,
+                        "Type": "SpamComplaint",
+                        "TypeCode": 512,
+                        "Name": "Spam complaint",
+                        "Tag": null,
+                        "MessageID": null,
+                        "ServerID": 23,
+                        "Description": "The subscriber explicitly marked this message as spam.",
+                        "Details": "",
+                        "Email": "spam@example.com",
+                        "From": "sender@example.com",
+                        "BouncedAt": "2013-10-18T09:49:59.8253577-04:00",
+                        "DumpAvailable": true,
+                        "Inactive": true,
+                        "CanActivate": false,
+                        "Subject": null
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.total_count, 253);
+        assert_eq!(response.bounces.len(), 2);
+
+        let bounce = &response.bounces[0];
+        assert_eq!(bounce.id, 692560173);
+        assert_eq!(bounce.bounce_type, "HardBounce");
+        assert_eq!(bounce.message_id.as_deref(), Some("2c1b63fe-43f2-4db5-91b0-8bdfa44a9316"));
+        assert_eq!(bounce.message_stream.as_deref(), Some("outbound"));
+        assert!(bounce.can_activate);
+        assert!(!bounce.inactive);
+
+        let bounce = &response.bounces[1];
+        assert_eq!(bounce.bounce_type, "SpamComplaint");
+        assert_eq!(bounce.tag, None);
+        assert_eq!(bounce.message_stream, None);
+        assert!(bounce.inactive);
+        assert!(!bounce.can_activate);
+    }
+}
@@ -1,12 +1,25 @@
+use std::fmt;
+
+use hyper_utils::HttpClientError;
 use reqwest::{
     Method,
     header::{self, HeaderMap, HeaderValue},
 };
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
+/// The default base URL of the Postmark API.
+pub const DEFAULT_API_BASE_URL: &str = "https://api.postmarkapp.com";
+
+/// A Postmark API client.
+///
+/// Cloning a client is cheap: clones share the same connection pool, so a single client should
+/// be created and cloned where needed (e.g. in each request handler) instead of being wrapped in
+/// an `Arc`.
+#[derive(Clone)]
 pub struct Client {
     pub http_client: reqwest::Client,
-    pub api_base_url: &'static str,
+    /// Base URL of the API, without trailing slash. Requests are sent to `{api_base_url}{path}`.
+    pub api_base_url: String,
     pub account_api_token: Option<String>,
 }
 
@@ -17,20 +30,49 @@ pub struct ApiError {
     pub message: String,
 }
 
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (error code: {})", self.message, self.error_code)
+    }
+}
+
+/// API errors don't wrap other errors, so [`source`](std::error::Error::source) returns `None`.
+impl std::error::Error for ApiError {}
+
+/// `error_code` is Postmark's API error code, and `0` for errors that happened before a response
+/// was received, which are converted to a `None` code.
+impl From<ApiError> for HttpClientError {
+    fn from(err: ApiError) -> Self {
+        HttpClientError {
+            code: (err.error_code != 0).then_some(err.error_code),
+            message: err.message,
+            source: None,
+        }
+    }
+}
+
 pub struct SendRequestInput<B: Serialize> {
     pub method: Method,
     pub url: String,
-    pub body: B,
+    /// The JSON body of the request. `None` for requests without a body, such as `GET` requests.
+    pub body: Option<B>,
     pub server_token: Option<String>,
 }
 
 impl Client {
     pub fn new(account_api_token: Option<String>) -> Client {
+        return Client::with_base_url(account_api_token, DEFAULT_API_BASE_URL.to_string());
+    }
+
+    /// Creates a client sending requests to `api_base_url` instead of [`DEFAULT_API_BASE_URL`],
+    /// e.g. a mock server for tests or a proxy. A trailing slash is removed.
+    pub fn with_base_url(account_api_token: Option<String>, api_base_url: String) -> Client {
         let http_client = reqwest::Client::new();
+        let api_base_url = api_base_url.trim_end_matches('/').to_string();
 
         return Client {
             http_client,
-            api_base_url: "https://api.postmarkapp.com",
+            api_base_url,
             account_api_token,
         };
     }
@@ -55,17 +97,15 @@ impl Client {
         }
 
         let url = format!("{}{}", &self.api_base_url, input.url);
-        let res = self
-            .http_client
-            .request(input.method, url)
-            .headers(headers)
-            .json(&input.body)
-            .send()
-            .await
-            .map_err(|err| ApiError {
-                error_code: 0,
-                message: format!("postmark: error sending request: {err}"),
-            })?;
+        let mut req = self.http_client.request(input.method, url).headers(headers);
+        if let Some(body) = &input.body {
+            req = req.json(body);
+        }
+
+        let res = req.send().await.map_err(|err| ApiError {
+            error_code: 0,
+            message: format!("postmark: error sending request: {err}"),
+        })?;
 
         if res.status().as_u16() > 399 {
             let err: ApiError = res.json().await.map_err(|err| ApiError {
@@ -83,3 +123,108 @@ impl Client {
         return Ok(res);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        error::Error,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+    use crate::Email;
+
+    #[test]
+    fn base_url() {
+        assert_eq!(Client::new(None).api_base_url, DEFAULT_API_BASE_URL);
+        assert_eq!(
+            Client::with_base_url(None, "http://localhost:8080/".to_string()).api_base_url,
+            "http://localhost:8080"
+        );
+    }
+
+    /// Starts a minimal HTTP server answering `requests` requests, returning its address and the
+    /// request lines it received.
+    fn mock_server(requests: usize) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let server = thread::spawn(move || {
+            let mut request_lines = Vec::with_capacity(requests);
+            for _ in 0..requests {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header == "\r\n" {
+                        break;
+                    }
+                }
+
+                let body = r#"{"ErrorCode": 0, "Message": "OK"}"#;
+                let headers = format!("Content-Type: application/json\r\nContent-Length: {}\r\n", body.len());
+                write!(reader.get_mut(), "HTTP/1.1 200 OK\r\n{headers}Connection: close\r\n\r\n{body}").unwrap();
+                request_lines.push(request_line);
+            }
+            request_lines
+        });
+
+        return (format!("http://{address}"), server);
+    }
+
+    #[tokio::test]
+    async fn send_request_to_custom_base_url() {
+        let (address, server) = mock_server(1);
+
+        let client = Client::with_base_url(None, format!("{address}/api"));
+        let res = client.send_email("token".to_string(), Email::default()).await.unwrap();
+
+        assert_eq!(res.message, "OK");
+        assert_eq!(server.join().unwrap(), vec!["POST /api/email HTTP/1.1\r\n"]);
+    }
+
+    #[tokio::test]
+    async fn cloned_clients() {
+        let (address, server) = mock_server(2);
+
+        let client = Client::with_base_url(Some("account".to_string()), address);
+        let clone = client.clone();
+        assert_eq!(clone.api_base_url, client.api_base_url);
+        assert_eq!(clone.account_api_token.as_deref(), Some("account"));
+
+        let handle = tokio::spawn(async move { clone.send_email("token".to_string(), Email::default()).await });
+        let res = client.send_email("token".to_string(), Email::default()).await.unwrap();
+        assert_eq!(res.message, "OK");
+        assert_eq!(handle.await.unwrap().unwrap().message, "OK");
+
+        assert_eq!(server.join().unwrap(), vec!["POST /email HTTP/1.1\r\n"; 2]);
+    }
+
+    #[test]
+    fn into_http_client_error() {
+        let err = ApiError {
+            error_code: 406,
+            message: "Recipient is marked as inactive.".to_string(),
+        };
+        assert_eq!(err.to_string(), "Recipient is marked as inactive. (error code: 406)");
+        assert!(err.source().is_none());
+
+        let err: HttpClientError = err.into();
+        assert_eq!(err.code, Some(406));
+        assert_eq!(err.message, "Recipient is marked as inactive.");
+        assert!(err.source().is_none());
+
+        let err: HttpClientError = ApiError {
+            error_code: 0,
+            message: "postmark: error sending request: timeout".to_string(),
+        }
+        .into();
+        assert_eq!(err.code, None);
+        assert_eq!(err.message, "postmark: error sending request: timeout");
+    }
+}
@@ -163,7 +163,7 @@ impl Client {
             .send_request(SendRequestInput {
                 method: Method::POST,
                 url: "/email".to_string(),
-                body: email,
+                body: Some(email),
                 server_token: Some(server_token),
             })
             .await;
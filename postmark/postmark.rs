@@ -1,5 +1,7 @@
+mod bounces;
 mod client;
 mod emails;
 
+pub use bounces::*;
 pub use client::*;
 pub use emails::*;
@@ -1,4 +1,7 @@
+use serde::de::{Deserialize, Deserializer, Visitor};
 use serde_derive::Deserialize;
+use serde_urlencoded::Utf8Policy;
+use std::fmt;
 
 #[derive(Deserialize, Debug, PartialEq)]
 struct NewType<T>(T);
@@ -97,3 +100,112 @@ fn deserialize_128bit() {
     let q = format!("min={}", i128::MIN);
     assert_eq!(serde_urlencoded::from_str(&q), Ok(result));
 }
+
+/// A bytes field, like `serde_bytes::ByteBuf`.
+#[derive(Debug, Clone, PartialEq)]
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+#[test]
+fn deserialize_invalid_utf8_lossy() {
+    let result = vec![("field".to_owned(), "a\u{FFFD}b".to_owned())];
+
+    assert_eq!(
+        serde_urlencoded::from_str("field=a%FFb"),
+        Ok(result.clone())
+    );
+    assert_eq!(
+        serde_urlencoded::from_str_with_policy(
+            "field=a%FFb",
+            Utf8Policy::Lossy
+        ),
+        Ok(result)
+    );
+}
+
+#[test]
+fn deserialize_invalid_utf8_strict() {
+    let err = serde_urlencoded::from_str_with_policy::<Vec<(String, String)>>(
+        "field=a%FFb",
+        Utf8Policy::Strict,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid UTF-8 in percent-decoded input: \
+         invalid utf-8 sequence of 1 bytes from index 1"
+    );
+
+    // names are checked too
+    assert!(
+        serde_urlencoded::from_str_with_policy::<Vec<(String, String)>>(
+            "%FF=a",
+            Utf8Policy::Strict
+        )
+        .is_err()
+    );
+
+    let result = vec![("field".to_owned(), "comté".to_owned())];
+    assert_eq!(
+        serde_urlencoded::from_str_with_policy(
+            "field=comt%C3%A9",
+            Utf8Policy::Strict
+        ),
+        Ok(result)
+    );
+}
+
+#[test]
+fn deserialize_invalid_utf8_bytes() {
+    let result = vec![("field".to_owned(), ByteBuf(vec![b'a', 0xFF, b'b']))];
+
+    for policy in [Utf8Policy::Lossy, Utf8Policy::Strict] {
+        assert_eq!(
+            serde_urlencoded::from_str_with_policy("field=a%FFb", policy),
+            Ok(result.clone())
+        );
+    }
+
+    let result = vec![("field", &b"ab"[..])];
+    assert_eq!(serde_urlencoded::from_bytes(b"field=ab"), Ok(result));
+}
+
+#[test]
+fn deserialize_reset() {
+    type Pairs<'a> = Vec<(&'a str, String)>;
+
+    let inputs = ["first=23&last=42", "", "name=comt%C3%A9&first=1"];
+    let mut deserializer =
+        serde_urlencoded::Deserializer::new(form_urlencoded::parse(b""));
+
+    for input in inputs {
+        deserializer.reset(input);
+        let result = Pairs::deserialize(&mut deserializer);
+        assert_eq!(result, serde_urlencoded::from_str(input));
+
+        // the input is consumed until the next reset
+        assert_eq!(Pairs::deserialize(&mut deserializer), Ok(vec![]));
+    }
+}
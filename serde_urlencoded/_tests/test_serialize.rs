@@ -131,3 +131,81 @@ fn key_type_error_msg() {
     let mut map = HashMap::from([(vec![1, 2, 3], "value")]);
     let _ = serde_urlencoded::to_string(map).unwrap();
 }
+
+#[test]
+fn serialize_sorted() {
+    let params = &[("b", "2"), ("a", "2"), ("a", "1"), ("c d", "é")];
+    assert_eq!(
+        serde_urlencoded::to_string_sorted(params),
+        Ok("a=1&a=2&b=2&c+d=%C3%A9".to_owned())
+    );
+    assert_eq!(serde_urlencoded::to_string_sorted(()), Ok("".to_owned()));
+}
+
+#[derive(Serialize)]
+struct Payment {
+    timestamp: u64,
+    currency: &'static str,
+    amount: u32,
+}
+
+#[derive(Serialize)]
+struct PaymentReordered {
+    amount: u32,
+    currency: &'static str,
+    timestamp: u64,
+}
+
+#[cfg(feature = "sign")]
+#[test]
+fn sign_query() {
+    let (query, signature) = serde_urlencoded::sign_query(
+        &Payment {
+            timestamp: 1700000000,
+            currency: "EUR",
+            amount: 100,
+        },
+        b"secret",
+    )
+    .unwrap();
+    assert_eq!(query, "amount=100&currency=EUR&timestamp=1700000000");
+    assert_eq!(
+        signature,
+        "bf246c0470f8170368153cc982c75958f66407dde6852b6dc4f09e7ed598cf32"
+    );
+
+    // the order of the fields doesn't change the signature
+    let reordered = serde_urlencoded::sign_query(
+        &PaymentReordered {
+            amount: 100,
+            currency: "EUR",
+            timestamp: 1700000000,
+        },
+        b"secret",
+    )
+    .unwrap();
+    assert_eq!(reordered, (query.clone(), signature.clone()));
+
+    // but any value or the key does
+    let (_, other_signature) = serde_urlencoded::sign_query(
+        &Payment {
+            timestamp: 1700000000,
+            currency: "EUR",
+            amount: 101,
+        },
+        b"secret",
+    )
+    .unwrap();
+    assert_ne!(other_signature, signature);
+
+    let (_, other_signature) = serde_urlencoded::sign_query(
+        &Payment {
+            timestamp: 1700000000,
+            currency: "EUR",
+            amount: 100,
+        },
+        b"other",
+    )
+    .unwrap();
+    assert_ne!(other_signature, signature);
+}
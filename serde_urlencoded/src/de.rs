@@ -1,13 +1,15 @@
 //! Deserialization support for the `application/x-www-form-urlencoded` format.
 
 use form_urlencoded::Parse as UrlEncodedParse;
-use form_urlencoded::parse;
+use form_urlencoded::ParseBytes as UrlEncodedParseBytes;
+use form_urlencoded::{parse, parse_bytes};
 use serde::de::Error as de_Error;
 use serde::de::value::MapDeserializer;
 use serde::de::{self, IntoDeserializer};
 use serde::forward_to_deserialize_any;
 use std::borrow::Cow;
 use std::io::Read;
+use std::str;
 
 #[doc(inline)]
 pub use serde::de::value::Error;
@@ -34,6 +36,32 @@ where
     T::deserialize(Deserializer::new(parse(input)))
 }
 
+/// Deserializes a `application/x-www-form-urlencoded` value from a `&[u8]`,
+/// handling percent-decoded bytes that are not valid UTF-8 according to
+/// `policy`.
+///
+/// ```
+/// use serde_urlencoded::de::Utf8Policy;
+///
+/// assert_eq!(
+///     serde_urlencoded::from_bytes_with_policy::<Vec<(String, String)>>(
+///         b"name=caf%E9", Utf8Policy::Lossy),
+///     Ok(vec![("name".to_owned(), "caf\u{FFFD}".to_owned())]));
+///
+/// assert!(
+///     serde_urlencoded::from_bytes_with_policy::<Vec<(String, String)>>(
+///         b"name=caf%E9", Utf8Policy::Strict).is_err());
+/// ```
+pub fn from_bytes_with_policy<'de, T>(
+    input: &'de [u8],
+    policy: Utf8Policy,
+) -> Result<T, Error>
+where
+    T: de::Deserialize<'de>,
+{
+    T::deserialize(Deserializer::with_utf8_policy(parse(input), policy))
+}
+
 /// Deserializes a `application/x-www-form-urlencoded` value from a `&str`.
 ///
 /// ```
@@ -56,6 +84,19 @@ where
     from_bytes(input.as_bytes())
 }
 
+/// Deserializes a `application/x-www-form-urlencoded` value from a `&str`,
+/// handling percent-decoded bytes that are not valid UTF-8 according to
+/// `policy`.
+pub fn from_str_with_policy<'de, T>(
+    input: &'de str,
+    policy: Utf8Policy,
+) -> Result<T, Error>
+where
+    T: de::Deserialize<'de>,
+{
+    from_bytes_with_policy(input.as_bytes(), policy)
+}
+
 /// Convenience function that reads all bytes from `reader` and deserializes
 /// them with `from_bytes`.
 pub fn from_reader<T, R>(mut reader: R) -> Result<T, Error>
@@ -70,6 +111,24 @@ where
     from_bytes(&buf)
 }
 
+/// How percent-decoded bytes that are not valid UTF-8, such as `%FF`, are
+/// deserialized into strings.
+///
+/// Whatever the policy, bytes fields (those deserialized with
+/// `deserialize_bytes` or `deserialize_byte_buf`, e.g. `serde_bytes::ByteBuf`
+/// or `bytes::Bytes`) receive the raw percent-decoded bytes, which is useful
+/// for forms submitted in legacy encodings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Utf8Policy {
+    /// Invalid sequences are replaced with U+FFFD REPLACEMENT CHARACTER, like
+    /// `String::from_utf8_lossy`.
+    #[default]
+    Lossy,
+    /// Deserializing invalid UTF-8 as a string, a number or an enum returns an
+    /// error.
+    Strict,
+}
+
 /// A deserializer for the `application/x-www-form-urlencoded` format.
 ///
 /// * Supported top-level outputs are structs, maps and sequences of pairs,
@@ -79,17 +138,80 @@ where
 ///
 /// * Everything else but `deserialize_seq` and `deserialize_seq_fixed_size`
 ///   defers to `deserialize`.
+///
+/// * `&mut Deserializer` is also a deserializer, so that a single deserializer
+///   can parse several inputs with [`Deserializer::reset`].
 pub struct Deserializer<'de> {
     inner: MapDeserializer<'de, PartIterator<'de>, Error>,
+    policy: Utf8Policy,
 }
 
 impl<'de> Deserializer<'de> {
-    /// Returns a new `Deserializer`.
+    /// Returns a new `Deserializer`, that replaces invalid UTF-8 like
+    /// [`Utf8Policy::Lossy`].
     pub fn new(parser: UrlEncodedParse<'de>) -> Self {
+        Self::with_utf8_policy(parser, Utf8Policy::default())
+    }
+
+    /// Returns a new `Deserializer` that handles invalid UTF-8 according to
+    /// `policy`.
+    pub fn with_utf8_policy(
+        parser: UrlEncodedParse<'de>,
+        policy: Utf8Policy,
+    ) -> Self {
         Deserializer {
-            inner: MapDeserializer::new(PartIterator(parser)),
+            inner: MapDeserializer::new(PartIterator {
+                parser: parser.into_bytes(),
+                policy,
+            }),
+            policy,
         }
     }
+
+    /// Resets the deserializer to parse `input`, keeping its UTF-8 policy.
+    ///
+    /// With a `&mut Deserializer`, the same deserializer can be used for
+    /// several inputs, e.g. the query strings of successive requests into the
+    /// same target type:
+    ///
+    /// ```
+    /// use serde::Deserialize;
+    ///
+    /// let inputs = ["page=1", "page=2&sort=name"];
+    /// let mut deserializer =
+    ///     serde_urlencoded::Deserializer::new(form_urlencoded::parse(b""));
+    /// for input in inputs {
+    ///     deserializer.reset(input);
+    ///     let query = Vec::<(String, String)>::deserialize(&mut deserializer);
+    ///     assert_eq!(query, serde_urlencoded::from_str(input));
+    /// }
+    /// ```
+    ///
+    /// Pairs are parsed lazily, so a deserializer never allocates to store
+    /// them: only the names and values that contain `+` or percent-encoded
+    /// bytes are allocated when they are deserialized, reused or not.
+    ///
+    /// The deserialized values may borrow from the input for `'de` (e.g. the
+    /// `&str` fields of a struct), so every input given to a deserializer must
+    /// outlive it, and the values borrowed from a previous input stay valid
+    /// after a reset. Inputs with shorter lifetimes, like a buffer that is
+    /// overwritten for each request, need a new `Deserializer`, which is cheap
+    /// as it doesn't allocate either.
+    ///
+    /// Once an input has been deserialized through a `&mut Deserializer`, the
+    /// deserializer is empty until it is reset.
+    pub fn reset(&mut self, input: &'de str) {
+        self.inner = MapDeserializer::new(PartIterator {
+            parser: parse_bytes(input.as_bytes()),
+            policy: self.policy,
+        });
+    }
+
+    /// Takes the current input out of the deserializer, leaving it empty.
+    fn take(&mut self) -> Self {
+        let empty = Self::with_utf8_policy(parse(b""), self.policy);
+        std::mem::replace(self, empty)
+    }
 }
 
 impl<'de> de::Deserializer<'de> for Deserializer<'de> {
@@ -155,17 +277,116 @@ impl<'de> de::Deserializer<'de> for Deserializer<'de> {
     }
 }
 
-struct PartIterator<'de>(UrlEncodedParse<'de>);
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.take().deserialize_any(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.take().deserialize_map(visitor)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.take().deserialize_seq(visitor)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.take().deserialize_unit(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool
+        u8
+        u16
+        u32
+        u64
+        u128
+        i8
+        i16
+        i32
+        i64
+        i128
+        f32
+        f64
+        char
+        str
+        string
+        option
+        bytes
+        byte_buf
+        unit_struct
+        newtype_struct
+        tuple_struct
+        struct
+        identifier
+        tuple
+        enum
+        ignored_any
+    }
+}
+
+struct PartIterator<'de> {
+    parser: UrlEncodedParseBytes<'de>,
+    policy: Utf8Policy,
+}
 
 impl<'de> Iterator for PartIterator<'de> {
     type Item = (Part<'de>, Part<'de>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(|(k, v)| (Part(k), Part(v)))
+        let policy = self.policy;
+        self.parser
+            .next()
+            .map(|(k, v)| (Part(k, policy), Part(v, policy)))
     }
 }
 
-struct Part<'de>(Cow<'de, str>);
+struct Part<'de>(Cow<'de, [u8]>, Utf8Policy);
+
+impl<'de> Part<'de> {
+    fn into_str(self) -> Result<Cow<'de, str>, Error> {
+        match self.0 {
+            Cow::Borrowed(bytes) => match str::from_utf8(bytes) {
+                Ok(value) => Ok(Cow::Borrowed(value)),
+                Err(e) => invalid_utf8(bytes, e, self.1),
+            },
+            Cow::Owned(bytes) => match String::from_utf8(bytes) {
+                Ok(value) => Ok(Cow::Owned(value)),
+                Err(e) => invalid_utf8(e.as_bytes(), e.utf8_error(), self.1),
+            },
+        }
+    }
+}
+
+fn invalid_utf8(
+    bytes: &[u8],
+    error: str::Utf8Error,
+    policy: Utf8Policy,
+) -> Result<Cow<'static, str>, Error> {
+    match policy {
+        Utf8Policy::Lossy => {
+            Ok(Cow::Owned(String::from_utf8_lossy(bytes).into_owned()))
+        }
+        Utf8Policy::Strict => Err(Error::custom(format_args!(
+            "invalid UTF-8 in percent-decoded input: {}",
+            error
+        ))),
+    }
+}
 
 impl<'de> IntoDeserializer<'de> for Part<'de> {
     type Deserializer = Self;
@@ -181,7 +402,7 @@ macro_rules! forward_parsed_value {
             fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
                 where V: de::Visitor<'de>
             {
-                match self.0.parse::<$ty>() {
+                match self.into_str()?.parse::<$ty>() {
                     Ok(val) => val.into_deserializer().$method(visitor),
                     Err(e) => Err(de::Error::custom(e))
                 }
@@ -197,12 +418,32 @@ impl<'de> de::Deserializer<'de> for Part<'de> {
     where
         V: de::Visitor<'de>,
     {
-        match self.0 {
+        match self.into_str()? {
             Cow::Borrowed(value) => visitor.visit_borrowed_str(value),
             Cow::Owned(value) => visitor.visit_string(value),
         }
     }
 
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.0 {
+            Cow::Borrowed(value) => visitor.visit_borrowed_bytes(value),
+            Cow::Owned(value) => visitor.visit_byte_buf(value),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
@@ -219,7 +460,7 @@ impl<'de> de::Deserializer<'de> for Part<'de> {
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_enum(ValueEnumAccess(self.0))
+        visitor.visit_enum(ValueEnumAccess(self.into_str()?))
     }
 
     fn deserialize_newtype_struct<V>(
@@ -238,8 +479,6 @@ impl<'de> de::Deserializer<'de> for Part<'de> {
         str
         string
         unit
-        bytes
-        byte_buf
         unit_struct
         tuple_struct
         struct
@@ -5,8 +5,16 @@
 
 pub mod de;
 pub mod ser;
+#[cfg(feature = "sign")]
+pub mod sign;
 
 #[doc(inline)]
-pub use crate::de::{Deserializer, from_bytes, from_reader, from_str};
+pub use crate::de::{
+    Deserializer, Utf8Policy, from_bytes, from_bytes_with_policy, from_reader,
+    from_str, from_str_with_policy,
+};
 #[doc(inline)]
-pub use crate::ser::{Serializer, to_string};
+pub use crate::ser::{Serializer, to_string, to_string_sorted};
+#[cfg(feature = "sign")]
+#[doc(inline)]
+pub use crate::sign::sign_query;
@@ -33,6 +33,49 @@ pub fn to_string<T: ser::Serialize>(input: T) -> Result<String, Error> {
     Ok(urlencoder.finish())
 }
 
+/// Serializes a value into a `application/x-www-form-urlencoded` `String`
+/// buffer, with the pairs sorted by key, then by value.
+///
+/// The output doesn't depend on the order of the input, e.g. of the entries of
+/// a `HashMap`, so it can be used as the canonical form of a query string,
+/// for example to sign it. The pairs are compared by their encoded bytes.
+///
+/// ```
+/// let meal = &[
+///     ("meat", "ham"),
+///     ("bread", "baguette"),
+///     ("cheese", "comté"),
+///     ("bread", ""),
+/// ];
+///
+/// assert_eq!(
+///     serde_urlencoded::to_string_sorted(meal),
+///     Ok("bread=&bread=baguette&cheese=comt%C3%A9&meat=ham".to_owned()));
+/// ```
+pub fn to_string_sorted<T: ser::Serialize>(input: T) -> Result<String, Error> {
+    let encoded = to_string(input)?;
+
+    // `&` and `=` are percent-encoded in keys and values, so the pairs can be
+    // split without decoding them
+    let mut pairs: Vec<(&str, &str)> = encoded
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .collect();
+    pairs.sort_unstable();
+
+    let mut sorted = String::with_capacity(encoded.len());
+    for (key, value) in pairs {
+        if !sorted.is_empty() {
+            sorted.push('&');
+        }
+        sorted.push_str(key);
+        sorted.push('=');
+        sorted.push_str(value);
+    }
+    Ok(sorted)
+}
+
 /// A serializer for the `application/x-www-form-urlencoded` format.
 ///
 /// * Supported top-level inputs are structs, maps and sequences of pairs,
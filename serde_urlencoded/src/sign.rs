@@ -0,0 +1,31 @@
+//! Signatures of query strings, as used by many APIs to authenticate requests.
+
+use crypto::{hmac::Hmac, sha2::Sha256};
+use serde::ser;
+
+use crate::ser::{Error, to_string_sorted};
+
+/// Returns the canonical query string of `params`, with the pairs sorted as
+/// by [`to_string_sorted`], and its HMAC-SHA256 signature with `key`, encoded
+/// as lowercase hex.
+///
+/// The signature doesn't depend on the order of the fields of `params`, so
+/// the server can recompute it from the query string it received.
+///
+/// ```
+/// let params = &[("timestamp", "1700000000"), ("amount", "100")];
+/// let (query, signature) =
+///     serde_urlencoded::sign_query(params, b"secret").unwrap();
+///
+/// assert_eq!(query, "amount=100&timestamp=1700000000");
+/// assert_eq!(signature.len(), 64);
+/// ```
+pub fn sign_query<T: ser::Serialize>(
+    params: &T,
+    key: &[u8],
+) -> Result<(String, String), Error> {
+    let query = to_string_sorted(params)?;
+    let signature =
+        hex::encode(Hmac::<Sha256>::mac(key, query.as_bytes()).as_ref());
+    Ok((query, signature))
+}
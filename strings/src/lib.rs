@@ -10,3 +10,52 @@ pub fn ftoa<F: Float>(n: F) -> String {
     let mut buffer = ryu::Buffer::new();
     return buffer.format(n).to_string();
 }
+
+/// Returns the length of `s` in UTF-16 code units, as counted by JavaScript, Java or Windows
+/// APIs. Characters outside the Basic Multilingual Plane (e.g. most emoji) count as 2.
+pub fn utf16_len(s: &str) -> usize {
+    return s.chars().map(char::len_utf16).sum();
+}
+
+pub fn to_utf16(s: &str) -> Vec<u16> {
+    return s.encode_utf16().collect();
+}
+
+/// Decodes UTF-16 code units, returning an error if `units` contains unpaired surrogates.
+pub fn from_utf16(units: &[u16]) -> Result<String, std::string::FromUtf16Error> {
+    return String::from_utf16(units);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf16() {
+        let tests = [
+            ("", 0),
+            ("hello", 5),
+            ("héllo", 5),
+            ("日本語", 3),
+            ("😀", 2),
+            ("a😀b", 4),
+            ("👩‍💻", 5),
+        ];
+        for (s, len) in tests {
+            assert_eq!(utf16_len(s), len, "{s}");
+            let units = to_utf16(s);
+            assert_eq!(units.len(), len, "{s}");
+            assert_eq!(from_utf16(&units).unwrap(), s);
+        }
+
+        assert_eq!(to_utf16("😀"), [0xd83d, 0xde00]);
+    }
+
+    #[test]
+    fn from_utf16_invalid() {
+        // unpaired high surrogate, unpaired low surrogate, and swapped pair
+        for units in [&[0xd83d][..], &[0x61, 0xde00], &[0xde00, 0xd83d]] {
+            assert!(from_utf16(units).is_err(), "{units:x?}");
+        }
+    }
+}
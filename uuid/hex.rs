@@ -5,6 +5,11 @@ pub const HEX_ENCODE: [u8; 16] = [
     b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'a', b'b', b'c', b'd', b'e', b'f',
 ];
 
+/// Precomputed uppercase hex encoding table.
+pub const HEX_ENCODE_UPPER: [u8; 16] = [
+    b'0', b'1', b'2', b'3', b'4', b'5', b'6', b'7', b'8', b'9', b'A', b'B', b'C', b'D', b'E', b'F',
+];
+
 /// Precomputed hex decoding table (256-entry, O(1) lookup).
 ///
 /// Maps each byte value to its decoded nibble (0–15) or `0xff` for invalid
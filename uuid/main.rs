@@ -0,0 +1,178 @@
+use std::{env, process};
+
+use uuid::Uuid;
+
+const USAGE: &str = "usage: uuid [--v4 | --v7] [--count <n>] [--format <simple|hyphenated|urn>] [--json]
+
+Generate UUIDs, one per line.
+
+options:
+    --v4                generate random UUIDs (default)
+    --v7                generate time-ordered UUIDs
+    --count <n>         number of UUIDs to generate (default: 1)
+    --format <format>   simple (32 hex digits), hyphenated (default) or urn (urn:uuid:...)
+    --json              print a JSON array of strings instead of one UUID per line
+    -h, --help          print this help";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Version {
+    V4,
+    V7,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Simple,
+    Hyphenated,
+    Urn,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    version: Version,
+    count: usize,
+    format: Format,
+    json: bool,
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            eprintln!("{USAGE}");
+            process::exit(1);
+        }
+    };
+
+    let uuids: Vec<String> = (0..options.count)
+        .map(|_| {
+            let uuid = match options.version {
+                Version::V4 => Uuid::new_v4(),
+                Version::V7 => Uuid::new_v7(),
+            };
+            format_uuid(&uuid, options.format)
+        })
+        .collect();
+
+    if options.json {
+        println!("{}", to_json_array(&uuids));
+    } else {
+        for uuid in uuids {
+            println!("{uuid}");
+        }
+    }
+}
+
+/// Parses the command-line arguments, without the program name. Returns `None` if the help
+/// was requested.
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        version: Version::V4,
+        count: 1,
+        format: Format::Hyphenated,
+        json: false,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--v4" => options.version = Version::V4,
+            "--v7" => options.version = Version::V7,
+            "--json" => options.json = true,
+            "--count" => {
+                let count = args.next().ok_or("--count requires a value")?;
+                options.count = count.parse().map_err(|_| format!("invalid count: {count}"))?;
+            }
+            "--format" => {
+                let format = args.next().ok_or("--format requires a value")?;
+                options.format = match format.as_str() {
+                    "simple" => Format::Simple,
+                    "hyphenated" => Format::Hyphenated,
+                    "urn" => Format::Urn,
+                    _ => return Err(format!("invalid format: {format}")),
+                };
+            }
+            "-h" | "--help" => return Ok(None),
+            _ => return Err(format!("unknown argument: {arg}")),
+        }
+    }
+
+    return Ok(Some(options));
+}
+
+fn format_uuid(uuid: &Uuid, format: Format) -> String {
+    match format {
+        Format::Simple => uuid.to_string().replace('-', ""),
+        Format::Hyphenated => uuid.to_string(),
+        Format::Urn => format!("urn:uuid:{uuid}"),
+    }
+}
+
+/// UUIDs only contain ASCII letters, digits, `-` and `:`, so they don't need to be escaped.
+fn to_json_array(uuids: &[String]) -> String {
+    let items: Vec<String> = uuids.iter().map(|uuid| format!("\"{uuid}\"")).collect();
+    return format!("[{}]", items.join(","));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Options>, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_defaults() {
+        assert_eq!(
+            parse(&[]),
+            Ok(Some(Options {
+                version: Version::V4,
+                count: 1,
+                format: Format::Hyphenated,
+                json: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_all_flags() {
+        assert_eq!(
+            parse(&["--v7", "--count", "3", "--json", "--format", "urn"]),
+            Ok(Some(Options {
+                version: Version::V7,
+                count: 3,
+                format: Format::Urn,
+                json: true,
+            }))
+        );
+        assert_eq!(parse(&["--help"]), Ok(None));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse(&["--count"]).is_err());
+        assert!(parse(&["--count", "three"]).is_err());
+        assert!(parse(&["--format", "braced"]).is_err());
+        assert!(parse(&["--v8"]).is_err());
+    }
+
+    #[test]
+    fn formats() {
+        let uuid = Uuid::parse("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap();
+        assert_eq!(format_uuid(&uuid, Format::Simple), "f47ac10b58cc4372a5670e02b2c3d479");
+        assert_eq!(format_uuid(&uuid, Format::Hyphenated), "f47ac10b-58cc-4372-a567-0e02b2c3d479");
+        assert_eq!(format_uuid(&uuid, Format::Urn), "urn:uuid:f47ac10b-58cc-4372-a567-0e02b2c3d479");
+    }
+
+    #[test]
+    fn json_array() {
+        assert_eq!(to_json_array(&[]), "[]");
+        assert_eq!(to_json_array(&["a".to_string(), "b".to_string()]), r#"["a","b"]"#);
+    }
+}
@@ -0,0 +1,49 @@
+use std::process::Command;
+
+use uuid::{Uuid, Version};
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_uuid")).args(args).output().unwrap();
+    assert!(
+        output.status.success(),
+        "uuid {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    return String::from_utf8(output.stdout).unwrap();
+}
+
+#[test]
+fn json_output() {
+    let output = run(&["--v7", "--json", "--count", "2"]);
+    let uuids: Vec<String> = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(uuids.len(), 2);
+    for uuid in &uuids {
+        assert_eq!(Uuid::parse(uuid).unwrap().version(), Version::V7);
+    }
+    assert_ne!(uuids[0], uuids[1]);
+}
+
+#[test]
+fn formats() {
+    let simple = run(&["--format", "simple"]);
+    assert_eq!(simple.trim().len(), 32);
+    assert!(simple.trim().chars().all(|c| c.is_ascii_hexdigit()));
+
+    let urn = run(&["--format", "urn", "--count", "3"]);
+    let lines: Vec<&str> = urn.lines().collect();
+    assert_eq!(lines.len(), 3);
+    for line in lines {
+        let uuid = line.strip_prefix("urn:uuid:").unwrap();
+        assert_eq!(Uuid::parse(uuid).unwrap().version(), Version::V4);
+    }
+}
+
+#[test]
+fn invalid_arguments() {
+    let output = Command::new(env!("CARGO_BIN_EXE_uuid"))
+        .args(["--format", "braced"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
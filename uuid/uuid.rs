@@ -2,7 +2,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 //! Universally Unique IDentifiers (RFC 9562).
-//! Supports generating v4 and v7 UUIDs, and parsing all versions.
+//! Supports generating v4, v5, v6 and v7 UUIDs, and parsing all versions.
 //!
 //! UUIDs v7 support thread-local monotonic counters.
 //!
@@ -10,12 +10,14 @@
 //!
 //! | Flag   | Description                                                       | Default |
 //! |--------|-------------------------------------------------------------------|---------|
-//! | `std`  | Enables [`Uuid::new_v4`] and [`Uuid::new_v7`] via `rand`          | Yes     |
+//! | `std`  | Enables [`Uuid::new_v4`], [`Uuid::new_v7`] and their `_from_rng` variants via `rand`, and [`Uuid::new_v6`] | Yes |
+//! | `v5`   | Enables [`Uuid::new_v5`], [`Uuid::from_url`] and [`Uuid::from_dns`] via `crypto` | Yes |
 //! | `serde`| Enables [`serde`] serialization/deserialization                  | No      |
 //! | `sqlx` | Enables [`sqlx`] integration for PostgreSQL (type, encode, decode) | No      |
 //!
 //! When `std` is not enabled, the crate is `#![no_std]` compatible.
-//! Parsing, formatting, and version detection are all available.
+//! Parsing, formatting and version detection are all available, and so are name-based UUIDs v5
+//! with the `v5` feature.
 //!
 //! # Example
 //!
@@ -32,9 +34,24 @@
 //! assert_eq!(v4.version(), Version::V4);
 //! assert_eq!(v7.version(), Version::V7);
 //! ```
+//!
+//! # Command-line tool
+//!
+//! The `uuid` binary (requires the `std` feature) prints new UUIDs:
+//!
+//! ```text
+//! $ uuid --v7 --count 3 --json
+//! $ uuid --format urn
+//! ```
+//!
+//! `--format` accepts `simple`, `hyphenated` (default) or `urn`, and `--json` prints a JSON array
+//! of strings instead of one UUID per line.
 
 use core::{fmt, str::FromStr};
 
+#[cfg(feature = "v5")]
+use crypto::{Hasher, sha1::Sha1};
+
 mod hex;
 
 #[cfg(feature = "serde")]
@@ -43,6 +60,9 @@ mod serde;
 #[cfg(feature = "sqlx")]
 mod sqlx;
 
+#[cfg(feature = "std")]
+mod v6;
+
 #[cfg(feature = "std")]
 thread_local! {
     /// Per-thread state for UUIDv7 monotonic counter generation.
@@ -126,6 +146,30 @@ impl Uuid {
         uuid
     }
 
+    /// Generate a new version 4 (random) UUID drawing its randomness from `rng`.
+    ///
+    /// Useful to generate reproducible UUIDs in tests with a seeded RNG.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rand::SeedableRng;
+    /// use uuid::{Uuid, Version};
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// let uuid = Uuid::new_v4_from_rng(&mut rng);
+    /// assert_eq!(uuid.version(), Version::V4);
+    /// ```
+    pub fn new_v4_from_rng<R: rand::Rng + ?Sized>(rng: &mut R) -> Uuid {
+        let mut uuid = Uuid::nil();
+        rng.fill_bytes(&mut uuid.0);
+
+        uuid.0[6] = (uuid.0[6] & 0x0f) | 0x40;
+        uuid.0[8] = (uuid.0[8] & 0x3f) | 0x80;
+
+        uuid
+    }
+
     /// Generate a new version 7 UUID with a 32-bit monotonic counter.
     ///
     /// The 48-bit timestamp is milliseconds since the Unix epoch.
@@ -203,29 +247,39 @@ impl Uuid {
             }
         };
 
-        let mut uuid = Uuid(rand::random());
-
-        // unix_ts_ms: 48-bit big-endian timestamp
-        uuid.0[0..6].copy_from_slice(&timestamp.to_be_bytes()[2..8]);
-        // uuid.0[0] = (ts >> 40) as u8;
-        // uuid.0[1] = (ts >> 32) as u8;
-        // uuid.0[2] = (ts >> 24) as u8;
-        // uuid.0[3] = (ts >> 16) as u8;
-        // uuid.0[4] = (ts >> 8) as u8;
-        // uuid.0[5] = ts as u8;
-
-        // version (4 bits) + counter[31:28] (4 bits)
-        uuid.0[6] = 0x70 | ((counter >> 28) & 0x0F) as u8;
-        // counter[27:20] (8 bits)
-        uuid.0[7] = (counter >> 20) as u8;
-        // variant (2 bits) + counter[19:14] (6 bits)
-        uuid.0[8] = 0x80 | ((counter >> 14) & 0x3F) as u8;
-        // counter[13:6] (8 bits)
-        uuid.0[9] = (counter >> 6) as u8;
-        // counter[5:0] (6 bits) -> preserve upper 2 random bits
-        uuid.0[10] = (uuid.0[10] & 0xC0) | (counter & 0x3F) as u8;
+        v7_from_parts(timestamp, counter, rand::random())
+    }
 
-        uuid
+    /// Generate a new version 7 UUID drawing its counter and random bits from `rng`.
+    ///
+    /// The timestamp is still read from the system clock, but contrary to [`Uuid::new_v7`], the
+    /// thread-local monotonic counter is not used: the counter is seeded from `rng` on every
+    /// call. UUIDs generated within the same millisecond are thus not guaranteed to be sorted.
+    ///
+    /// Useful to generate reproducible UUIDs (apart from the timestamp) in tests with a seeded RNG.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system clock is before the Unix epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rand::SeedableRng;
+    /// use uuid::{Uuid, Version};
+    ///
+    /// let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    /// let uuid = Uuid::new_v7_from_rng(&mut rng);
+    /// assert_eq!(uuid.version(), Version::V7);
+    /// ```
+    pub fn new_v7_from_rng<R: rand::Rng + ?Sized>(rng: &mut R) -> Uuid {
+        let timestamp = v7_now_ms();
+        // see v7_random_counter
+        let counter = rng.next_u32() & 0x7FFFFFFF;
+        let mut random = [0u8; 16];
+        rng.fill_bytes(&mut random);
+
+        v7_from_parts(timestamp, counter, random)
     }
 }
 
@@ -441,6 +495,81 @@ impl Uuid {
         Version::Unknown
     }
 
+    /// Return `true` if this is the Nil UUID (all 128 bits set to zero).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::Uuid;
+    ///
+    /// assert!(Uuid::nil().is_nil());
+    /// assert!(!Uuid::max().is_nil());
+    /// ```
+    #[inline]
+    pub const fn is_nil(&self) -> bool {
+        self.as_u128() == 0
+    }
+
+    /// Return `true` if this is the Max UUID (all 128 bits set to one).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::Uuid;
+    ///
+    /// assert!(Uuid::max().is_max());
+    /// assert!(!Uuid::nil().is_max());
+    /// ```
+    #[inline]
+    pub const fn is_max(&self) -> bool {
+        self.as_u128() == u128::MAX
+    }
+
+    /// Return `true` if this is a version 4 (random) UUID.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::Uuid;
+    ///
+    /// let uuid = Uuid::parse("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap();
+    /// assert!(uuid.is_v4());
+    /// ```
+    #[inline]
+    pub const fn is_v4(&self) -> bool {
+        matches!(self.version(), Version::V4)
+    }
+
+    /// Return `true` if this is a version 7 (Unix Epoch time-based) UUID.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::Uuid;
+    ///
+    /// let uuid = Uuid::parse("017f22e2-79b0-7cc3-98c4-dc0c0c07398f").unwrap();
+    /// assert!(uuid.is_v7());
+    /// ```
+    #[inline]
+    pub const fn is_v7(&self) -> bool {
+        matches!(self.version(), Version::V7)
+    }
+
+    /// Return `true` if this is a version 8 (custom) UUID.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::Uuid;
+    ///
+    /// let uuid = Uuid::parse("2489e9ad-2ee2-8e00-8ec9-32d5f69181c0").unwrap();
+    /// assert!(uuid.is_v8());
+    /// ```
+    #[inline]
+    pub const fn is_v8(&self) -> bool {
+        matches!(self.version(), Version::V8)
+    }
+
     /// Return the Unix millisecond timestamp embedded in a UUIDv7.
     ///
     /// Only UUID version 7 (Unix Epoch time-based) carries a meaningful
@@ -473,42 +602,200 @@ impl Uuid {
     }
 }
 
-impl fmt::Display for Uuid {
-    /// Formats the UUID as a lowercase 8-4-4-4-12 hyphenated string.
+impl Uuid {
+    /// Namespace for fully-qualified domain names (RFC 9562 §6.6).
+    pub const NAMESPACE_DNS: Uuid = Uuid::from_u128(0x6ba7b810_9dad_11d1_80b4_00c04fd430c8);
+    /// Namespace for URLs (RFC 9562 §6.6).
+    pub const NAMESPACE_URL: Uuid = Uuid::from_u128(0x6ba7b811_9dad_11d1_80b4_00c04fd430c8);
+    /// Namespace for ISO OIDs (RFC 9562 §6.6).
+    pub const NAMESPACE_OID: Uuid = Uuid::from_u128(0x6ba7b812_9dad_11d1_80b4_00c04fd430c8);
+    /// Namespace for X.500 DNs (RFC 9562 §6.6).
+    pub const NAMESPACE_X500: Uuid = Uuid::from_u128(0x6ba7b814_9dad_11d1_80b4_00c04fd430c8);
+
+    /// Generate a version 5 (name-based, SHA-1) UUID from a namespace and a name.
+    ///
+    /// The same namespace and name always produce the same UUID.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// let uuid = uuid::Uuid::nil();
-    /// assert_eq!(uuid.to_string(), "00000000-0000-0000-0000-000000000000");
+    /// use uuid::{Uuid, Version};
+    ///
+    /// let uuid = Uuid::new_v5(Uuid::NAMESPACE_DNS, b"www.example.com");
+    /// assert_eq!(uuid.version(), Version::V5);
+    /// assert_eq!(uuid.to_string(), "2ed6657d-e927-568b-95e1-2665a8aea6a2");
     /// ```
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let bytes = &self.0;
-        let mut buf = [0u8; 36];
+    #[cfg(feature = "v5")]
+    pub fn new_v5(namespace: Uuid, name: &[u8]) -> Uuid {
+        let mut hasher = Sha1::new();
+        hasher.update(&namespace.0);
+        hasher.update(name);
+        let hash = hasher.sum();
+
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash.as_ref()[..16]);
+        // Set version (5) in the high nibble of byte 6
+        bytes[6] = (bytes[6] & 0x0f) | 0x50;
+        // Set variant (RFC 9562: 10xx) in the high bits of byte 8
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+        Uuid(bytes)
+    }
+
+    /// Generate the version 5 UUID of a URL, in the [`Uuid::NAMESPACE_URL`] namespace.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::Uuid;
+    ///
+    /// let uuid = Uuid::from_url("https://example.com/");
+    /// assert_eq!(uuid, Uuid::new_v5(Uuid::NAMESPACE_URL, b"https://example.com/"));
+    /// ```
+    #[cfg(feature = "v5")]
+    #[inline]
+    pub fn from_url(url: &str) -> Uuid {
+        Uuid::new_v5(Uuid::NAMESPACE_URL, url.as_bytes())
+    }
+
+    /// Generate the version 5 UUID of a domain name, in the [`Uuid::NAMESPACE_DNS`] namespace.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::Uuid;
+    ///
+    /// let uuid = Uuid::from_dns("www.example.com");
+    /// assert_eq!(uuid.to_string(), "2ed6657d-e927-568b-95e1-2665a8aea6a2");
+    /// ```
+    #[cfg(feature = "v5")]
+    #[inline]
+    pub fn from_dns(name: &str) -> Uuid {
+        Uuid::new_v5(Uuid::NAMESPACE_DNS, name.as_bytes())
+    }
+}
 
+impl Uuid {
+    /// Encode the UUID as a 8-4-4-4-12 hyphenated string into a stack buffer, using the given hex
+    /// encoding table.
+    #[inline]
+    fn encode_hyphenated(&self, table: &[u8; 16]) -> [u8; 36] {
+        let mut buf = [b'-'; 36];
         let groups: [(usize, usize); 5] = [(0, 8), (9, 13), (14, 18), (19, 23), (24, 36)];
 
         let mut src = 0;
-        for (gi, &(start, end)) in groups.iter().enumerate() {
+        for (start, end) in groups {
             let mut j = start;
             while j < end {
-                let b = bytes[src];
+                let b = self.0[src];
                 src += 1;
-                buf[j] = hex::HEX_ENCODE[(b >> 4) as usize];
-                buf[j + 1] = hex::HEX_ENCODE[(b & 0x0f) as usize];
+                buf[j] = table[(b >> 4) as usize];
+                buf[j + 1] = table[(b & 0x0f) as usize];
                 j += 2;
             }
-            if gi < 4 {
-                buf[end] = b'-';
-            }
         }
+        buf
+    }
+
+    /// Write the hyphenated string into the beginning of `buffer`, using the given hex encoding
+    /// table.
+    #[inline]
+    fn encode_hyphenated_into<'buf>(&self, buffer: &'buf mut [u8], table: &[u8; 16]) -> &'buf mut str {
+        assert!(
+            buffer.len() >= 36,
+            "uuid: buffer is too small to encode a UUID: {} < 36",
+            buffer.len()
+        );
+        let buf = &mut buffer[..36];
+        buf.copy_from_slice(&self.encode_hyphenated(table));
+        // the buffer only contains ASCII hex digits and hyphens, so this never fails
+        core::str::from_utf8_mut(buf).expect("uuid: encoded UUID is not ASCII")
+    }
+
+    /// Write the lowercase 8-4-4-4-12 hyphenated string into the beginning of `buffer`, without
+    /// allocating, and return it. This is the same as [`Display`](fmt::Display).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is shorter than 36 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let uuid = uuid::Uuid::parse("F47AC10B-58CC-4372-A567-0E02B2C3D479").unwrap();
+    /// let mut buffer = [0u8; 36];
+    /// assert_eq!(uuid.encode_lower(&mut buffer), "f47ac10b-58cc-4372-a567-0e02b2c3d479");
+    /// ```
+    #[inline]
+    pub fn encode_lower<'buf>(&self, buffer: &'buf mut [u8]) -> &'buf mut str {
+        self.encode_hyphenated_into(buffer, &hex::HEX_ENCODE)
+    }
+
+    /// Write the uppercase 8-4-4-4-12 hyphenated string into the beginning of `buffer`, without
+    /// allocating, and return it. This is the same as [`UpperHex`](fmt::UpperHex).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is shorter than 36 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let uuid = uuid::Uuid::parse("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap();
+    /// let mut buffer = [0u8; 36];
+    /// assert_eq!(uuid.encode_upper(&mut buffer), "F47AC10B-58CC-4372-A567-0E02B2C3D479");
+    /// ```
+    #[inline]
+    pub fn encode_upper<'buf>(&self, buffer: &'buf mut [u8]) -> &'buf mut str {
+        self.encode_hyphenated_into(buffer, &hex::HEX_ENCODE_UPPER)
+    }
+
+    /// Write the hyphenated string directly to the formatter, without allocating.
+    #[inline]
+    fn fmt_hyphenated(&self, f: &mut fmt::Formatter<'_>, table: &[u8; 16]) -> fmt::Result {
+        let mut buf = [0u8; 36];
+        f.write_str(self.encode_hyphenated_into(&mut buf, table))
+    }
+}
 
-        // SAFETY: `buf` is filled by writing only ASCII bytes from our
-        // precomputed hex encoding table plus the hyphen byte `b'-'`,
-        // all of which are valid UTF-8. The loop guarantees every position
-        // is initialized before we reach this point.
-        let s = unsafe { core::str::from_utf8_unchecked(&buf) };
-        f.write_str(s)
+impl fmt::Display for Uuid {
+    /// Formats the UUID as a lowercase 8-4-4-4-12 hyphenated string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let uuid = uuid::Uuid::nil();
+    /// assert_eq!(uuid.to_string(), "00000000-0000-0000-0000-000000000000");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_hyphenated(f, &hex::HEX_ENCODE)
+    }
+}
+
+impl fmt::LowerHex for Uuid {
+    /// Formats the UUID as a lowercase 8-4-4-4-12 hyphenated string, like [`Display`](fmt::Display).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let uuid = uuid::Uuid::max();
+    /// assert_eq!(format!("{uuid:x}"), "ffffffff-ffff-ffff-ffff-ffffffffffff");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_hyphenated(f, &hex::HEX_ENCODE)
+    }
+}
+
+impl fmt::UpperHex for Uuid {
+    /// Formats the UUID as an uppercase 8-4-4-4-12 hyphenated string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let uuid = uuid::Uuid::max();
+    /// assert_eq!(format!("{uuid:X}"), "FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_hyphenated(f, &hex::HEX_ENCODE_UPPER)
     }
 }
 
@@ -543,6 +830,36 @@ fn v7_random_counter() -> u32 {
     rand::random::<u32>() & 0x7FFFFFFF
 }
 
+/// Build a UUIDv7 from its 48-bit millisecond `timestamp`, its 32-bit `counter` and `random`
+/// bytes. See [`Uuid::new_v7`] for the layout.
+#[cfg(feature = "std")]
+#[inline]
+fn v7_from_parts(timestamp: u64, counter: u32, random: [u8; 16]) -> Uuid {
+    let mut uuid = Uuid(random);
+
+    // unix_ts_ms: 48-bit big-endian timestamp
+    uuid.0[0..6].copy_from_slice(&timestamp.to_be_bytes()[2..8]);
+    // uuid.0[0] = (ts >> 40) as u8;
+    // uuid.0[1] = (ts >> 32) as u8;
+    // uuid.0[2] = (ts >> 24) as u8;
+    // uuid.0[3] = (ts >> 16) as u8;
+    // uuid.0[4] = (ts >> 8) as u8;
+    // uuid.0[5] = ts as u8;
+
+    // version (4 bits) + counter[31:28] (4 bits)
+    uuid.0[6] = 0x70 | ((counter >> 28) & 0x0F) as u8;
+    // counter[27:20] (8 bits)
+    uuid.0[7] = (counter >> 20) as u8;
+    // variant (2 bits) + counter[19:14] (6 bits)
+    uuid.0[8] = 0x80 | ((counter >> 14) & 0x3F) as u8;
+    // counter[13:6] (8 bits)
+    uuid.0[9] = (counter >> 6) as u8;
+    // counter[5:0] (6 bits) -> preserve upper 2 random bits
+    uuid.0[10] = (uuid.0[10] & 0xC0) | (counter & 0x3F) as u8;
+
+    uuid
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -565,6 +882,110 @@ mod tests {
         assert_eq!(Uuid::parse(&uuid.to_string()).unwrap(), uuid);
     }
 
+    #[test]
+    fn predicates() {
+        let v4 = Uuid::parse("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap();
+        let v7 = Uuid::parse("017f22e2-79b0-7cc3-98c4-dc0c0c07398f").unwrap();
+        let v8 = Uuid::parse("2489e9ad-2ee2-8e00-8ec9-32d5f69181c0").unwrap();
+        let nil = Uuid::nil();
+        let max = Uuid::max();
+
+        assert!(nil.is_nil());
+        assert!(!max.is_nil());
+        assert!(!v4.is_nil());
+
+        assert!(max.is_max());
+        assert!(!nil.is_max());
+        assert!(!v4.is_max());
+
+        assert!(v4.is_v4());
+        assert!(!v7.is_v4());
+        assert!(!v8.is_v4());
+
+        assert!(v7.is_v7());
+        assert!(!v4.is_v7());
+        assert!(!v8.is_v7());
+
+        assert!(v8.is_v8());
+        assert!(!v4.is_v8());
+        assert!(!v7.is_v8());
+
+        for uuid in [nil, max] {
+            assert!(!uuid.is_v4());
+            assert!(!uuid.is_v7());
+            assert!(!uuid.is_v8());
+        }
+
+        let uuids = [v4, v7, nil, v8, v4, max];
+        assert_eq!(uuids.iter().filter(|uuid| uuid.is_v4()).count(), 2);
+    }
+
+    #[test]
+    fn encode_lower_and_upper() {
+        let uuids = [
+            Uuid::nil(),
+            Uuid::max(),
+            Uuid::parse("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap(),
+            Uuid::parse("2ed6657d-e927-568b-95e1-2665a8aea6a2").unwrap(),
+        ];
+        for uuid in uuids {
+            let mut buffer = [0u8; 36];
+            assert_eq!(&*uuid.encode_lower(&mut buffer), uuid.to_string());
+            assert_eq!(&*uuid.encode_upper(&mut buffer), format!("{uuid:X}"));
+            assert_eq!(&*uuid.encode_upper(&mut buffer), uuid.to_string().to_uppercase());
+
+            // only the beginning of larger buffers is written
+            let mut buffer = [b'!'; 40];
+            assert_eq!(&*uuid.encode_lower(&mut buffer), format!("{uuid:x}"));
+            assert_eq!(&buffer[36..], b"!!!!");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "buffer is too small")]
+    fn encode_into_small_buffer() {
+        Uuid::nil().encode_lower(&mut [0u8; 35]);
+    }
+
+    #[cfg(feature = "v5")]
+    #[test]
+    fn new_v5() {
+        assert_eq!(Uuid::NAMESPACE_DNS.to_string(), "6ba7b810-9dad-11d1-80b4-00c04fd430c8");
+        assert_eq!(Uuid::NAMESPACE_URL.to_string(), "6ba7b811-9dad-11d1-80b4-00c04fd430c8");
+
+        let dns = [
+            ("python.org", "886313e1-3b8a-5372-9b90-0c9aee199e5d"),
+            ("www.example.com", "2ed6657d-e927-568b-95e1-2665a8aea6a2"),
+        ];
+        for (name, expected) in dns {
+            let uuid = Uuid::from_dns(name);
+            assert_eq!(uuid.to_string(), expected);
+            assert_eq!(uuid.version(), Version::V5);
+            assert_eq!(uuid.0[8] & 0xc0, 0x80);
+            assert_eq!(uuid, Uuid::new_v5(Uuid::NAMESPACE_DNS, name.as_bytes()));
+        }
+
+        let urls = [
+            ("http://python.org/", "4c565f0d-3f5a-5890-b41b-20cf47701c5e"),
+            ("https://example.com/", "dd2c1780-811a-5296-81c5-178a0ef488bc"),
+            ("https://example.com/a", "6639460f-3425-5329-8097-a58f06127860"),
+        ];
+        for (url, expected) in urls {
+            assert_eq!(Uuid::from_url(url).to_string(), expected);
+            assert_eq!(Uuid::from_url(url), Uuid::from_url(url));
+        }
+        assert_ne!(Uuid::from_url("https://example.com/"), Uuid::from_url("https://example.com/a"));
+        // the namespace is part of the hash
+        assert_ne!(Uuid::from_url("www.example.com"), Uuid::from_dns("www.example.com"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn predicates_generated() {
+        assert!(Uuid::new_v4().is_v4());
+        assert!(Uuid::new_v7().is_v7());
+    }
+
     #[test]
     fn parse_v4() {
         let uuid = Uuid::parse("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap();
@@ -583,6 +1004,51 @@ mod tests {
         assert_eq!(uuid.version(), Version::V4);
     }
 
+    #[test]
+    fn parse_is_case_insensitive() {
+        let expected = [
+            0xf4, 0x7a, 0xc1, 0x0b, 0x58, 0xcc, 0x43, 0x72, 0xa5, 0x67, 0x0e, 0x02, 0xb2, 0xc3, 0xd4, 0x79,
+        ];
+        let lower = Uuid::parse("f47ac10b-58cc-4372-a567-0e02b2c3d479").unwrap();
+        let upper = Uuid::parse("F47AC10B-58CC-4372-A567-0E02B2C3D479").unwrap();
+        let mixed = Uuid::parse("F47ac10B-58cC-4372-A567-0e02B2c3d479").unwrap();
+
+        assert_eq!(lower.as_bytes(), expected);
+        assert_eq!(upper.as_bytes(), expected);
+        assert_eq!(mixed.as_bytes(), expected);
+    }
+
+    #[test]
+    fn parse_uppercase_roundtrip() {
+        // formatting is always lowercase, so parsing the uppercase form and formatting it again must
+        // give back the lowercase form, which itself parses to the same bytes.
+        let upper = "F47AC10B-58CC-4372-A567-0E02B2C3D479";
+        let uuid = Uuid::parse(upper).unwrap();
+        let formatted = uuid.to_string();
+        assert_eq!(formatted, "f47ac10b-58cc-4372-a567-0e02b2c3d479");
+        assert_eq!(Uuid::parse(&formatted).unwrap(), uuid);
+        assert_eq!(formatted.to_ascii_uppercase(), upper);
+    }
+
+    #[test]
+    fn parse_every_hex_digit_case() {
+        for (i, c) in "0123456789abcdef".bytes().enumerate() {
+            let lower = core::str::from_utf8(&[c; 32]).unwrap().to_string();
+            let lower = format!(
+                "{}-{}-{}-{}-{}",
+                &lower[0..8],
+                &lower[8..12],
+                &lower[12..16],
+                &lower[16..20],
+                &lower[20..]
+            );
+            let upper = lower.to_ascii_uppercase();
+            let expected = Uuid::from_bytes([(i as u8) << 4 | i as u8; 16]);
+            assert_eq!(Uuid::parse(&lower).unwrap(), expected);
+            assert_eq!(Uuid::parse(&upper).unwrap(), expected);
+        }
+    }
+
     #[test]
     fn parse_invalid_short() {
         assert_eq!(Uuid::parse("too-short"), Err(Error::InvalidUuid));
@@ -615,6 +1081,26 @@ mod tests {
         assert_eq!(uuid.to_string(), "01020304-0506-0708-090a-0b0c0d0e0f10");
     }
 
+    #[test]
+    fn display_and_hex_formatting() {
+        let tests = [
+            ("00000000-0000-0000-0000-000000000000", "00000000-0000-0000-0000-000000000000"),
+            ("ffffffff-ffff-ffff-ffff-ffffffffffff", "FFFFFFFF-FFFF-FFFF-FFFF-FFFFFFFFFFFF"),
+            ("f47ac10b-58cc-4372-a567-0e02b2c3d479", "F47AC10B-58CC-4372-A567-0E02B2C3D479"),
+            ("01020304-0506-0708-090a-0b0c0d0e0f10", "01020304-0506-0708-090A-0B0C0D0E0F10"),
+            ("0189a3b2-c4d5-7e6f-8a9b-0c1d2e3f4a5b", "0189A3B2-C4D5-7E6F-8A9B-0C1D2E3F4A5B"),
+        ];
+        for (lower, upper) in tests {
+            let uuid = Uuid::parse(lower).unwrap();
+            assert_eq!(uuid.to_string(), lower);
+            assert_eq!(format!("{uuid}"), lower);
+            assert_eq!(format!("{uuid:?}"), lower);
+            assert_eq!(format!("{uuid:x}"), lower);
+            assert_eq!(format!("{uuid:X}"), upper);
+            assert_eq!(Uuid::parse(upper).unwrap(), uuid);
+        }
+    }
+
     #[test]
     fn parse_then_to_string() {
         let s = "f47ac10b-58cc-4372-a567-0e02b2c3d479";
@@ -742,6 +1228,40 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn new_v4_from_seeded_rng_is_reproducible() {
+        use rand::SeedableRng;
+
+        let mut rng1 = rand::rngs::StdRng::seed_from_u64(42);
+        let mut rng2 = rand::rngs::StdRng::seed_from_u64(42);
+        let a = Uuid::new_v4_from_rng(&mut rng1);
+        let b = Uuid::new_v4_from_rng(&mut rng2);
+        assert_eq!(a, b);
+        assert_eq!(a.version(), Version::V4);
+        assert_eq!(a.0[8] & 0xc0, 0x80);
+
+        // the RNG advances between calls
+        assert_ne!(Uuid::new_v4_from_rng(&mut rng1), a);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn new_v7_from_seeded_rng_is_reproducible() {
+        use rand::SeedableRng;
+
+        let mut rng1 = rand::rngs::StdRng::seed_from_u64(42);
+        let mut rng2 = rand::rngs::StdRng::seed_from_u64(42);
+        let a = Uuid::new_v7_from_rng(&mut rng1);
+        let b = Uuid::new_v7_from_rng(&mut rng2);
+        assert_eq!(a.version(), Version::V7);
+        assert_eq!(a.0[8] & 0xc0, 0x80);
+        assert!(a.timestamp().is_some());
+        // everything but the timestamp comes from the RNG
+        assert_eq!(a.0[6..], b.0[6..]);
+        assert_eq!(extract_v7_counter(&a), extract_v7_counter(&b));
+    }
+
     /// Extract the 32-bit monotonic counter from a v7 UUID.
     #[cfg(feature = "std")]
     fn extract_v7_counter(uuid: &Uuid) -> u32 {
@@ -0,0 +1,136 @@
+//! UUIDs v6 (RFC 9562 §5.6): the fields of a UUID v1 reordered so that UUIDs sort by time, which
+//! keeps the indexes of databases compact.
+
+use crate::Uuid;
+
+/// Number of 100-nanosecond intervals between the start of the Gregorian calendar
+/// (1582-10-15 00:00:00 UTC), which is the epoch of UUIDs v1 and v6, and the Unix epoch.
+const GREGORIAN_OFFSET: u64 = 0x01B2_1DD2_1381_4000;
+
+thread_local! {
+    /// Per-thread state for UUIDv6 generation.
+    /// Tracks `(last_timestamp, clock_sequence)`, the clock sequence being seeded on first use.
+    static V6_STATE: std::cell::RefCell<(u64, u16)> = std::cell::RefCell::new((0, rand::random::<u16>() & 0x3FFF));
+}
+
+impl Uuid {
+    /// Generate a new version 6 (reordered time-based) UUID for the 48-bit `node`, usually a MAC
+    /// address.
+    ///
+    /// The 60-bit timestamp is the number of 100-nanosecond intervals since the start of the
+    /// Gregorian calendar (1582-10-15), and the 14-bit clock sequence is random for each thread.
+    /// When the clock doesn't advance between two calls, or moves backward, the timestamp of the
+    /// previous UUID is incremented instead, so UUIDs generated by a thread are sorted.
+    ///
+    /// The 128-bit layout follows RFC 9562:
+    ///
+    /// ```text
+    ///  0                   1                   2                   3
+    ///  0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// |                           time_high                           |
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// |           time_mid            |  ver  |       time_low        |
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// |var|         clock_seq         |             node              |
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// |                              node                             |
+    /// +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system clock is before the Unix epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use uuid::{Uuid, Version};
+    ///
+    /// let uuid = Uuid::new_v6([0x9f, 0x6b, 0xde, 0xce, 0xd8, 0x46]);
+    /// assert_eq!(uuid.version(), Version::V6);
+    /// ```
+    pub fn new_v6(node: [u8; 6]) -> Uuid {
+        let now = v6_now();
+
+        let (timestamp, clock_seq) = V6_STATE.with(|cell| {
+            let mut state = cell.borrow_mut();
+            state.0 = if now > state.0 { now } else { state.0 + 1 };
+            *state
+        });
+
+        v6_from_parts(timestamp, clock_seq, node)
+    }
+}
+
+#[inline]
+fn v6_now() -> u64 {
+    let elapsed = std::time::SystemTime::UNIX_EPOCH
+        .elapsed()
+        .expect("SystemTime::UNIX_EPOCH elapsed should not fail");
+    GREGORIAN_OFFSET + elapsed.as_secs() * 10_000_000 + (elapsed.subsec_nanos() / 100) as u64
+}
+
+/// Build a UUIDv6 from its 60-bit `timestamp`, its 14-bit `clock_seq` and its `node`. See
+/// [`Uuid::new_v6`] for the layout.
+#[inline]
+fn v6_from_parts(timestamp: u64, clock_seq: u16, node: [u8; 6]) -> Uuid {
+    let mut uuid = Uuid::nil();
+
+    // time_high and time_mid: the 48 most significant bits of the timestamp
+    uuid.0[0..6].copy_from_slice(&(timestamp >> 12).to_be_bytes()[2..8]);
+    // version (4 bits) + time_low (12 bits)
+    uuid.0[6] = 0x60 | ((timestamp >> 8) & 0x0F) as u8;
+    uuid.0[7] = timestamp as u8;
+    // variant (2 bits) + clock_seq (14 bits)
+    uuid.0[8] = 0x80 | ((clock_seq >> 8) & 0x3F) as u8;
+    uuid.0[9] = clock_seq as u8;
+    uuid.0[10..16].copy_from_slice(&node);
+
+    uuid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Version;
+
+    #[test]
+    fn rfc_test_vector() {
+        // RFC 9562 appendix A.5
+        let uuid = v6_from_parts(0x1EC9414C232AB00, 0x33C8, [0x9f, 0x6b, 0xde, 0xce, 0xd8, 0x46]);
+        assert_eq!(uuid.to_string(), "1ec9414c-232a-6b00-b3c8-9f6bdeced846");
+        assert_eq!(uuid.version(), Version::V6);
+    }
+
+    #[test]
+    fn new_v6_has_correct_version_and_variant() {
+        let uuid = Uuid::new_v6([1, 2, 3, 4, 5, 6]);
+        assert_eq!(uuid.version(), Version::V6);
+        assert_eq!(uuid.0[8] & 0xc0, 0x80);
+        assert_eq!(uuid.0[10..16], [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn new_v6_is_sorted() {
+        let mut previous = Uuid::new_v6([0; 6]);
+        for _ in 0..1000 {
+            let uuid = Uuid::new_v6([0; 6]);
+            assert!(uuid.as_u128() > previous.as_u128(), "{uuid} should sort after {previous}");
+            previous = uuid;
+        }
+    }
+
+    #[test]
+    fn new_v6_has_recent_timestamp() {
+        let uuid = Uuid::new_v6([0; 6]);
+        let bytes = uuid.as_bytes();
+        let timestamp = (u64::from_be_bytes([0, 0, bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5]]) << 12)
+            | ((bytes[6] as u64 & 0x0F) << 8)
+            | bytes[7] as u64;
+
+        // 2020-01-01 00:00:00 UTC
+        assert!(timestamp > GREGORIAN_OFFSET + 1_577_836_800 * 10_000_000);
+        assert!(timestamp <= v6_now());
+    }
+}